@@ -2,18 +2,20 @@ use crate::error::ContractError;
 use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg};
 use crate::prepare;
 use crate::state::{
-    Config, DailyRunState, Entry, LysisEntity, LysisInfo, MetadosisInfo, TouchEntity, TouchInfo,
-    CONFIG, CREATOR, DAILY_RUN_STATE, ENTRY_STATE, METADOSIS_INFO, WINNERS,
+    Config, DailyRunState, Entry, LysisEntity, LysisInfo, MetadosisInfo, RunProgress, TouchEntity,
+    TouchInfo, CONFIG, CREATOR, DAILY_RUN_STATE, ENTRY_STATE, METADOSIS_INFO, RUN_PROGRESS,
+    UNFINISHED_CONCLUSIONS, WINNERS,
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    from_json, to_json_binary, Decimal, DepsMut, Env, Event, HexBinary, MessageInfo, Reply,
-    Response, SubMsg, Timestamp, Uint128, WasmMsg,
+    from_json, to_json_binary, Addr, Decimal, Deps, DepsMut, Env, Event, HexBinary, MessageInfo,
+    Reply, Response, StdResult, SubMsg, Timestamp, Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
 use cw_utils::ParseReplyError::SubMsgFailure;
 use cw_utils::{parse_execute_response_data, MsgExecuteContractResponse};
+use outbe_nft::msg::MintBatchItem;
 use outbe_utils::consts::to_decimals_amount;
 use outbe_utils::date::{subtract_days, DateError, WorldwideDay};
 use outbe_utils::{date, gen_compound_hash};
@@ -50,6 +52,7 @@ pub fn instantiate(
             token_allocator: msg.token_allocator,
             price_oracle: msg.price_oracle,
             random_oracle: msg.random_oracle,
+            tribute_factory: msg.tribute_factory,
             lysis_limit_percent: msg.lysis_limit_percent,
         },
     )?;
@@ -64,7 +67,15 @@ pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, Co
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     match msg {
-        MigrateMsg::Migrate {} => Ok(Response::new()),
+        MigrateMsg::Migrate { tribute_factory } => {
+            if let Some(tribute_factory) = tribute_factory {
+                CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+                    config.tribute_factory = Some(tribute_factory);
+                    Ok(config)
+                })?;
+            }
+            Ok(Response::new())
+        }
     }
 }
 
@@ -77,20 +88,29 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Prepare { run_date } => execute_prepare(deps, env, info, run_date),
-        ExecuteMsg::Execute { run_date } => execute_run(deps, env, info, run_date),
+        ExecuteMsg::Execute { run_date, limit } => execute_run(deps, env, info, run_date, limit),
+        ExecuteMsg::FinishConclusion { date, limit } => {
+            execute_finish_conclusion(deps, date, limit)
+        }
         #[cfg(feature = "demo")]
         ExecuteMsg::BurnAll {} => execute_burn_all(deps, &env, &info),
     }
 }
 
+/// Tributes processed by a single `Execute`, the maximum the tribute contract returns
+const TRIBUTES_PAGE_SIZE: u32 = 1000;
+
 /// A unique ID for tokens allocation callback
 const ALLOCATE_NATIVE_TOKENS_REPLY_ID: u64 = 1;
+/// A unique ID for the callback of concluding the tributes of a day
+const CONCLUDE_DAY_REPLY_ID: u64 = 2;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
     // Match on the ID of the reply to handle the correct one
     match msg.id {
         ALLOCATE_NATIVE_TOKENS_REPLY_ID => handle_token_allocation_reply(deps, msg),
+        CONCLUDE_DAY_REPLY_ID => handle_conclude_day_reply(deps, msg),
         _ => Err(ContractError::UnrecognizedReplyId { id: msg.id }),
     }
 }
@@ -103,12 +123,31 @@ fn execute_prepare(
 ) -> Result<Response, ContractError> {
     // todo verify ownership to run metadosis
 
-    let execution_date = get_execution_date(run_date, &env.block.time)?;
-
     let config = CONFIG.load(deps.storage)?;
+    let execution_date = get_execution_date(deps.as_ref(), &config, run_date, &env.block.time)?;
+
     let token_allocator_address = config
         .token_allocator
         .ok_or(ContractError::NotInitialized {})?;
+    let tribute_address = config.tribute.ok_or(ContractError::NotInitialized {})?;
+
+    // fix the set of tributes the run works on
+    let seal: Option<tribute::merkle::DaySeal> = deps.querier.query_wasm_smart(
+        &tribute_address,
+        &tribute::query::QueryMsg::DaySeal {
+            date: execution_date,
+        },
+    )?;
+    let mut messages = vec![];
+    if seal.is_none() {
+        messages.push(WasmMsg::Execute {
+            contract_addr: tribute_address.to_string(),
+            msg: to_json_binary(&tribute::msg::ExecuteMsg::SealDay {
+                date: execution_date,
+            })?,
+            funds: vec![],
+        });
+    }
 
     let wasm_msg = WasmMsg::Execute {
         contract_addr: token_allocator_address.to_string(),
@@ -119,6 +158,7 @@ fn execute_prepare(
     };
 
     Ok(Response::new()
+        .add_messages(messages)
         .add_submessage(SubMsg::reply_on_success(
             wasm_msg,
             ALLOCATE_NATIVE_TOKENS_REPLY_ID,
@@ -164,49 +204,144 @@ fn handle_token_allocation_reply(deps: DepsMut, msg: Reply) -> Result<Response,
 }
 
 fn execute_run(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     _info: MessageInfo,
     run_date: Option<WorldwideDay>,
+    limit: Option<u32>,
 ) -> Result<Response, ContractError> {
     // todo verify ownership to run metadosis
 
-    let execution_date = get_execution_date(run_date, &env.block.time)?;
     let config = CONFIG.load(deps.storage)?;
+    let execution_date = get_execution_date(deps.as_ref(), &config, run_date, &env.block.time)?;
+    let tribute_address = config.tribute.ok_or(ContractError::NotInitialized {})?;
 
     let run_today = DAILY_RUN_STATE.may_load(deps.storage, execution_date)?;
     let mut run_today = run_today.unwrap_or(DailyRunState { number_of_runs: 0 });
+    // a run is executed once, over as many calls as it has pages of tributes
+    let progress = RUN_PROGRESS.may_load(deps.storage, execution_date)?;
+    if run_today.number_of_runs > 0 && progress.is_none() {
+        return Err(ContractError::BadRunConfiguration {});
+    }
+    let mut progress = progress.unwrap_or_default();
     run_today.number_of_runs += 1;
 
     let info = METADOSIS_INFO
         .load(deps.storage, execution_date)
         .map_err(|_| ContractError::NotPrepared {})?;
 
-    let result: Result<Response, ContractError> = match info {
-        MetadosisInfo::Lysis { lysis_info } => match run_today.number_of_runs {
-            1 => do_execute_lysis(deps, env.block.time, execution_date, lysis_info, run_today),
-            _ => return Err(ContractError::BadRunConfiguration {}),
-        },
-        MetadosisInfo::Touch { touch_info } => {
-            if run_today.number_of_runs > 1 {
-                return Err(ContractError::BadRunConfiguration {});
-            }
-            do_execute_touch(deps, env.block.time, execution_date, touch_info, run_today)
-        }
+    let limit = limit
+        .unwrap_or(TRIBUTES_PAGE_SIZE)
+        .clamp(1, TRIBUTES_PAGE_SIZE);
+    let page = query_day_tributes(
+        &deps,
+        &tribute_address,
+        execution_date,
+        progress.start_after.clone(),
+        limit,
+    )?;
+    let page_count = page.tributes.len() as u64;
+
+    let response = match info {
+        MetadosisInfo::Lysis { lysis_info } => do_execute_lysis(
+            deps.branch(),
+            env.block.time,
+            execution_date,
+            lysis_info,
+            &run_today,
+            page.tributes,
+        )?,
+        MetadosisInfo::Touch { touch_info } => do_execute_touch(
+            deps.branch(),
+            env.block.time,
+            execution_date,
+            touch_info,
+            &run_today,
+            &mut progress,
+            page.tributes,
+        )?,
     };
+    DAILY_RUN_STATE.save(deps.storage, execution_date, &run_today)?;
+    progress.processed += page_count;
+
+    let finished = page.next_start_after.is_none();
+    let response = response.add_event(
+        Event::new("metadosis::run_progress")
+            .add_attribute("date", execution_date.to_string())
+            .add_attribute("processed", progress.processed.to_string())
+            .add_attribute("finished", finished.to_string()),
+    );
+    if let Some(next_start_after) = page.next_start_after {
+        progress.start_after = Some(next_start_after);
+        RUN_PROGRESS.save(deps.storage, execution_date, &progress)?;
+        return Ok(response);
+    }
+
+    // tributes are concluded once all of them had the chance to be recognized
+    RUN_PROGRESS.remove(deps.storage, execution_date);
+    UNFINISHED_CONCLUSIONS.save(deps.storage, execution_date, &())?;
+    let submsg = conclude_day_msg(&tribute_address, execution_date, None)?;
 
-    let mut submessages: Vec<SubMsg> = vec![];
+    Ok(response.add_submessage(submsg))
+}
+
+fn execute_finish_conclusion(
+    deps: DepsMut,
+    date: WorldwideDay,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    if !UNFINISHED_CONCLUSIONS.has(deps.storage, date) {
+        return Err(ContractError::NothingToConclude { day: date });
+    }
+    let config = CONFIG.load(deps.storage)?;
     let tribute_address = config.tribute.ok_or(ContractError::NotInitialized {})?;
-    let submsg = SubMsg::new(WasmMsg::Execute {
-        contract_addr: tribute_address.to_string(),
-        msg: to_json_binary(&tribute::msg::ExecuteMsg::BurnForDay {
-            date: execution_date,
-        })?,
-        funds: vec![],
-    });
-    submessages.push(submsg);
 
-    Ok(result?.add_submessages(submessages))
+    Ok(Response::new()
+        .add_submessage(conclude_day_msg(&tribute_address, date, limit)?)
+        .add_attribute("action", "metadosis::finish_conclusion")
+        .add_event(
+            Event::new("metadosis::finish_conclusion").add_attribute("date", date.to_string()),
+        ))
+}
+
+/// Marks tributes of the day that won nothing as unrecognized,
+/// the reply tells whether another `FinishConclusion` is required
+fn conclude_day_msg(
+    tribute_address: &Addr,
+    date: WorldwideDay,
+    limit: Option<u32>,
+) -> Result<SubMsg, ContractError> {
+    Ok(SubMsg::reply_on_success(
+        WasmMsg::Execute {
+            contract_addr: tribute_address.to_string(),
+            msg: to_json_binary(&tribute::msg::ExecuteMsg::ConcludeDay { date, limit })?,
+            funds: vec![],
+        },
+        CONCLUDE_DAY_REPLY_ID,
+    ))
+}
+
+fn handle_conclude_day_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    let subcall_result = msg.result.into_result().map_err(SubMsgFailure)?;
+
+    #[allow(deprecated)] // NB: older version for SEI
+    let data = subcall_result.data.ok_or(ContractError::NoDataInReply {})?;
+    let result: MsgExecuteContractResponse = parse_execute_response_data(data.as_slice())?;
+    let result = result.data.ok_or(ContractError::NoDataInReply {})?;
+    let result: tribute::contract::TransitionResult = from_json(result.as_slice())?;
+
+    if result.finished {
+        UNFINISHED_CONCLUSIONS.remove(deps.storage, result.date);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "metadosis::handle_conclude_day_reply")
+        .add_event(
+            Event::new("metadosis::handle_conclude_day_reply")
+                .add_attribute("date", result.date.to_string())
+                .add_attribute("unrecognized", result.updated.to_string())
+                .add_attribute("finished", result.finished.to_string()),
+        ))
 }
 
 fn do_execute_lysis(
@@ -214,7 +349,8 @@ fn do_execute_lysis(
     block_time: Timestamp,
     execution_date: WorldwideDay,
     lysis_info: LysisInfo,
-    run_today: DailyRunState,
+    run_today: &DailyRunState,
+    tributes: Vec<FullTributeData>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let tribute_address = config.tribute.ok_or(ContractError::NotInitialized {})?;
@@ -227,59 +363,9 @@ fn do_execute_lysis(
         &price_oracle::query::QueryMsg::GetPrice {},
     )?;
 
-    let tributes: tribute::query::FullTributesResponse = deps.querier.query_wasm_smart(
-        &tribute_address,
-        &tribute::query::QueryMsg::DailyTributes {
-            date: Some(execution_date),
-            query_order: None,
-            limit: None,
-            start_after: None,
-        },
-    )?;
-
-    let all_tributes_count = tributes.tributes.len();
-    println!(
-        "All Fetched Tributes in current run {}: count = {}",
-        run_today.number_of_runs, all_tributes_count
-    );
-
-    let entity_id = gen_compound_hash(
-        Some("lysis"),
-        vec![
-            &execution_date.to_le_bytes(),
-            &block_time.nanos().to_le_bytes(),
-        ],
-    );
-
-    // fast exit when no tributes
-    if all_tributes_count == 0 {
-        DAILY_RUN_STATE.save(deps.storage, execution_date, &run_today)?;
-
-        ENTRY_STATE.save(
-            deps.storage,
-            execution_date,
-            &Entry::Lysis(LysisEntity {
-                id: entity_id.to_hex(),
-                index: run_today.number_of_runs,
-                limit_minor: lysis_info.total_lysis_limit_minor,
-                deficit_minor: lysis_info.total_lysis_deficit_minor,
-                total_tribute_interest_minor: lysis_info.total_tribute_interest_minor,
-                worldwide_day: execution_date,
-                total_gratis_limit_minor: lysis_info.total_gratis_limit_minor,
-                assigned_tributes: 0,
-                timestamp: block_time,
-                assigned_tributes_sum_minor: Uint128::zero(),
-            }),
-        )?;
-
-        return Ok(Response::new()
-            .add_attribute("action", "metadosis::lysis")
-            .add_event(Event::new("metadosis::lysis").add_attribute("tributes_count", "0")));
-    }
-
     let mut allocated_tributes_sum = Uint128::zero();
     let mut allocated_tributes: Vec<(FullTributeData, Uint128)> = vec![];
-    for tribute in tributes.tributes {
+    for tribute in tributes {
         let symbolic_load = (to_decimals_amount(tribute.data.nominal_amount_minor)
             * lysis_info.distribution_percent)
             .atomics();
@@ -293,51 +379,65 @@ fn do_execute_lysis(
         run_today.number_of_runs, allocated_tributes_count, allocated_tributes_sum
     );
 
-    let mut messages: Vec<SubMsg> = vec![];
+    let mut nods: Vec<MintBatchItem<nod::msg::SubmitExtension>> = vec![];
+    let mut recognitions: Vec<tribute::msg::TributeRecognition> = vec![];
     for (tribute, symbolic_load) in allocated_tributes {
         let nod_token_id = generate_nod_id(&tribute.token_id, &tribute.owner);
+        recognitions.push(tribute::msg::TributeRecognition {
+            token_id: tribute.token_id.clone(),
+            nod_id: nod_token_id.to_hex(),
+        });
 
         // todo impl floor price with S-Curve
         let floor_rate = Decimal::percent(8);
         let floor_price = exchange_rate.price * (Decimal::one() + floor_rate);
 
         let mod_issuance_price = exchange_rate.price.max(tribute.data.nominal_price);
-        let nod_mint = WasmMsg::Execute {
-            contract_addr: nod_address.to_string(),
-            msg: to_json_binary(&nod::msg::ExecuteMsg::Submit {
-                token_id: nod_token_id.to_hex(),
-                owner: tribute.owner.to_string(),
-                extension: Box::new(nod::msg::SubmitExtension {
-                    entity: nod::msg::NodEntity {
-                        nod_id: nod_token_id.to_hex(),
-                        worldwide_day: execution_date,
-                        settlement_currency: tribute.data.settlement_currency.clone(),
-                        symbolic_rate: config.lysis_limit_percent,
-                        floor_rate,
-                        nominal_price: tribute.data.nominal_price,
-                        issuance_price: mod_issuance_price,
-                        gratis_load_minor: symbolic_load,
-                        floor_price,
-                        state: nod::types::State::Issued,
-                        owner: tribute.owner.to_string(),
-                        qualified_at: None,
-                        is_touch: false,
-                    },
-                    created_at: None,
-                }),
-            })?,
-            funds: vec![],
-        };
-        messages.push(SubMsg::new(nod_mint));
+        nods.push(MintBatchItem {
+            token_id: nod_token_id.to_hex(),
+            owner: tribute.owner.to_string(),
+            token_uri: None,
+            extension: nod::msg::SubmitExtension {
+                entity: nod::msg::NodEntity {
+                    nod_id: nod_token_id.to_hex(),
+                    worldwide_day: execution_date,
+                    settlement_currency: tribute.data.settlement_currency.clone(),
+                    symbolic_rate: config.lysis_limit_percent,
+                    floor_rate,
+                    nominal_price: tribute.data.nominal_price,
+                    issuance_price: mod_issuance_price,
+                    gratis_load_minor: symbolic_load,
+                    floor_price,
+                    state: nod::types::State::Issued,
+                    owner: tribute.owner.to_string(),
+                    qualified_at: None,
+                    is_touch: false,
+                },
+                created_at: None,
+            },
+            reason: Some(format!("lysis of tribute {}", tribute.token_id)),
+        });
     }
-
-    DAILY_RUN_STATE.save(deps.storage, execution_date, &run_today)?;
-
-    ENTRY_STATE.save(
-        deps.storage,
-        execution_date,
-        &Entry::Lysis(LysisEntity {
-            id: entity_id.to_hex(),
+    let mut messages = nod_mint_batch(&nod_address, nods)?;
+    messages.extend(recognize_msg(&tribute_address, recognitions)?);
+
+    let entity = match ENTRY_STATE.may_load(deps.storage, execution_date)? {
+        // a later page of the run adds its tributes
+        Some(Entry::Lysis(mut entity)) if run_today.number_of_runs > 1 => {
+            entity.assigned_tributes += allocated_tributes_count;
+            entity.assigned_tributes_sum_minor += allocated_tributes_sum;
+            entity.timestamp = block_time;
+            entity
+        }
+        _ => LysisEntity {
+            id: gen_compound_hash(
+                Some("lysis"),
+                vec![
+                    &execution_date.to_le_bytes(),
+                    &block_time.nanos().to_le_bytes(),
+                ],
+            )
+            .to_hex(),
             index: run_today.number_of_runs,
             limit_minor: lysis_info.total_lysis_limit_minor,
             deficit_minor: lysis_info.total_lysis_deficit_minor,
@@ -347,8 +447,10 @@ fn do_execute_lysis(
             assigned_tributes: allocated_tributes_count,
             timestamp: block_time,
             assigned_tributes_sum_minor: allocated_tributes_sum,
-        }),
-    )?;
+            tributes_root: Some(sealed_day(&deps, &tribute_address, execution_date)?.root),
+        },
+    };
+    ENTRY_STATE.save(deps.storage, execution_date, &Entry::Lysis(entity))?;
 
     Ok(Response::new()
         .add_attribute("action", "metadosis::lysis")
@@ -365,7 +467,9 @@ fn do_execute_touch(
     block_time: Timestamp,
     execution_date: WorldwideDay,
     touch_info: TouchInfo,
-    run_today: DailyRunState,
+    run_today: &DailyRunState,
+    progress: &mut RunProgress,
+    tributes: Vec<FullTributeData>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let tribute_address = config.tribute.ok_or(ContractError::NotInitialized {})?;
@@ -381,129 +485,110 @@ fn do_execute_touch(
         &price_oracle::query::QueryMsg::GetPrice {},
     )?;
 
-    // TODO add pagination if required and split into multiple runs
-    let tributes: tribute::query::FullTributesResponse = deps.querier.query_wasm_smart(
-        &tribute_address,
-        &tribute::query::QueryMsg::DailyTributes {
-            date: Some(execution_date),
-            query_order: None,
-            limit: None,
-            start_after: None,
-        },
-    )?;
+    let (expected_winners_count, win_amount) =
+        calc_touch_win_amount(touch_info.touch_limit_minor, touch_info.gold_ignot_price);
 
-    let mut allocated_tributes: Vec<FullTributeData> = vec![];
-    for tribute in tributes.tributes {
+    let mut tributes_root = None;
+    if run_today.number_of_runs == 1 {
+        let seal = sealed_day(&deps, &tribute_address, execution_date)?;
+        tributes_root = Some(seal.root);
+
+        // the winners are drawn among all sealed tributes of the day on the first page,
+        // every page then picks the tributes at the drawn leaf positions
+        if seal.leaves_count > 0 {
+            let seed: random_oracle::msg::SeedResponse = deps.querier.query_wasm_smart(
+                &random_oracle_address,
+                &random_oracle::msg::QueryMsg::RandomSeed {},
+            )?;
+            let mut rnd = ChaCha8Rng::seed_from_u64(seed.seed);
+
+            let mut positions: Vec<u64> = (0..seal.leaves_count).collect();
+            positions.shuffle(&mut rnd);
+            positions.truncate(expected_winners_count);
+            positions.sort_unstable();
+            progress.winner_positions = positions;
+        }
+    }
+
+    // positions are those of the sealed leaves, whatever changed in the day since the seal
+    let token_ids: Vec<String> = tributes.iter().map(|t| t.token_id.clone()).collect();
+    let positions = sealed_leaf_indexes(&deps, &tribute_address, execution_date, token_ids)?;
+
+    let mut assigned_tributes_count = 0;
+    let mut winners: Vec<FullTributeData> = vec![];
+    for (position, tribute) in positions.into_iter().zip(tributes) {
         if WINNERS.has(deps.storage, tribute.token_id.clone()) {
             continue;
         }
-        allocated_tributes.push(tribute);
+        assigned_tributes_count += 1;
+        if position
+            .is_some_and(|position| progress.winner_positions.binary_search(&position).is_ok())
+        {
+            WINNERS.save(deps.storage, tribute.token_id.clone(), &())?;
+            winners.push(tribute);
+        }
     }
-
-    let assigned_tributes_count = allocated_tributes.len();
     println!(
         "Tributes in current run {}: count = {}",
         run_today.number_of_runs, assigned_tributes_count
     );
-
-    let entity_id = gen_compound_hash(
-        Some("touch"),
-        vec![
-            &execution_date.to_le_bytes(),
-            &block_time.nanos().to_le_bytes(),
-        ],
-    );
-
-    // fast exit when no tributes
-    if allocated_tributes.is_empty() {
-        ENTRY_STATE.save(
-            deps.storage,
-            execution_date,
-            &Entry::Touch(TouchEntity {
-                id: entity_id.to_hex(),
-                worldwide_day: execution_date,
-                total_gratis_limit_minor: touch_info.total_gratis_limit_minor,
-                gold_ignot_price: touch_info.gold_ignot_price,
-                touch_limit_minor: touch_info.touch_limit_minor,
-                assigned_tributes: assigned_tributes_count,
-                recognised_tributes: vec![],
-                timestamp: block_time,
-            }),
-        )?;
-
-        DAILY_RUN_STATE.save(deps.storage, execution_date, &run_today)?;
-
-        return Ok(Response::new()
-            .add_attribute("action", "metadosis::touch")
-            .add_event(Event::new("metadosis::touch").add_attribute("tributes_count", "0")));
-    }
-
-    // update state
-    DAILY_RUN_STATE.save(deps.storage, execution_date, &run_today)?;
-
-    // shuffle here like the following
-    let seed: random_oracle::msg::SeedResponse = deps.querier.query_wasm_smart(
-        &random_oracle_address,
-        &random_oracle::msg::QueryMsg::RandomSeed {},
-    )?;
-
-    let mut rnd = ChaCha8Rng::seed_from_u64(seed.seed);
-
-    // Shuffle and pick winners
-    allocated_tributes.shuffle(&mut rnd);
-
-    let (expected_winners_count, win_amount) =
-        calc_touch_win_amount(touch_info.touch_limit_minor, touch_info.gold_ignot_price);
-
-    let mut winners: Vec<FullTributeData> = vec![];
-    for tribute in allocated_tributes {
-        WINNERS.save(deps.storage, tribute.token_id.clone(), &())?;
-        winners.push(tribute);
-        if expected_winners_count == winners.len() {
-            break;
-        }
-    }
     let winners_ids: Vec<String> = winners.iter().map(|t| t.token_id.clone()).collect();
 
-    let mut messages: Vec<SubMsg> = vec![];
+    let mut nods: Vec<MintBatchItem<nod::msg::SubmitExtension>> = vec![];
+    let mut recognitions: Vec<tribute::msg::TributeRecognition> = vec![];
     for tribute in winners {
         let token_id = generate_nod_id(&tribute.token_id, &tribute.owner);
+        recognitions.push(tribute::msg::TributeRecognition {
+            token_id: tribute.token_id.clone(),
+            nod_id: token_id.to_hex(),
+        });
 
         let mod_issuance_price = exchange_rate.price.max(tribute.data.nominal_price);
-        let nod_mint = WasmMsg::Execute {
-            contract_addr: nod_address.to_string(),
-            msg: to_json_binary(&nod::msg::ExecuteMsg::Submit {
-                token_id: token_id.to_hex(),
-                owner: tribute.owner.to_string(),
-                extension: Box::new(nod::msg::SubmitExtension {
-                    entity: nod::msg::NodEntity {
-                        nod_id: token_id.to_hex(),
-                        worldwide_day: execution_date,
-                        settlement_currency: tribute.data.settlement_currency.clone(),
-                        symbolic_rate: config.lysis_limit_percent,
-                        floor_rate: Decimal::zero(),
-                        nominal_price: tribute.data.nominal_price,
-                        issuance_price: mod_issuance_price,
-                        gratis_load_minor: win_amount,
-                        floor_price: exchange_rate.price,
-                        state: nod::types::State::Qualified,
-                        owner: tribute.owner.to_string(),
-                        qualified_at: Some(block_time),
-                        is_touch: true,
-                    },
-                    created_at: None,
-                }),
-            })?,
-            funds: vec![],
-        };
-        messages.push(SubMsg::new(nod_mint));
+        nods.push(MintBatchItem {
+            token_id: token_id.to_hex(),
+            owner: tribute.owner.to_string(),
+            token_uri: None,
+            extension: nod::msg::SubmitExtension {
+                entity: nod::msg::NodEntity {
+                    nod_id: token_id.to_hex(),
+                    worldwide_day: execution_date,
+                    settlement_currency: tribute.data.settlement_currency.clone(),
+                    symbolic_rate: config.lysis_limit_percent,
+                    floor_rate: Decimal::zero(),
+                    nominal_price: tribute.data.nominal_price,
+                    issuance_price: mod_issuance_price,
+                    gratis_load_minor: win_amount,
+                    floor_price: exchange_rate.price,
+                    state: nod::types::State::Qualified,
+                    owner: tribute.owner.to_string(),
+                    qualified_at: Some(block_time),
+                    is_touch: true,
+                },
+                created_at: None,
+            },
+            reason: Some(format!("touch of tribute {}", tribute.token_id)),
+        });
     }
-
-    ENTRY_STATE.save(
-        deps.storage,
-        execution_date,
-        &Entry::Touch(TouchEntity {
-            id: entity_id.to_hex(),
+    let mut messages = nod_mint_batch(&nod_address, nods)?;
+    messages.extend(recognize_msg(&tribute_address, recognitions)?);
+
+    let entity = match ENTRY_STATE.may_load(deps.storage, execution_date)? {
+        // a later page of the run adds its tributes
+        Some(Entry::Touch(mut entity)) if run_today.number_of_runs > 1 => {
+            entity.assigned_tributes += assigned_tributes_count;
+            entity.recognised_tributes.extend(winners_ids);
+            entity.timestamp = block_time;
+            entity
+        }
+        _ => TouchEntity {
+            id: gen_compound_hash(
+                Some("touch"),
+                vec![
+                    &execution_date.to_le_bytes(),
+                    &block_time.nanos().to_le_bytes(),
+                ],
+            )
+            .to_hex(),
             worldwide_day: execution_date,
             total_gratis_limit_minor: touch_info.total_gratis_limit_minor,
             gold_ignot_price: touch_info.gold_ignot_price,
@@ -511,18 +596,98 @@ fn do_execute_touch(
             assigned_tributes: assigned_tributes_count,
             recognised_tributes: winners_ids,
             timestamp: block_time,
-        }),
-    )?;
+            tributes_root,
+        },
+    };
+    ENTRY_STATE.save(deps.storage, execution_date, &Entry::Touch(entity))?;
 
     Ok(Response::new()
         .add_attribute("action", "metadosis::touch")
         .add_event(
             Event::new("metadosis::touch")
-                .add_attribute("run", run_today.number_of_runs.to_string()),
+                .add_attribute("run", run_today.number_of_runs.to_string())
+                .add_attribute("tributes_count", assigned_tributes_count.to_string()),
         )
         .add_submessages(messages))
 }
 
+/// Loads a page of the tributes of the day
+fn query_day_tributes(
+    deps: &DepsMut,
+    tribute_address: &Addr,
+    date: WorldwideDay,
+    start_after: Option<String>,
+    limit: u32,
+) -> Result<tribute::query::TributesPageResponse, ContractError> {
+    Ok(deps.querier.query_wasm_smart(
+        tribute_address,
+        &tribute::query::QueryMsg::TributesByDay {
+            date,
+            start_after,
+            limit: Some(limit),
+        },
+    )?)
+}
+
+/// Seal of the tributes of the day, made by `Prepare`
+fn sealed_day(
+    deps: &DepsMut,
+    tribute_address: &Addr,
+    date: WorldwideDay,
+) -> Result<tribute::merkle::DaySeal, ContractError> {
+    let seal: Option<tribute::merkle::DaySeal> = deps
+        .querier
+        .query_wasm_smart(tribute_address, &tribute::query::QueryMsg::DaySeal { date })?;
+    seal.ok_or(ContractError::DayNotSealed { day: date })
+}
+
+/// Leaf positions of the tributes in the seal of the day, `None` for tributes not sealed
+fn sealed_leaf_indexes(
+    deps: &DepsMut,
+    tribute_address: &Addr,
+    date: WorldwideDay,
+    token_ids: Vec<String>,
+) -> Result<Vec<Option<u64>>, ContractError> {
+    if token_ids.is_empty() {
+        return Ok(vec![]);
+    }
+    let response: tribute::merkle::SealedLeafIndexesResponse = deps.querier.query_wasm_smart(
+        tribute_address,
+        &tribute::query::QueryMsg::SealedLeafIndexes { date, token_ids },
+    )?;
+    Ok(response.indexes)
+}
+
+/// Mints all Nods of a run with a single batch message
+fn nod_mint_batch(
+    nod_address: &Addr,
+    nods: Vec<MintBatchItem<nod::msg::SubmitExtension>>,
+) -> Result<Vec<SubMsg>, ContractError> {
+    if nods.is_empty() {
+        return Ok(vec![]);
+    }
+    Ok(vec![SubMsg::new(WasmMsg::Execute {
+        contract_addr: nod_address.to_string(),
+        msg: to_json_binary(&nod::msg::ExecuteMsg::MintBatch { tokens: nods })?,
+        funds: vec![],
+    })])
+}
+
+/// Links the tributes to the Nods minted for them
+fn recognize_msg(
+    tribute_address: &Addr,
+    tributes: Vec<tribute::msg::TributeRecognition>,
+) -> Result<Vec<SubMsg>, ContractError> {
+    if tributes.is_empty() {
+        return Ok(vec![]);
+    }
+    Ok(vec![SubMsg::new(WasmMsg::Execute {
+        contract_addr: tribute_address.to_string(),
+        msg: to_json_binary(&tribute::msg::ExecuteMsg::Recognize { tributes })?,
+        funds: vec![],
+    })])
+}
+
 fn generate_nod_id(token_id: &String, owner: &String) -> HexBinary {
     gen_compound_hash(
         Some("metadosis:nod_id"),
@@ -545,18 +710,38 @@ fn calc_touch_win_amount(touch_limit: Uint128, ignot_price: Decimal) -> (usize,
 }
 
 fn get_execution_date(
+    deps: Deps,
+    config: &Config,
     run_date: Option<WorldwideDay>,
     block_time: &Timestamp,
 ) -> Result<WorldwideDay, ContractError> {
-    let execution_date = run_date.unwrap_or(calc_run_date(block_time)?);
+    let execution_date = match run_date {
+        Some(run_date) => run_date,
+        None => {
+            let tribute_factory = config
+                .tribute_factory
+                .as_ref()
+                .ok_or(ContractError::NotInitialized {})?;
+            let offer_window: tribute_factory::query::OfferWindowResponse =
+                deps.querier.query_wasm_smart(
+                    tribute_factory,
+                    &tribute_factory::query::QueryMsg::OfferWindow {
+                        day: date::normalize_to_date(block_time),
+                    },
+                )?;
+            calc_run_date(block_time, offer_window.offer_window_days)?
+        }
+    };
     date::is_valid(execution_date)?;
     println!("execution date = {}", execution_date);
     Ok(execution_date)
 }
 
-fn calc_run_date(timestamp: &Timestamp) -> Result<WorldwideDay, DateError> {
+/// Runs the day whose offer window is over. Tributes are offered until `offer_window_days`
+/// days after their worldwide day, that last day included, so the day is run the day after.
+fn calc_run_date(timestamp: &Timestamp, offer_window_days: u32) -> Result<WorldwideDay, DateError> {
     let normalized = date::normalize_to_date(timestamp);
-    subtract_days(normalized, 3)
+    subtract_days(normalized, offer_window_days.saturating_add(1))
 }
 
 #[cfg(feature = "demo")]
@@ -569,6 +754,7 @@ fn execute_burn_all(
     DAILY_RUN_STATE.clear(deps.storage);
     ENTRY_STATE.clear(deps.storage);
     WINNERS.clear(deps.storage);
+    UNFINISHED_CONCLUSIONS.clear(deps.storage);
 
     Ok(Response::new()
         .add_attribute("action", "metadosis::burn_all")
@@ -675,14 +861,22 @@ mod tests {
     #[test]
     fn test_calc_run_date() {
         let current_time = Timestamp::from_seconds(1632960000); // 2021-09-30 00:00:00 UTC
-        let result = calc_run_date(&current_time).unwrap();
-        assert_eq!(result, 20210927);
+        let result = calc_run_date(&current_time, 3).unwrap();
+        assert_eq!(result, 20210926);
     }
 
     #[test]
     fn test_calc_run_date2() {
         let current_time = Timestamp::from_seconds(1758889055); // 2025-09-26 12:17:35 UTC
-        let result = calc_run_date(&current_time).unwrap();
-        assert_eq!(result, 20250923);
+        let result = calc_run_date(&current_time, 3).unwrap();
+        assert_eq!(result, 20250922);
+    }
+
+    #[test]
+    fn test_calc_run_date_with_offer_window() {
+        let current_time = Timestamp::from_seconds(1758889055); // 2025-09-26 12:17:35 UTC
+        assert_eq!(calc_run_date(&current_time, 0).unwrap(), 20250925);
+        assert_eq!(calc_run_date(&current_time, 1).unwrap(), 20250924);
+        assert_eq!(calc_run_date(&current_time, 7).unwrap(), 20250918);
     }
 }
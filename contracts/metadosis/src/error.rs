@@ -15,8 +15,12 @@ pub enum ContractError {
     BadRunConfiguration {},
     #[error("Data already prepared {day}")]
     AlreadyPrepared { day: WorldwideDay },
+    #[error("Tributes of {day} are not sealed")]
+    DayNotSealed { day: WorldwideDay },
     #[error(transparent)]
     DateError(#[from] DateError),
+    #[error("No tributes left to conclude for {day}")]
+    NothingToConclude { day: WorldwideDay },
     #[error("Bad Reply ID {id}")]
     UnrecognizedReplyId { id: u64 },
     #[error("NoDataInReply")]
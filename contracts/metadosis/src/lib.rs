@@ -3,4 +3,4 @@ pub mod error;
 pub mod msg;
 mod prepare;
 pub mod query;
-mod state;
+pub mod state;
@@ -10,13 +10,18 @@ pub struct InstantiateMsg {
     pub token_allocator: Option<Addr>,
     pub price_oracle: Option<Addr>,
     pub random_oracle: Option<Addr>,
+    pub tribute_factory: Option<Addr>,
     /// Lysis limit config where 1 mean 100%
     pub lysis_limit_percent: Decimal,
 }
 
 #[cw_serde]
 pub enum MigrateMsg {
-    Migrate {},
+    Migrate {
+        /// Sets the tribute factory if it is not configured yet
+        #[serde(default)]
+        tribute_factory: Option<Addr>,
+    },
 }
 
 #[cw_serde]
@@ -24,8 +29,17 @@ pub enum ExecuteMsg {
     Prepare {
         run_date: Option<WorldwideDay>,
     },
+    /// Processes a page of the tributes of the day, call it again until the run is finished
     Execute {
         run_date: Option<WorldwideDay>,
+        /// Tributes processed by this call, at most 1000
+        limit: Option<u32>,
+    },
+    /// Continues marking tributes of a day as unrecognized when `Execute` could not conclude
+    /// all of them at once
+    FinishConclusion {
+        date: WorldwideDay,
+        limit: Option<u32>,
     },
     #[cfg(feature = "demo")]
     BurnAll {},
@@ -1,10 +1,11 @@
 use crate::error::ContractError;
 use crate::state::{LysisInfo, MetadosisInfo, TouchInfo, CONFIG, METADOSIS_INFO};
-use cosmwasm_std::{Addr, Decimal, DepsMut, QuerierWrapper, Uint128};
+use cosmwasm_std::{Addr, Decimal, DepsMut, QuerierWrapper, StdError, Uint128};
 use outbe_utils::consts::to_decimals_amount;
 use outbe_utils::date::WorldwideDay;
 use outbe_utils::denom::{CommodityType, Currency, Denom};
 use price_oracle::types::DayType;
+use tribute_factory::query::NominalTotalsResponse;
 
 // todo implement fees calculation
 const TOTAL_FEES: Uint128 = Uint128::zero();
@@ -36,8 +37,14 @@ pub fn prepare_executions(
 
     let metadosis_info: MetadosisInfo = match coen_usdc_rate.day_type {
         DayType::Green => {
-            let total_tribute_interest: Uint128 =
+            let mut total_tribute_interest: Uint128 =
                 query_total_tribute_amount(deps.querier, &tribute_address, execution_date)?;
+            // Tributes offered with rounded amounts record a rounded nominal amount,
+            // the factory keeps the exact total
+            if let Some(ref tribute_factory) = config.tribute_factory {
+                let totals = query_nominal_totals(deps.querier, tribute_factory, execution_date)?;
+                total_tribute_interest = exact_total_interest(total_tribute_interest, &totals)?;
+            }
             println!("Total tribute quantity = {}", total_tribute_interest);
 
             let (total_lysis_limit, total_lysis_deficit, distribution_percent) = calc_lysis_limit(
@@ -125,6 +132,31 @@ fn query_total_tribute_amount(
     Ok(response.total_nominal_amount)
 }
 
+fn query_nominal_totals(
+    querier: QuerierWrapper,
+    addr: &Addr,
+    date: WorldwideDay,
+) -> Result<NominalTotalsResponse, ContractError> {
+    Ok(querier.query_wasm_smart(
+        addr,
+        &tribute_factory::query::QueryMsg::NominalTotals {
+            worldwide_day: date,
+        },
+    )?)
+}
+
+/// Replaces the rounded nominal amounts of the offered Tributes by the exact ones. Offered
+/// Tributes can not be burned, so the factory totals cover the same Tributes.
+fn exact_total_interest(
+    total_tribute_interest: Uint128,
+    totals: &NominalTotalsResponse,
+) -> Result<Uint128, ContractError> {
+    Ok(total_tribute_interest
+        .checked_add(totals.exact_minor)
+        .map_err(StdError::from)?
+        .saturating_sub(totals.published_minor))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,4 +212,27 @@ mod tests {
         assert_eq!(total_lysis_deficit, Uint128::zero());
         assert_eq!(distribution_percent, Decimal::percent(10));
     }
+
+    #[test]
+    fn test_exact_total_interest() {
+        let totals = NominalTotalsResponse {
+            worldwide_day: 20250322,
+            exact_minor: Uint128::new(1234),
+            published_minor: Uint128::new(1300),
+        };
+        // tributes minted directly are counted as they are
+        assert_eq!(
+            exact_total_interest(Uint128::new(1500), &totals).unwrap(),
+            Uint128::new(1434)
+        );
+        let totals = NominalTotalsResponse {
+            worldwide_day: 20250322,
+            exact_minor: Uint128::zero(),
+            published_minor: Uint128::zero(),
+        };
+        assert_eq!(
+            exact_total_interest(Uint128::new(1500), &totals).unwrap(),
+            Uint128::new(1500)
+        );
+    }
 }
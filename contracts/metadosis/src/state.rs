@@ -1,5 +1,5 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
+use cosmwasm_std::{Addr, Decimal, HexBinary, Timestamp, Uint128};
 use cw_ownable::{OwnershipStore, OWNERSHIP_KEY};
 use cw_storage_plus::{Item, Map};
 use outbe_utils::date::WorldwideDay;
@@ -11,6 +11,9 @@ pub struct Config {
     pub token_allocator: Option<Addr>,
     pub price_oracle: Option<Addr>,
     pub random_oracle: Option<Addr>,
+    /// Tribute factory, its offer window sets the day metadosis runs
+    #[serde(default)]
+    pub tribute_factory: Option<Addr>,
     /// Percentage of Total Tribute Interest
     pub lysis_limit_percent: Decimal,
 }
@@ -25,6 +28,24 @@ pub const METADOSIS_INFO: Map<WorldwideDay, MetadosisInfo> = Map::new("metadosis
 /// Map to track how many runs were happened for each day
 pub const DAILY_RUN_STATE: Map<WorldwideDay, DailyRunState> = Map::new("daily_runs");
 
+/// Days whose tributes are not completely concluded yet, see `ExecuteMsg::FinishConclusion`
+pub const UNFINISHED_CONCLUSIONS: Map<WorldwideDay, ()> = Map::new("unfinished_conclusions");
+
+/// Progress of a run whose tributes are processed one page per `Execute`
+#[cw_serde]
+#[derive(Default)]
+pub struct RunProgress {
+    /// Cursor of the next page of tributes, see `tribute::query::QueryMsg::TributesByDay`
+    pub start_after: Option<String>,
+    /// Number of tributes processed so far
+    pub processed: u64,
+    /// Leaf positions of the Touch winners in the seal of the day, in ascending order
+    pub winner_positions: Vec<u64>,
+}
+
+/// Runs with tributes left to process, the next `Execute` of the day continues them
+pub const RUN_PROGRESS: Map<WorldwideDay, RunProgress> = Map::new("run_progress");
+
 /// Saves winners to do not peek them in Touch
 pub const WINNERS: Map<String, ()> = Map::new("tribute_winners");
 
@@ -85,6 +106,9 @@ pub struct LysisEntity {
     pub assigned_tributes: usize,
     /// Sum of tributes were assigned for this run
     pub assigned_tributes_sum_minor: Uint128,
+    /// Merkle root of the tributes of the day, see `tribute::msg::ExecuteMsg::SealDay`
+    #[serde(default)]
+    pub tributes_root: Option<HexBinary>,
 }
 
 #[cw_serde]
@@ -105,6 +129,9 @@ pub struct TouchEntity {
     pub recognised_tributes: Vec<String>,
     /// Timestamp of the last tribute was recognized
     pub timestamp: Timestamp,
+    /// Merkle root of the tributes of the day, see `tribute::msg::ExecuteMsg::SealDay`
+    #[serde(default)]
+    pub tributes_root: Option<HexBinary>,
 }
 
 #[cw_serde]
@@ -1,12 +1,19 @@
 use crate::error::ContractError;
 use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, SubmitExtension};
-use crate::types::{NodConfig, NodData, NodNft, State};
+use crate::types::{NodCollection, NodConfig, NodData, NodIndexes, NodNft, State};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{Decimal, DepsMut, Env, Event, MessageInfo, Response};
+use cosmwasm_std::{
+    to_json_binary, Api, Decimal, DepsMut, Env, Event, MessageInfo, Order, Response, StdResult,
+    Uint128,
+};
 use cw_ownable::OwnershipStore;
-use outbe_nft::error::Cw721ContractError;
-use outbe_nft::state::{CollectionInfo, Cw721Config, NftInfo};
+use cw_storage_plus::PrefixBound;
+use outbe_nft::collection::Cw721Collection;
+use outbe_nft::execute::Cw721ExecuteMsg;
+use outbe_nft::migration::{ensure_storage_version, initialize_storage_version, reindex_nft_infos};
+use outbe_nft::msg::MintBatchItem;
+use outbe_nft::state::{HistoryContext, NftInfo, TokenAction, BURNER};
 
 const CONTRACT_NAME: &str = "outbe.net:nod";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -16,49 +23,67 @@ pub const PRICE_UPDATER: OwnershipStore = OwnershipStore::new("price_updater");
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    let cfg = NodConfig {};
-    let collection_info = CollectionInfo {
-        name: msg.name,
-        symbol: msg.symbol,
-        updated_at: env.block.time,
+    let cfg = NodConfig {
+        transfer_policy: msg
+            .collection_info_extension
+            .transfer_policy
+            .clone()
+            .unwrap_or_default(),
+        track_history: msg
+            .collection_info_extension
+            .track_history
+            .unwrap_or_default(),
+        render_token_uri: msg
+            .collection_info_extension
+            .render_token_uri
+            .unwrap_or_default(),
     };
-    let config = Cw721Config::<NodData, NodConfig>::default();
-    config.collection_config.save(deps.storage, &cfg)?;
-    config
-        .collection_info
-        .save(deps.storage, &collection_info)?;
-
-    let minter = msg
-        .minter
-        .clone()
-        .unwrap_or_else(|| info.sender.to_string());
-    outbe_nft::execute::initialize_minter(deps.storage, deps.api, Some(&minter))?;
-
-    let creator = msg
-        .creator
-        .clone()
-        .unwrap_or_else(|| info.sender.to_string());
-    outbe_nft::execute::initialize_creator(deps.storage, deps.api, Some(&creator))?;
+    let roles = NodCollection::instantiate_collection(deps.branch(), &env, &info, &msg, &cfg)?;
+    initialize_storage_version::<NodData>(deps.storage)?;
 
     Ok(Response::new()
         .add_attribute("action", "nod::instantiate")
-        .add_attribute("minter", minter)
-        .add_attribute("creator", creator))
+        .add_attribute("minter", roles.minter)
+        .add_attribute("creator", roles.creator)
+        .add_attribute("burner", roles.burner))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    Ok(Response::new())
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    cw2::ensure_from_older_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    ensure_storage_version::<NodData>(deps.storage)?;
+    match msg {
+        MigrateMsg::Migrate { burner } => {
+            // deployments created before the burner role was introduced have no burner set
+            if let Some(burner) = burner {
+                if BURNER.get_ownership(deps.storage)?.owner.is_none() {
+                    outbe_nft::execute::initialize_burner(deps.storage, deps.api, Some(&burner))?;
+                }
+            }
+            Ok(Response::new())
+        }
+        MigrateMsg::ReindexTokens { limit } => {
+            let limit = limit.unwrap_or(DEFAULT_REINDEX_LIMIT).max(1) as usize;
+            let progress = reindex_nft_infos::<NodData, NodIndexes>(deps.storage, limit)?;
+            Ok(Response::new()
+                .set_data(to_json_binary(&progress)?)
+                .add_attribute("action", "nod::reindex_tokens")
+                .add_attribute("reindexed", progress.migrated.to_string())
+                .add_attribute("finished", progress.finished.to_string()))
+        }
+    }
 }
 
+/// Default number of nods saved by a single `ReindexTokens` call
+const DEFAULT_REINDEX_LIMIT: u32 = 500;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -72,7 +97,7 @@ pub fn execute(
             owner,
             extension,
         } => execute_submit(deps, &env, &info, token_id, owner, *extension),
-        ExecuteMsg::Burn { token_id } => execute_burn(deps, &env, &info, token_id),
+        ExecuteMsg::MintBatch { tokens } => execute_mint_batch(deps, &env, &info, tokens),
         ExecuteMsg::PriceUpdate { price_threshold } => {
             execute_update_tokens_to_qualified(deps, &env, &info, price_threshold)
         }
@@ -81,14 +106,102 @@ pub fn execute(
         }
         #[cfg(feature = "demo")]
         ExecuteMsg::BurnAll { batch_size } => execute_burn_all(deps, &env, &info, batch_size),
+        ExecuteMsg::UpdateMinterOwnership(action) => execute_cw721(
+            deps,
+            &env,
+            &info,
+            Cw721ExecuteMsg::UpdateMinterOwnership(action),
+        ),
+        ExecuteMsg::UpdateCreatorOwnership(action) => execute_cw721(
+            deps,
+            &env,
+            &info,
+            Cw721ExecuteMsg::UpdateCreatorOwnership(action),
+        ),
+        ExecuteMsg::UpdateBurnerOwnership(action) => execute_cw721(
+            deps,
+            &env,
+            &info,
+            Cw721ExecuteMsg::UpdateBurnerOwnership(action),
+        ),
+        ExecuteMsg::Burn { token_id } => {
+            execute_cw721(deps, &env, &info, Cw721ExecuteMsg::Burn { token_id })
+        }
+        ExecuteMsg::BurnBatch { token_ids } => {
+            execute_cw721(deps, &env, &info, Cw721ExecuteMsg::BurnBatch { token_ids })
+        }
+        ExecuteMsg::TransferNft {
+            recipient,
+            token_id,
+        } => execute_cw721(
+            deps,
+            &env,
+            &info,
+            Cw721ExecuteMsg::TransferNft {
+                recipient,
+                token_id,
+            },
+        ),
+        ExecuteMsg::SendNft {
+            contract,
+            token_id,
+            msg,
+        } => execute_cw721(
+            deps,
+            &env,
+            &info,
+            Cw721ExecuteMsg::SendNft {
+                contract,
+                token_id,
+                msg,
+            },
+        ),
+        ExecuteMsg::Approve {
+            spender,
+            token_id,
+            expires,
+        } => execute_cw721(
+            deps,
+            &env,
+            &info,
+            Cw721ExecuteMsg::Approve {
+                spender,
+                token_id,
+                expires,
+            },
+        ),
+        ExecuteMsg::Revoke { spender, token_id } => execute_cw721(
+            deps,
+            &env,
+            &info,
+            Cw721ExecuteMsg::Revoke { spender, token_id },
+        ),
+        ExecuteMsg::ApproveAll { operator, expires } => execute_cw721(
+            deps,
+            &env,
+            &info,
+            Cw721ExecuteMsg::ApproveAll { operator, expires },
+        ),
+        ExecuteMsg::RevokeAll { operator } => {
+            execute_cw721(deps, &env, &info, Cw721ExecuteMsg::RevokeAll { operator })
+        }
     }
 }
 
+fn execute_cw721(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    msg: Cw721ExecuteMsg,
+) -> Result<Response, ContractError> {
+    Ok(NodCollection::execute_cw721(deps, env, info, msg)?)
+}
+
 #[allow(clippy::too_many_arguments)]
 fn execute_submit(
     deps: DepsMut,
     env: &Env,
-    _info: &MessageInfo,
+    info: &MessageInfo,
     token_id: String,
     owner: String,
     extension: SubmitExtension,
@@ -96,41 +209,16 @@ fn execute_submit(
     // TODO uncomment after demo
     // outbe_nft::execute::assert_minter(deps.storage, &info.sender)?;
 
-    let owner_addr = deps.api.addr_validate(&owner)?;
-    let entity = extension.entity;
+    let entity = extension.entity.clone();
+    let token = new_nod(deps.api, env, &owner, None, extension)?;
+    let node_issued_at = token.extension.issued_at;
 
-    let node_issued_at = extension.created_at.unwrap_or(env.block.time);
-
-    let data = NodData {
-        nod_id: entity.nod_id.clone(),
-        worldwide_day: entity.worldwide_day,
-        settlement_currency: entity.settlement_currency.clone(),
-        symbolic_rate: entity.symbolic_rate,
-        floor_rate: entity.floor_rate,
-        nominal_price: entity.nominal_price,
-        issuance_price: entity.issuance_price,
-        gratis_load_minor: entity.gratis_load_minor,
-        floor_price: entity.floor_price,
-        state: entity.state.clone(),
-        owner: entity.owner.clone(),
-        issued_at: node_issued_at,
-        qualified_at: entity.qualified_at,
-        is_touch: entity.is_touch,
-    };
-    let token = NodNft {
-        owner: owner_addr,
-        token_uri: None, // todo populate
-        extension: data,
-    };
-
-    let config = Cw721Config::<NodData, NodConfig>::default();
-    config
-        .nft_info
-        .update(deps.storage, &token_id, |old| match old {
-            Some(_) => Err(Cw721ContractError::Claimed {}),
-            None => Ok(token),
-        })?;
-    config.increment_tokens(deps.storage)?;
+    NodCollection::mint(
+        deps.storage,
+        &HistoryContext::new(env, info),
+        &token_id,
+        token,
+    )?;
 
     Ok(Response::new()
         .add_attribute("action", "nod::submit")
@@ -162,20 +250,68 @@ fn execute_submit(
         ))
 }
 
-fn execute_burn(
+fn execute_mint_batch(
     deps: DepsMut,
-    _env: &Env,
+    env: &Env,
     info: &MessageInfo,
-    token_id: String,
+    tokens: Vec<MintBatchItem<SubmitExtension>>,
 ) -> Result<Response, ContractError> {
-    let config = Cw721Config::<NodData, NodConfig>::default();
-    config.nft_info.remove(deps.storage, &token_id)?;
-    config.decrement_tokens(deps.storage)?;
+    outbe_nft::execute::assert_minter(deps.storage, &info.sender)?;
+
+    let mut token_ids: Vec<String> = Vec::with_capacity(tokens.len());
+    let mut gratis_load_total = Uint128::zero();
+    let mut batch: Vec<(String, NodNft, Option<String>)> = Vec::with_capacity(tokens.len());
+    for item in tokens {
+        let token = new_nod(deps.api, env, &item.owner, item.token_uri, item.extension)?;
+        gratis_load_total += token.extension.gratis_load_minor;
+        token_ids.push(item.token_id.clone());
+        batch.push((item.token_id, token, item.reason));
+    }
+
+    NodCollection::mint_batch(deps.storage, &HistoryContext::new(env, info), batch)?;
 
     Ok(Response::new()
-        .add_attribute("action", "nod::burn")
-        .add_attribute("sender", info.sender.to_string())
-        .add_attribute("token_id", token_id))
+        .add_attribute("action", "nod::mint_batch")
+        .add_event(
+            Event::new("nod::mint_batch")
+                .add_attribute("count", token_ids.len().to_string())
+                .add_attribute("token_ids", token_ids.join(","))
+                .add_attribute("gratis_load_minor", gratis_load_total.to_string()),
+        ))
+}
+
+fn new_nod(
+    api: &dyn Api,
+    env: &Env,
+    owner: &str,
+    token_uri: Option<String>,
+    extension: SubmitExtension,
+) -> Result<NodNft, ContractError> {
+    let owner_addr = api.addr_validate(owner)?;
+    let entity = extension.entity;
+
+    let data = NodData {
+        nod_id: entity.nod_id,
+        worldwide_day: entity.worldwide_day,
+        settlement_currency: entity.settlement_currency,
+        symbolic_rate: entity.symbolic_rate,
+        floor_rate: entity.floor_rate,
+        nominal_price: entity.nominal_price,
+        issuance_price: entity.issuance_price,
+        gratis_load_minor: entity.gratis_load_minor,
+        floor_price: entity.floor_price,
+        state: entity.state,
+        owner: entity.owner,
+        issued_at: extension.created_at.unwrap_or(env.block.time),
+        qualified_at: entity.qualified_at,
+        is_touch: entity.is_touch,
+    };
+    Ok(NodNft {
+        owner: owner_addr,
+        token_uri,
+        approvals: vec![],
+        extension: data,
+    })
 }
 
 fn execute_update_tokens_to_qualified(
@@ -192,32 +328,48 @@ fn execute_update_tokens_to_qualified(
         return Err(ContractError::Unauthorized {});
     }
 
-    let config = Cw721Config::<NodData, NodConfig>::default();
+    let config = NodCollection::config();
     let mut updated_count = 0u32;
     let mut updated_tokens = Vec::<String>::new();
 
+    let issued = State::Issued.to_string();
+    // NB: `MultiIndex::prefix_range` decodes the index key instead of the primary key,
+    // so the raw variant is used and the token id is decoded from the returned key.
     let all_tokens: Vec<(String, NftInfo<NodData>)> = config
         .nft_info
-        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
-        .filter_map(|item| match item {
-            Ok((token_id, data)) => {
-                if data.extension.state == State::Issued
-                    && data.extension.floor_price <= price_threshold
-                {
-                    let mut new_data = data.clone();
-                    new_data.extension.state = State::Qualified;
-                    new_data.extension.qualified_at = Some(env.block.time);
-                    Some((token_id, new_data))
-                } else {
-                    None
-                }
-            }
-            Err(_) => None,
+        .idx
+        .extension
+        .floor_price
+        .prefix_range_raw(
+            deps.storage,
+            Some(PrefixBound::inclusive((issued.clone(), 0u128))),
+            Some(PrefixBound::inclusive((
+                issued,
+                price_threshold.atomics().u128(),
+            ))),
+            Order::Ascending,
+        )
+        .map(|item| {
+            let (token_id, mut data) = item?;
+            data.extension.state = State::Qualified;
+            data.extension.qualified_at = Some(env.block.time);
+            Ok((String::from_utf8(token_id)?, data))
         })
-        .collect();
+        .collect::<StdResult<_>>()?;
 
+    let ctx = HistoryContext::new(env, info)
+        .with_reason(format!("floor price below {}", price_threshold));
     for (token_id, data) in all_tokens {
         config.nft_info.save(deps.storage, &token_id, &data)?;
+        config.record_history(
+            deps.storage,
+            &ctx,
+            &token_id,
+            TokenAction::StateChange {
+                from: State::Issued.to_string(),
+                to: State::Qualified.to_string(),
+            },
+        )?;
 
         updated_count += 1;
         updated_tokens.push(token_id);
@@ -268,16 +420,17 @@ fn execute_update_price_updater(
 #[cfg(feature = "demo")]
 fn execute_burn_all(
     deps: DepsMut,
-    _env: &Env,
+    env: &Env,
     info: &MessageInfo,
     batch_size: Option<usize>,
 ) -> Result<Response, ContractError> {
-    let config = Cw721Config::<NodData, NodConfig>::default();
-    // TODO verify ownership
-    // let token = config.nft_info.load(deps.storage, &token_id)?;
-    // check_can_send(deps.as_ref(), env, info.sender.as_str(), &token)?;
-
-    config.clean_tokens(deps.storage, batch_size)?;
+    outbe_nft::execute::assert_burner(deps.storage, &info.sender)?;
+    let config = NodCollection::config();
+    config.clean_tokens(
+        deps.storage,
+        &HistoryContext::new(env, info).with_reason("burn_all"),
+        batch_size,
+    )?;
 
     Ok(Response::new()
         .add_attribute("action", "nod::burn_all")
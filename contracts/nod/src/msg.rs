@@ -1,13 +1,22 @@
 use crate::types::State;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Decimal, Timestamp, Uint128};
-use outbe_nft::msg::Cw721InstantiateMsg;
+use cosmwasm_std::{Binary, Decimal, Timestamp, Uint128};
+use cw_utils::Expiration;
+use outbe_nft::msg::{Cw721InstantiateMsg, MintBatchItem};
+use outbe_nft::state::TransferPolicy;
 use outbe_utils::date::WorldwideDay;
 use outbe_utils::denom::Denom;
 
 /// Custom collection extension for instantiate
 #[cw_serde]
-pub struct NodCollectionExtension {}
+pub struct NodCollectionExtension {
+    /// Defines who can transfer Nods, soulbound if not set
+    pub transfer_policy: Option<TransferPolicy>,
+    /// Keeps mint, state change, transfer and burn history of Nods, disabled if not set
+    pub track_history: Option<bool>,
+    /// Renders the metadata of Nods as a `data:` token_uri, disabled if not set
+    pub render_token_uri: Option<bool>,
+}
 
 pub type InstantiateMsg = Cw721InstantiateMsg<NodCollectionExtension>;
 
@@ -23,8 +32,55 @@ pub enum ExecuteMsg {
         /// Custom extension data for the Nod
         extension: Box<SubmitExtension>,
     },
-    /// Burn an existing Nod NFT
-    Burn { token_id: String },
+    /// Submit (mint) several Nod NFTs at once, either all of them or none
+    MintBatch {
+        tokens: Vec<MintBatchItem<SubmitExtension>>,
+    },
+    /// Burn an existing Nod NFT, allowed to the owner, an approved operator or the burner
+    Burn {
+        token_id: String,
+    },
+    /// Burn several Nod NFTs at once, the sender must be allowed to burn all of them
+    BurnBatch {
+        token_ids: Vec<String>,
+    },
+    UpdateMinterOwnership(cw_ownable::Action),
+    UpdateCreatorOwnership(cw_ownable::Action),
+    UpdateBurnerOwnership(cw_ownable::Action),
+    /// Transfer is a base message to move a token to another account without triggering actions
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    /// Send is a base message to transfer a token to a contract and trigger an action
+    /// on the receiving contract.
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    /// Allows operator to transfer / send the token from the owner's account.
+    /// If expiration is set, then this allowance has a time/height limit
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted Approval
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    /// Allows operator to transfer / send any token from the owner's account.
+    /// If expiration is set, then this allowance has a time/height limit
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted ApproveAll permission
+    RevokeAll {
+        operator: String,
+    },
     /// Update tokens with floor_price less than threshold to Qualified status
     PriceUpdate {
         /// Price threshold - tokens with floor_price < threshold will be updated
@@ -36,7 +92,9 @@ pub enum ExecuteMsg {
         price_updater: Option<String>,
     },
     #[cfg(feature = "demo")]
-    BurnAll { batch_size: Option<usize> },
+    BurnAll {
+        batch_size: Option<usize>,
+    },
 }
 
 /// Extension data for submit (mint)
@@ -67,5 +125,11 @@ pub struct NodEntity {
 
 #[cw_serde]
 pub enum MigrateMsg {
-    Migrate {},
+    Migrate {
+        /// Sets the burner if the contract does not have one yet
+        burner: Option<String>,
+    },
+    /// Saves at most `limit` stored nods again so that indexes added by an upgrade cover them.
+    /// The response data tells whether nods are left.
+    ReindexTokens { limit: Option<u32> },
 }
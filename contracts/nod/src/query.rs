@@ -1,31 +1,65 @@
-use crate::types::{NodConfig, NodData};
+use crate::types::{NodCollection, NodConfig, NodData};
 use cosmwasm_schema::{cw_serde, QueryResponses};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order, StdResult};
+use cosmwasm_std::{Binary, Deps, Env, Order, StdResult};
+use outbe_nft::collection::Cw721Collection;
+use outbe_nft::query::Cw721QueryMsg;
 
 /// Query messages for Nod contract
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
-    #[returns(outbe_nft::msg::ContractInfoResponse<NodConfig>)]
-    ContractInfo {},
-
+    // ---- CW721 spec queries ----
     #[returns(outbe_nft::msg::OwnerOfResponse)]
-    OwnerOf { token_id: String },
+    OwnerOf {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+
+    #[returns(outbe_nft::msg::ApprovalResponse)]
+    Approval {
+        token_id: String,
+        spender: String,
+        include_expired: Option<bool>,
+    },
+
+    #[returns(outbe_nft::msg::ApprovalsResponse)]
+    Approvals {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+
+    #[returns(outbe_nft::msg::OperatorResponse)]
+    Operator {
+        owner: String,
+        operator: String,
+        include_expired: Option<bool>,
+    },
+
+    #[returns(outbe_nft::msg::OperatorsResponse)]
+    AllOperators {
+        owner: String,
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 
     #[returns(outbe_nft::msg::NumTokensResponse)]
     NumTokens {},
 
-    #[returns(cw_ownable::Ownership<String>)]
-    GetMinterOwnership {},
-
-    #[returns(cw_ownable::Ownership<String>)]
-    GetCreatorOwnership {},
+    #[returns(outbe_nft::msg::ContractInfoResponse)]
+    ContractInfo {},
 
     #[returns(outbe_nft::msg::NftInfoResponse<NodData>)]
     NftInfo { token_id: String },
 
+    #[returns(outbe_nft::msg::AllNftInfoResponse<NodData>)]
+    AllNftInfo {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+
     #[returns(outbe_nft::msg::TokensResponse)]
     Tokens {
         owner: String,
@@ -40,56 +74,126 @@ pub enum QueryMsg {
         limit: Option<u32>,
         query_order: Option<Order>,
     },
+
+    // ---- Nod queries ----
+    #[returns(outbe_nft::msg::CollectionInfoResponse<NodConfig>)]
+    GetCollectionInfo {},
+
+    #[returns(cw_ownable::Ownership<String>)]
+    GetMinterOwnership {},
+
+    #[returns(cw_ownable::Ownership<String>)]
+    GetCreatorOwnership {},
+
+    #[returns(cw_ownable::Ownership<String>)]
+    GetBurnerOwnership {},
+
+    /// Returns the ERC721 metadata built from the token data
+    #[returns(outbe_nft::metadata::NftMetadata)]
+    NftMetadata { token_id: String },
+
+    /// Returns the recorded history of the token, also after it was burned.
+    /// Empty unless the collection keeps history.
+    #[returns(outbe_nft::msg::TokenHistoryResponse)]
+    TokenHistory {
+        token_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::ContractInfo {} => to_json_binary(&outbe_nft::query::query_contract_info::<
-            NodConfig,
-        >(deps.storage)?),
-        QueryMsg::OwnerOf { token_id } => to_json_binary(&outbe_nft::query::query_owner_of(
-            deps.storage,
-            &env,
+    let cw721_msg = match msg {
+        QueryMsg::OwnerOf {
             token_id,
-        )?),
-        QueryMsg::NumTokens {} => {
-            to_json_binary(&outbe_nft::query::query_num_tokens(deps.storage)?)
-        }
-        QueryMsg::GetMinterOwnership {} => {
-            to_json_binary(&outbe_nft::query::query_minter_ownership(deps.storage)?)
-        }
-        QueryMsg::GetCreatorOwnership {} => {
-            to_json_binary(&outbe_nft::query::query_creator_ownership(deps.storage)?)
-        }
-        QueryMsg::NftInfo { token_id } => to_json_binary(&outbe_nft::query::query_nft_info::<
-            NodData,
-        >(deps.storage, token_id)?),
+            include_expired,
+        } => Cw721QueryMsg::OwnerOf {
+            token_id,
+            include_expired,
+        },
+        QueryMsg::Approval {
+            token_id,
+            spender,
+            include_expired,
+        } => Cw721QueryMsg::Approval {
+            token_id,
+            spender,
+            include_expired,
+        },
+        QueryMsg::Approvals {
+            token_id,
+            include_expired,
+        } => Cw721QueryMsg::Approvals {
+            token_id,
+            include_expired,
+        },
+        QueryMsg::Operator {
+            owner,
+            operator,
+            include_expired,
+        } => Cw721QueryMsg::Operator {
+            owner,
+            operator,
+            include_expired,
+        },
+        QueryMsg::AllOperators {
+            owner,
+            include_expired,
+            start_after,
+            limit,
+        } => Cw721QueryMsg::AllOperators {
+            owner,
+            include_expired,
+            start_after,
+            limit,
+        },
+        QueryMsg::NumTokens {} => Cw721QueryMsg::NumTokens {},
+        QueryMsg::ContractInfo {} => Cw721QueryMsg::ContractInfo {},
+        QueryMsg::NftInfo { token_id } => Cw721QueryMsg::NftInfo { token_id },
+        QueryMsg::AllNftInfo {
+            token_id,
+            include_expired,
+        } => Cw721QueryMsg::AllNftInfo {
+            token_id,
+            include_expired,
+        },
         QueryMsg::Tokens {
             owner,
             start_after,
             limit,
             query_order,
-        } => to_json_binary(&outbe_nft::query::query_tokens(
-            deps,
-            &env,
+        } => Cw721QueryMsg::Tokens {
             owner,
             start_after,
             limit,
             query_order,
-        )?),
+        },
         QueryMsg::AllTokens {
             start_after,
             limit,
             query_order,
-        } => to_json_binary(&outbe_nft::query::query_all_tokens(
-            deps,
-            &env,
+        } => Cw721QueryMsg::AllTokens {
             start_after,
             limit,
             query_order,
-        )?),
-    }
+        },
+        QueryMsg::GetCollectionInfo {} => Cw721QueryMsg::GetCollectionInfo {},
+        QueryMsg::GetMinterOwnership {} => Cw721QueryMsg::GetMinterOwnership {},
+        QueryMsg::GetCreatorOwnership {} => Cw721QueryMsg::GetCreatorOwnership {},
+        QueryMsg::GetBurnerOwnership {} => Cw721QueryMsg::GetBurnerOwnership {},
+        QueryMsg::NftMetadata { token_id } => Cw721QueryMsg::NftMetadata { token_id },
+        QueryMsg::TokenHistory {
+            token_id,
+            start_after,
+            limit,
+        } => Cw721QueryMsg::TokenHistory {
+            token_id,
+            start_after,
+            limit,
+        },
+    };
+    NodCollection::query_cw721(deps, &env, cw721_msg)
 }
 
 #[cfg(test)]
@@ -102,6 +206,7 @@ mod tests {
     use crate::types::{NodData, State};
     use cosmwasm_std::{Decimal, Timestamp, Uint128};
     use cw_multi_test::{App, ContractWrapper, Executor};
+    use outbe_nft::state::{TokenAction, TransferPolicy};
     use outbe_utils::denom::{Currency, Denom};
     use std::str::FromStr;
 
@@ -116,7 +221,11 @@ mod tests {
         let init_msg = InstantiateMsg {
             name: "nod".to_string(),
             symbol: "NOD".to_string(),
-            collection_info_extension: NodCollectionExtension {},
+            collection_info_extension: NodCollectionExtension {
+                transfer_policy: None,
+                track_history: None,
+                render_token_uri: None,
+            },
             minter: None,
             creator: None,
             burner: None,
@@ -143,6 +252,11 @@ mod tests {
             .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetCreatorOwnership {})
             .unwrap();
         assert_eq!(resp.owner.unwrap(), owner.to_string());
+        let resp: cw_ownable::Ownership<String> = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetBurnerOwnership {})
+            .unwrap();
+        assert_eq!(resp.owner.unwrap(), owner.to_string());
 
         // Submit (mint) a new Nod NFT
         let token_id = "token1".to_string();
@@ -188,6 +302,7 @@ mod tests {
                 contract_addr.clone(),
                 &QueryMsg::OwnerOf {
                     token_id: token_id.clone(),
+                    include_expired: None,
                 },
             )
             .unwrap();
@@ -247,10 +362,20 @@ mod tests {
             .unwrap();
         assert_eq!(resp.tokens, vec![token_id.clone()]);
 
-        // Burn the token
+        // Burn the token, a stranger is not allowed to
         let exec_msg = ExecuteMsg::Burn {
             token_id: token_id.clone(),
         };
+        let stranger = app.api().addr_make("stranger");
+        let err = app
+            .execute_contract(stranger, contract_addr.clone(), &exec_msg, &[])
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<crate::error::ContractError>().unwrap(),
+            crate::error::ContractError::Cw721ContractError(
+                outbe_nft::error::Cw721ContractError::NotOwnerOperatorOrBurner {}
+            )
+        );
         app.execute_contract(owner.clone(), contract_addr.clone(), &exec_msg, &[])
             .unwrap();
 
@@ -273,7 +398,11 @@ mod tests {
         let init_msg = InstantiateMsg {
             name: "nod".to_string(),
             symbol: "NOD".to_string(),
-            collection_info_extension: NodCollectionExtension {},
+            collection_info_extension: NodCollectionExtension {
+                transfer_policy: None,
+                track_history: None,
+                render_token_uri: None,
+            },
             minter: None,
             creator: None,
             burner: None,
@@ -333,6 +462,7 @@ mod tests {
                 contract_addr.clone(),
                 &QueryMsg::OwnerOf {
                     token_id: token_id.clone(),
+                    include_expired: None,
                 },
             )
             .unwrap();
@@ -396,7 +526,11 @@ mod tests {
         let init_msg = InstantiateMsg {
             name: "nod".to_string(),
             symbol: "NOD".to_string(),
-            collection_info_extension: NodCollectionExtension {},
+            collection_info_extension: NodCollectionExtension {
+                transfer_policy: None,
+                track_history: None,
+                render_token_uri: None,
+            },
             minter: None,
             creator: Some(creator.to_string()),
             burner: None,
@@ -426,7 +560,11 @@ mod tests {
         let init_msg = InstantiateMsg {
             name: "nod".to_string(),
             symbol: "NOD".to_string(),
-            collection_info_extension: NodCollectionExtension {},
+            collection_info_extension: NodCollectionExtension {
+                transfer_policy: None,
+                track_history: None,
+                render_token_uri: None,
+            },
             minter: None,
             creator: Some(creator.to_string()),
             burner: None,
@@ -456,7 +594,11 @@ mod tests {
         let init_msg = InstantiateMsg {
             name: "nod".to_string(),
             symbol: "NOD".to_string(),
-            collection_info_extension: NodCollectionExtension {},
+            collection_info_extension: NodCollectionExtension {
+                transfer_policy: None,
+                track_history: None,
+                render_token_uri: None,
+            },
             minter: None,
             creator: Some(creator.to_string()),
             burner: None,
@@ -493,7 +635,11 @@ mod tests {
         let init_msg = InstantiateMsg {
             name: "nod".to_string(),
             symbol: "NOD".to_string(),
-            collection_info_extension: NodCollectionExtension {},
+            collection_info_extension: NodCollectionExtension {
+                transfer_policy: None,
+                track_history: None,
+                render_token_uri: None,
+            },
             minter: None,
             creator: Some(creator.to_string()),
             burner: None,
@@ -510,4 +656,618 @@ mod tests {
             app.execute_contract(unauthorized.clone(), contract_addr.clone(), &exec_msg, &[]);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_price_update_qualifies_issued_tokens_below_threshold() {
+        let mut app = App::default();
+        let creator = app.api().addr_make("creator");
+        let recipient = app.api().addr_make("recipient");
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let init_msg = InstantiateMsg {
+            name: "nod".to_string(),
+            symbol: "NOD".to_string(),
+            collection_info_extension: NodCollectionExtension {
+                transfer_policy: None,
+                track_history: None,
+                render_token_uri: None,
+            },
+            minter: None,
+            creator: None,
+            burner: None,
+        };
+        let contract_addr = app
+            .instantiate_contract(code_id, creator.clone(), &init_msg, &[], "nod1", None)
+            .unwrap();
+        app.execute_contract(
+            creator.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdatePriceUpdater {
+                price_updater: Some(creator.to_string()),
+            },
+            &[],
+        )
+        .unwrap();
+
+        for (token_id, floor_price) in [("t1", "50"), ("t2", "100"), ("t3", "100.5"), ("t4", "7")] {
+            let entity = NodEntity {
+                nod_id: token_id.to_string(),
+                worldwide_day: 20250101,
+                settlement_currency: Denom::Fiat(Currency::Usd),
+                symbolic_rate: Decimal::one(),
+                floor_rate: Decimal::one(),
+                nominal_price: Decimal::one(),
+                issuance_price: Decimal::one(),
+                gratis_load_minor: Uint128::new(1),
+                floor_price: Decimal::from_str(floor_price).unwrap(),
+                state: State::Issued,
+                owner: recipient.to_string(),
+                qualified_at: None,
+                is_touch: false,
+            };
+            let exec_msg = ExecuteMsg::Submit {
+                token_id: token_id.to_string(),
+                owner: recipient.to_string(),
+                extension: Box::new(SubmitExtension {
+                    entity,
+                    created_at: None,
+                }),
+            };
+            app.execute_contract(creator.clone(), contract_addr.clone(), &exec_msg, &[])
+                .unwrap();
+        }
+
+        let exec_msg = ExecuteMsg::PriceUpdate {
+            price_threshold: Decimal::from_str("100").unwrap(),
+        };
+        let res = app
+            .execute_contract(creator.clone(), contract_addr.clone(), &exec_msg, &[])
+            .unwrap();
+        assert!(res.events.iter().any(|e| e
+            .attributes
+            .iter()
+            .any(|a| a.key == "updated_count" && a.value == "3")));
+
+        for (token_id, expected) in [
+            ("t1", State::Qualified),
+            ("t2", State::Qualified),
+            ("t3", State::Issued),
+            ("t4", State::Qualified),
+        ] {
+            let resp: outbe_nft::msg::NftInfoResponse<NodData> = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::NftInfo {
+                        token_id: token_id.to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(resp.extension.state, expected, "token {token_id}");
+        }
+    }
+
+    #[test]
+    fn test_transfer_updates_owner_index() {
+        let mut app = App::default();
+        let owner = app.api().addr_make("owner");
+        let holder = app.api().addr_make("holder");
+        let escrow = app.api().addr_make("escrow");
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let init_msg = InstantiateMsg {
+            name: "nod".to_string(),
+            symbol: "NOD".to_string(),
+            collection_info_extension: NodCollectionExtension {
+                transfer_policy: Some(TransferPolicy::OwnerOnly),
+                track_history: None,
+                render_token_uri: None,
+            },
+            minter: None,
+            creator: None,
+            burner: None,
+        };
+        let contract_addr = app
+            .instantiate_contract(code_id, owner.clone(), &init_msg, &[], "nod1", None)
+            .unwrap();
+
+        let entity = NodEntity {
+            nod_id: "nod1".to_string(),
+            worldwide_day: 20250101,
+            settlement_currency: Denom::Fiat(Currency::Usd),
+            symbolic_rate: Decimal::one(),
+            floor_rate: Decimal::one(),
+            nominal_price: Decimal::one(),
+            issuance_price: Decimal::one(),
+            gratis_load_minor: Uint128::new(1),
+            floor_price: Decimal::one(),
+            state: State::Issued,
+            owner: holder.to_string(),
+            qualified_at: None,
+            is_touch: false,
+        };
+        let exec_msg = ExecuteMsg::Submit {
+            token_id: "nod1".to_string(),
+            owner: holder.to_string(),
+            extension: Box::new(SubmitExtension {
+                entity,
+                created_at: None,
+            }),
+        };
+        app.execute_contract(owner.clone(), contract_addr.clone(), &exec_msg, &[])
+            .unwrap();
+
+        // only the holder can move the token
+        let exec_msg = ExecuteMsg::TransferNft {
+            recipient: escrow.to_string(),
+            token_id: "nod1".to_string(),
+        };
+        app.execute_contract(owner.clone(), contract_addr.clone(), &exec_msg, &[])
+            .unwrap_err();
+        app.execute_contract(holder.clone(), contract_addr.clone(), &exec_msg, &[])
+            .unwrap();
+
+        let tokens_of = |owner: &cosmwasm_std::Addr| -> Vec<String> {
+            let resp: outbe_nft::msg::TokensResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::Tokens {
+                        owner: owner.to_string(),
+                        start_after: None,
+                        limit: None,
+                        query_order: None,
+                    },
+                )
+                .unwrap();
+            resp.tokens
+        };
+        assert!(tokens_of(&holder).is_empty());
+        assert_eq!(tokens_of(&escrow), vec!["nod1".to_string()]);
+
+        let resp: outbe_nft::msg::OwnerOfResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::OwnerOf {
+                    token_id: "nod1".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(resp.owner, escrow.to_string());
+    }
+
+    #[test]
+    fn test_mint_and_burn_batch() {
+        let mut app = App::default();
+        let owner = app.api().addr_make("owner");
+        let holder = app.api().addr_make("holder");
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let init_msg = InstantiateMsg {
+            name: "nod".to_string(),
+            symbol: "NOD".to_string(),
+            collection_info_extension: NodCollectionExtension {
+                transfer_policy: None,
+                track_history: None,
+                render_token_uri: None,
+            },
+            minter: None,
+            creator: None,
+            burner: None,
+        };
+        let contract_addr = app
+            .instantiate_contract(code_id, owner.clone(), &init_msg, &[], "nod1", None)
+            .unwrap();
+
+        let item = |token_id: &str| outbe_nft::msg::MintBatchItem {
+            token_id: token_id.to_string(),
+            owner: holder.to_string(),
+            token_uri: None,
+            extension: SubmitExtension {
+                entity: NodEntity {
+                    nod_id: token_id.to_string(),
+                    worldwide_day: 20250101,
+                    settlement_currency: Denom::Fiat(Currency::Usd),
+                    symbolic_rate: Decimal::one(),
+                    floor_rate: Decimal::one(),
+                    nominal_price: Decimal::one(),
+                    issuance_price: Decimal::one(),
+                    gratis_load_minor: Uint128::new(1),
+                    floor_price: Decimal::one(),
+                    state: State::Issued,
+                    owner: holder.to_string(),
+                    qualified_at: None,
+                    is_touch: false,
+                },
+                created_at: None,
+            },
+            reason: None,
+        };
+        let num_tokens = |app: &App| -> u64 {
+            let resp: outbe_nft::msg::NumTokensResponse = app
+                .wrap()
+                .query_wasm_smart(contract_addr.clone(), &QueryMsg::NumTokens {})
+                .unwrap();
+            resp.count
+        };
+
+        let exec_msg = ExecuteMsg::MintBatch {
+            tokens: vec![item("nod1"), item("nod2"), item("nod3")],
+        };
+        // only the minter mints batches
+        app.execute_contract(holder.clone(), contract_addr.clone(), &exec_msg, &[])
+            .unwrap_err();
+        assert_eq!(num_tokens(&app), 0);
+
+        let res = app
+            .execute_contract(owner.clone(), contract_addr.clone(), &exec_msg, &[])
+            .unwrap();
+        assert!(res.has_event(
+            &cosmwasm_std::Event::new("wasm-nod::mint_batch")
+                .add_attribute("token_ids", "nod1,nod2,nod3")
+        ));
+        assert_eq!(num_tokens(&app), 3);
+
+        // the batch overlapping with minted tokens is rejected as a whole
+        let exec_msg = ExecuteMsg::MintBatch {
+            tokens: vec![item("nod4"), item("nod3")],
+        };
+        app.execute_contract(owner.clone(), contract_addr.clone(), &exec_msg, &[])
+            .unwrap_err();
+        assert_eq!(num_tokens(&app), 3);
+
+        let exec_msg = ExecuteMsg::BurnBatch {
+            token_ids: vec!["nod1".to_string(), "nod2".to_string()],
+        };
+        app.execute_contract(holder.clone(), contract_addr.clone(), &exec_msg, &[])
+            .unwrap();
+        assert_eq!(num_tokens(&app), 1);
+    }
+
+    #[test]
+    fn test_token_history_survives_burn() {
+        let mut app = App::default();
+        let creator = app.api().addr_make("creator");
+        let holder = app.api().addr_make("holder");
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let init_msg = InstantiateMsg {
+            name: "nod".to_string(),
+            symbol: "NOD".to_string(),
+            collection_info_extension: NodCollectionExtension {
+                transfer_policy: None,
+                track_history: Some(true),
+                render_token_uri: None,
+            },
+            minter: None,
+            creator: None,
+            burner: None,
+        };
+        let contract_addr = app
+            .instantiate_contract(code_id, creator.clone(), &init_msg, &[], "nod1", None)
+            .unwrap();
+        app.execute_contract(
+            creator.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdatePriceUpdater {
+                price_updater: Some(creator.to_string()),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let exec_msg = ExecuteMsg::MintBatch {
+            tokens: vec![outbe_nft::msg::MintBatchItem {
+                token_id: "nod1".to_string(),
+                owner: holder.to_string(),
+                token_uri: None,
+                extension: SubmitExtension {
+                    entity: NodEntity {
+                        nod_id: "nod1".to_string(),
+                        worldwide_day: 20250101,
+                        settlement_currency: Denom::Fiat(Currency::Usd),
+                        symbolic_rate: Decimal::one(),
+                        floor_rate: Decimal::one(),
+                        nominal_price: Decimal::one(),
+                        issuance_price: Decimal::one(),
+                        gratis_load_minor: Uint128::new(1),
+                        floor_price: Decimal::one(),
+                        state: State::Issued,
+                        owner: holder.to_string(),
+                        qualified_at: None,
+                        is_touch: false,
+                    },
+                    created_at: None,
+                },
+                reason: Some("lysis of tribute t1".to_string()),
+            }],
+        };
+        app.execute_contract(creator.clone(), contract_addr.clone(), &exec_msg, &[])
+            .unwrap();
+        let exec_msg = ExecuteMsg::PriceUpdate {
+            price_threshold: Decimal::from_str("2").unwrap(),
+        };
+        app.execute_contract(creator.clone(), contract_addr.clone(), &exec_msg, &[])
+            .unwrap();
+        let exec_msg = ExecuteMsg::Burn {
+            token_id: "nod1".to_string(),
+        };
+        app.execute_contract(holder.clone(), contract_addr.clone(), &exec_msg, &[])
+            .unwrap();
+
+        let resp: outbe_nft::msg::TokenHistoryResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::TokenHistory {
+                    token_id: "nod1".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        let actions: Vec<_> = resp
+            .entries
+            .iter()
+            .map(|entry| (entry.action.clone(), entry.sender.clone()))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                (
+                    TokenAction::Mint {
+                        owner: holder.clone()
+                    },
+                    creator.clone()
+                ),
+                (
+                    TokenAction::StateChange {
+                        from: State::Issued.to_string(),
+                        to: State::Qualified.to_string(),
+                    },
+                    creator.clone()
+                ),
+                (
+                    TokenAction::Burn {
+                        owner: holder.clone()
+                    },
+                    holder.clone()
+                ),
+            ]
+        );
+        assert_eq!(
+            resp.entries[0].reason,
+            Some("lysis of tribute t1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_nft_metadata() {
+        use outbe_nft::metadata::{NftAttribute, NftMetadata, DATA_URI_PREFIX};
+
+        let mut app = App::default();
+        let creator = app.api().addr_make("creator");
+        let holder = app.api().addr_make("holder");
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let init_msg = InstantiateMsg {
+            name: "nod".to_string(),
+            symbol: "NOD".to_string(),
+            collection_info_extension: NodCollectionExtension {
+                transfer_policy: None,
+                track_history: None,
+                render_token_uri: Some(true),
+            },
+            minter: None,
+            creator: None,
+            burner: None,
+        };
+        let contract_addr = app
+            .instantiate_contract(code_id, creator.clone(), &init_msg, &[], "nod1", None)
+            .unwrap();
+
+        let item = |token_id: &str, token_uri: Option<String>| outbe_nft::msg::MintBatchItem {
+            token_id: token_id.to_string(),
+            owner: holder.to_string(),
+            token_uri,
+            extension: SubmitExtension {
+                entity: NodEntity {
+                    nod_id: token_id.to_string(),
+                    worldwide_day: 20250101,
+                    settlement_currency: Denom::Fiat(Currency::Usd),
+                    symbolic_rate: Decimal::one(),
+                    floor_rate: Decimal::one(),
+                    nominal_price: Decimal::one(),
+                    issuance_price: Decimal::one(),
+                    gratis_load_minor: Uint128::new(25),
+                    floor_price: Decimal::one(),
+                    state: State::Issued,
+                    owner: holder.to_string(),
+                    qualified_at: None,
+                    is_touch: false,
+                },
+                created_at: None,
+            },
+            reason: None,
+        };
+        let exec_msg = ExecuteMsg::MintBatch {
+            tokens: vec![
+                item("nod1", None),
+                item("nod2", Some("ipfs://nod2".to_string())),
+            ],
+        };
+        app.execute_contract(creator.clone(), contract_addr.clone(), &exec_msg, &[])
+            .unwrap();
+
+        let metadata: NftMetadata = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::NftMetadata {
+                    token_id: "nod1".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(metadata.name, "nod #nod1");
+        assert!(metadata
+            .attributes
+            .contains(&NftAttribute::number("gratis_load_minor", 25)));
+        assert!(metadata
+            .attributes
+            .contains(&NftAttribute::new("state", "Issued")));
+
+        let token_uri = |token_id: &str| -> Option<String> {
+            let info: outbe_nft::msg::NftInfoResponse<NodData> = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::NftInfo {
+                        token_id: token_id.to_string(),
+                    },
+                )
+                .unwrap();
+            info.token_uri
+        };
+        // the metadata is rendered only for tokens minted without a token_uri
+        let rendered = token_uri("nod1").unwrap();
+        let encoded = rendered.strip_prefix(DATA_URI_PREFIX).unwrap();
+        let decoded: NftMetadata =
+            cosmwasm_std::from_json(cosmwasm_std::Binary::from_base64(encoded).unwrap()).unwrap();
+        assert_eq!(decoded, metadata);
+        assert_eq!(token_uri("nod2"), Some("ipfs://nod2".to_string()));
+    }
+
+    #[test]
+    fn test_reindex_tokens() {
+        use crate::contract::migrate;
+        use crate::msg::MigrateMsg;
+        use crate::types::NodConfig;
+        use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+        use cosmwasm_std::Addr;
+        use outbe_nft::migration::MigrationProgress;
+        use outbe_nft::state::{Cw721Config, NftInfo, NoExtensionIndexes};
+
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let info = message_info(&creator, &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                name: "nod".to_string(),
+                symbol: "NOD".to_string(),
+                collection_info_extension: NodCollectionExtension {
+                    transfer_policy: None,
+                    track_history: None,
+                    render_token_uri: None,
+                },
+                minter: None,
+                creator: None,
+                burner: None,
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::UpdatePriceUpdater {
+                price_updater: Some(creator.to_string()),
+            },
+        )
+        .unwrap();
+
+        // nods stored before the state and floor price indexes existed
+        let unindexed = Cw721Config::<NodData, NodConfig, NoExtensionIndexes>::default();
+        for (token_id, floor_price) in [("t1", "50"), ("t2", "100.5"), ("t3", "7")] {
+            let token = NftInfo {
+                owner: Addr::unchecked("holder"),
+                approvals: vec![],
+                token_uri: None,
+                extension: NodData {
+                    nod_id: token_id.to_string(),
+                    worldwide_day: 20250101,
+                    settlement_currency: Denom::Fiat(Currency::Usd),
+                    symbolic_rate: Decimal::one(),
+                    floor_rate: Decimal::one(),
+                    nominal_price: Decimal::one(),
+                    issuance_price: Decimal::one(),
+                    gratis_load_minor: Uint128::new(1),
+                    floor_price: Decimal::from_str(floor_price).unwrap(),
+                    state: State::Issued,
+                    owner: "holder".to_string(),
+                    issued_at: Timestamp::from_seconds(1000),
+                    qualified_at: None,
+                    is_touch: false,
+                },
+            };
+            unindexed
+                .nft_info
+                .save(deps.as_mut().storage, token_id, &token)
+                .unwrap();
+            unindexed.increment_tokens(deps.as_mut().storage).unwrap();
+        }
+
+        let price_update = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>| {
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                ExecuteMsg::PriceUpdate {
+                    price_threshold: Decimal::from_str("100").unwrap(),
+                },
+            )
+            .unwrap();
+            res.attributes
+                .into_iter()
+                .find(|a| a.key == "updated_count")
+                .unwrap()
+                .value
+        };
+        assert_eq!(price_update(&mut deps), "0");
+
+        let reindex = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>| -> MigrationProgress {
+            let res = migrate(
+                deps.as_mut(),
+                mock_env(),
+                MigrateMsg::ReindexTokens { limit: Some(2) },
+            )
+            .unwrap();
+            cosmwasm_std::from_json(res.data.unwrap()).unwrap()
+        };
+        let progress: Vec<_> = (0..2)
+            .map(|_| {
+                let p = reindex(&mut deps);
+                (p.migrated, p.finished)
+            })
+            .collect();
+        assert_eq!(progress, vec![(2, false), (1, true)]);
+
+        assert_eq!(price_update(&mut deps), "2");
+        let resp: outbe_nft::msg::NftInfoResponse<NodData> = cosmwasm_std::from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::NftInfo {
+                    token_id: "t2".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(resp.extension.state, State::Issued);
+    }
 }
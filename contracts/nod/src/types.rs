@@ -2,16 +2,41 @@ use std::fmt;
 
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Decimal, Timestamp, Uint128};
-use outbe_nft::state::NftInfo;
-use outbe_nft::traits::{Cw721CollectionConfig, Cw721CustomMsg, Cw721State};
+use cw_storage_plus::{Index, IndexList, MultiIndex};
+use outbe_nft::collection::Cw721Collection;
+use outbe_nft::metadata::{NftAttribute, NftMetadata};
+use outbe_nft::migration::VersionedExtension;
+use outbe_nft::state::{CollectionInfo, NftInfo, TransferPolicy};
+use outbe_nft::traits::{Cw721CollectionConfig, Cw721CustomMsg, Cw721ExtensionIndexes, Cw721State};
 use outbe_utils::date::WorldwideDay;
 use outbe_utils::denom::Denom;
 
-/// Configuration for the Nod NFT collection (empty)
+/// Configuration for the Nod NFT collection
 #[cw_serde]
-pub struct NodConfig {}
+pub struct NodConfig {
+    #[serde(default)]
+    pub transfer_policy: TransferPolicy,
+    /// Keeps the history of every Nod, see `QueryMsg::TokenHistory`
+    #[serde(default)]
+    pub track_history: bool,
+    /// Renders the metadata as `token_uri` of Nods minted without one
+    #[serde(default)]
+    pub render_token_uri: bool,
+}
+
+impl Cw721CollectionConfig for NodConfig {
+    fn transfer_policy(&self) -> TransferPolicy {
+        self.transfer_policy.clone()
+    }
 
-impl Cw721CollectionConfig for NodConfig {}
+    fn track_history(&self) -> bool {
+        self.track_history
+    }
+
+    fn render_token_uri(&self) -> bool {
+        self.render_token_uri
+    }
+}
 
 /// Public data for each Nod NFT
 #[cw_serde]
@@ -60,5 +85,77 @@ impl fmt::Display for State {
 
 pub type NodNft = NftInfo<NodData>;
 
-impl Cw721State for NodData {}
+impl Cw721State for NodData {
+    fn metadata(&self, collection: &CollectionInfo, token_id: &str) -> NftMetadata {
+        let kind = if self.is_touch { "Touch" } else { "Lysis" };
+        NftMetadata {
+            description: format!(
+                "{} Nod of {} issued on day {}",
+                kind, self.settlement_currency, self.worldwide_day
+            ),
+            attributes: vec![
+                NftAttribute::number("worldwide_day", self.worldwide_day),
+                NftAttribute::new("settlement_currency", &self.settlement_currency),
+                NftAttribute::number("gratis_load_minor", self.gratis_load_minor),
+                NftAttribute::number("floor_price", self.floor_price),
+                NftAttribute::number("issuance_price", self.issuance_price),
+                NftAttribute::new("state", &self.state),
+                NftAttribute::new("kind", kind),
+            ],
+            ..NftMetadata::base(collection, token_id)
+        }
+    }
+}
 impl Cw721CustomMsg for NodData {}
+
+impl VersionedExtension for NodData {
+    const VERSION: u16 = 1;
+}
+
+/// Nod specific indexes over the `nft_info` map
+pub struct NodIndexes<'a> {
+    pub state: MultiIndex<'a, String, NodNft, String>,
+    /// Indexes tokens by `(state, floor_price)` where the price is represented by its atomics
+    /// so tokens of the same state are ordered by floor price.
+    pub floor_price: MultiIndex<'a, (String, u128), NodNft, String>,
+}
+
+impl Cw721ExtensionIndexes<NodData> for NodIndexes<'_> {
+    fn new(pk_namespace: &'static str) -> Self {
+        Self {
+            state: MultiIndex::new(
+                |_pk, d: &NodNft| d.extension.state.to_string(),
+                pk_namespace,
+                "tokens__state",
+            ),
+            floor_price: MultiIndex::new(
+                |_pk, d: &NodNft| {
+                    (
+                        d.extension.state.to_string(),
+                        d.extension.floor_price.atomics().u128(),
+                    )
+                },
+                pk_namespace,
+                "tokens__floor_price",
+            ),
+        }
+    }
+}
+
+impl IndexList<NodNft> for NodIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<NodNft>> + '_> {
+        let v: Vec<&dyn Index<NodNft>> = vec![&self.state, &self.floor_price];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Nod collection, the standard CW721 messages are implemented by `Cw721Collection`
+pub struct NodCollection;
+
+impl Cw721Collection for NodCollection {
+    type NftExtension = NodData;
+    type CollectionConfig = NodConfig;
+    type ExtensionIndexes = NodIndexes<'static>;
+
+    const NAME: &'static str = "nod";
+}
@@ -17,8 +17,8 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    if msg.random_value.is_some() {
-        RND.save(deps.storage, &msg.random_value.unwrap())?;
+    if let Some(random_value) = msg.random_value {
+        RND.save(deps.storage, &random_value)?;
     }
 
     Ok(Response::default()
@@ -41,8 +41,8 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::SetRandom { random_value } => {
-            if random_value.is_some() {
-                RND.save(deps.storage, &random_value.unwrap())?;
+            if let Some(random_value) = random_value {
+                RND.save(deps.storage, &random_value)?;
             } else {
                 RND.remove(deps.storage);
             }
@@ -234,7 +234,7 @@ pub fn execute_mine_gratis_with_nod(
         funds: vec![],
     };
 
-    // Create burn message for the Nod NFT
+    // Create burn message for the Nod NFT, token-miner must be the burner of the Nod collection
     let burn_msg = NodExecuteMsg::Burn {
         token_id: nod_token_id.clone(),
     };
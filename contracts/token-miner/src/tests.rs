@@ -668,9 +668,8 @@ mod test_token_miner {
                                 Uint128::new(500),
                             );
                             let response = NftInfoResponse {
+                                token_uri: None,
                                 extension: nod_data,
-                                owner: user1_addr.clone(),
-                                token_id: "test_nod_1".to_string(),
                             };
                             SystemResult::Ok(ContractResult::Ok(to_json_binary(&response).unwrap()))
                         }
@@ -769,9 +768,8 @@ mod test_token_miner {
                         Uint128::new(500),
                     );
                     let response = NftInfoResponse {
+                        token_uri: None,
                         extension: nod_data,
-                        owner: user2_addr.clone(),
-                        token_id: "test_nod_1".to_string(),
                     };
                     SystemResult::Ok(ContractResult::Ok(to_json_binary(&response).unwrap()))
                 } else {
@@ -822,9 +820,8 @@ mod test_token_miner {
                         Uint128::new(500),
                     );
                     let response = NftInfoResponse {
+                        token_uri: None,
                         extension: nod_data,
-                        owner: user1_addr.clone(),
-                        token_id: "test_nod_1".to_string(),
                     };
                     SystemResult::Ok(ContractResult::Ok(to_json_binary(&response).unwrap()))
                 } else {
@@ -876,9 +873,8 @@ mod test_token_miner {
                         Uint128::new(500),
                     );
                     let response = NftInfoResponse {
+                        token_uri: None,
                         extension: nod_data,
-                        owner: user1_addr.clone(),
-                        token_id: "test_nod_1".to_string(),
                     };
                     SystemResult::Ok(ContractResult::Ok(to_json_binary(&response).unwrap()))
                 } else {
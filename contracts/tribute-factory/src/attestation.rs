@@ -0,0 +1,124 @@
+use crate::error::ContractError;
+use crate::msg::{AgentAttestation, AgentAttestationSetup};
+use crate::state::AgentAttestationConfig;
+use agent_common::msg::AgentResponse;
+use agent_common::types::{AgentStatus, AgentType};
+use cosmwasm_std::{Addr, Api, Deps, Env};
+use outbe_utils::{gen_compound_hash, Base58Binary};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+pub fn validate_agent_attestation_config(
+    api: &dyn Api,
+    setup: &AgentAttestationSetup,
+) -> Result<AgentAttestationConfig, ContractError> {
+    Ok(AgentAttestationConfig {
+        required: setup.required,
+        cra_registry: api.addr_validate(setup.cra_registry.as_str())?,
+        iba_registry: api.addr_validate(setup.iba_registry.as_str())?,
+    })
+}
+
+/// Hash the agent signs, it binds the encrypted offer to this factory
+pub fn attestation_hash(
+    factory: &Addr,
+    cipher_text: &Base58Binary,
+    nonce: &Base58Binary,
+    ephemeral_pubkey: &Base58Binary,
+) -> [u8; 32] {
+    let hash = gen_compound_hash(
+        Some("tribute-factory:attestation"),
+        vec![
+            factory.as_bytes(),
+            cipher_text.as_slice(),
+            nonce.as_slice(),
+            ephemeral_pubkey.as_slice(),
+        ],
+    );
+    hash.to_array().expect("sha256 is 32 bytes")
+}
+
+/// Checks the attestation of an encrypted offer and returns the agent that relayed it.
+/// Without an attestation config offers are not attested, otherwise an attestation
+/// is checked if present and is mandatory when required.
+pub fn attest_offer(
+    deps: Deps,
+    env: &Env,
+    config: Option<&AgentAttestationConfig>,
+    attestation: Option<&AgentAttestation>,
+    cipher_text: &Base58Binary,
+    nonce: &Base58Binary,
+    ephemeral_pubkey: &Base58Binary,
+) -> Result<Option<Addr>, ContractError> {
+    let Some(config) = config else {
+        return Ok(None);
+    };
+    let Some(attestation) = attestation else {
+        return match config.required {
+            true => Err(ContractError::AttestationRequired {}),
+            false => Ok(None),
+        };
+    };
+    let hash = attestation_hash(&env.contract.address, cipher_text, nonce, ephemeral_pubkey);
+    verify_attestation(deps, config, attestation, &hash)?;
+    Ok(Some(attestation.agent.clone()))
+}
+
+/// Checks that the attestation is signed by the key of the agent address and that the agent
+/// is an Active CRA or IBA agent
+pub fn verify_attestation(
+    deps: Deps,
+    config: &AgentAttestationConfig,
+    attestation: &AgentAttestation,
+    message_hash: &[u8; 32],
+) -> Result<(), ContractError> {
+    let agent = deps.api.addr_canonicalize(attestation.agent.as_str())?;
+    let key_hash = Ripemd160::digest(Sha256::digest(attestation.public_key.as_slice()));
+    if agent.as_slice() != key_hash.as_slice() {
+        return Err(ContractError::InvalidAttestation {});
+    }
+    let valid = deps
+        .api
+        .secp256k1_verify(
+            message_hash,
+            attestation.signature.as_slice(),
+            attestation.public_key.as_slice(),
+        )
+        .map_err(|_| ContractError::InvalidAttestation {})?;
+    if !valid {
+        return Err(ContractError::InvalidAttestation {});
+    }
+
+    let address = attestation.agent.clone();
+    let agent = deps
+        .querier
+        .query_wasm_smart::<AgentResponse>(
+            &config.cra_registry,
+            &agent_cra::query::QueryMsg::GetAgentByAddress {
+                address: address.clone(),
+            },
+        )
+        .ok()
+        .filter(|response| response.agent.agent_type == AgentType::Cra)
+        .or_else(|| {
+            deps.querier
+                .query_wasm_smart::<AgentResponse>(
+                    &config.iba_registry,
+                    &agent_iba::query::QueryMsg::GetAgentByAddress {
+                        address: address.clone(),
+                    },
+                )
+                .ok()
+                .filter(|response| response.agent.agent_type == AgentType::Iba)
+        })
+        .ok_or(ContractError::UnknownAgent {
+            agent: address.to_string(),
+        })?
+        .agent;
+    if agent.status != AgentStatus::Active {
+        return Err(ContractError::AgentNotActive {
+            agent: address.to_string(),
+        });
+    }
+    Ok(())
+}
@@ -1,26 +1,40 @@
+use crate::attestation::{attest_offer, validate_agent_attestation_config};
 use crate::error::ContractError;
 use crate::msg::{
-    ExecuteMsg, InstantiateMsg, MigrateMsg, TeeSetup, TributeMintData, TributeMintExtension,
-    TributeMsg, ZkProof,
+    AgentAttestation, AgentAttestationSetup, ExecuteMsg, InstantiateMsg, MigrateMsg,
+    ObfuscationConfig, OfferBatchItem, OfferBatchMode, OfferBatchResponse, OfferOutcome,
+    PruneResult, TeeSetup, TributeMintBatchItem, TributeMintData, TributeMintExtension, TributeMsg,
+    ZkProof, ZkSetup,
+};
+use crate::obfuscation::{tee_obfuscate, validate_obfuscation_config};
+use crate::poseidon;
+use crate::state::{
+    has_expired_nominal_totals, prune_nominal_totals, Config, NominalTotals, TeeKey,
+    AGENT_DAILY_TRIBUTES, CONFIG, CONFIG_V1, DAILY_NOMINAL_TOTALS, DEFAULT_OFFER_WINDOW_DAYS,
+    L2_ROOTS, LEGACY_USED_DAY, OWNER, PRUNED_BEFORE, TEE_KEYS, USED_CU_HASHES, USED_TRIBUTE_IDS,
 };
-use crate::state::{Config, TeeConfig, CONFIG, OWNER, USED_CU_HASHES, USED_TRIBUTE_IDS};
 use crate::types::TributeInputPayload;
+use crate::zk::{to_field, validate_zk_config, verify_offer};
+use ark_bls12_381::Fr;
 use chacha20poly1305::{
     aead::{Aead, KeyInit},
     ChaCha20Poly1305, Nonce,
 };
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    entry_point, to_json_binary, Addr, Decimal, DepsMut, Empty, Env, Event, HexBinary, MessageInfo,
-    Response, Storage, Timestamp, WasmMsg,
+    to_json_binary, Addr, Api, Decimal, DepsMut, Empty, Env, Event, HexBinary, MessageInfo, Order,
+    QuerierWrapper, Response, StdResult, Storage, Timestamp, WasmMsg,
 };
 use curve25519_dalek::{MontgomeryPoint, Scalar};
 use cw_ownable::Action;
 use hkdf::Hkdf;
-use outbe_utils::amount_utils::normalize_amount;
-use outbe_utils::date::{add_days, normalize_to_date, WorldwideDay};
+use outbe_utils::amount_utils::{normalize_amount, FRACTIONAL};
+use outbe_utils::date::{add_days, normalize_to_date, subtract_days, DateError, WorldwideDay};
 use outbe_utils::denom::{Currency, Denom};
-use outbe_utils::{gen_compound_hash, gen_hash, Base58Binary};
+use outbe_utils::{gen_compound_hash, Base58Binary};
 use sha2::Sha256;
+use std::collections::BTreeSet;
 
 const CONTRACT_NAME: &str = "outbe.net:tribute-factory";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -28,28 +42,28 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     // Validate TEE config if provided
-    let mut cft: Option<TeeConfig> = None;
     if let Some(ref tee_setup) = msg.tee_config {
-        let pubkey = validate_tee_config(tee_setup)?;
-        cft = Some(TeeConfig {
-            private_key: tee_setup.private_key.clone(),
-            public_key: pubkey,
-            salt: tee_setup.salt.clone(),
-        });
+        add_tee_key(deps.storage, &env.block.time, tee_setup)?;
     }
 
+    let zk_config = msg.zk_config.as_ref().map(validate_zk_config).transpose()?;
+
     CONFIG.save(
         deps.storage,
         &Config {
             tribute_address: msg.tribute_address,
-            tee_config: cft,
+            zk_config,
+            agent_attestation: None,
+            offer_window_days: DEFAULT_OFFER_WINDOW_DAYS,
+            obfuscation: Some(ObfuscationConfig::default()),
+            check_draft_id: false,
         },
     )?;
 
@@ -65,13 +79,58 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     msg: MigrateMsg,
 ) -> Result<Response, tribute::error::ContractError> {
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     match msg {
-        MigrateMsg::Migrate {} => Ok(Response::new()),
+        MigrateMsg::Migrate {} => {
+            // moves the single TEE key of the former config into the keyring
+            if let Ok(Some(config)) = CONFIG_V1.may_load(deps.storage) {
+                if let Some(tee_config) = config.tee_config {
+                    TEE_KEYS.save(
+                        deps.storage,
+                        0,
+                        &TeeKey {
+                            key_id: 0,
+                            private_key: tee_config.private_key,
+                            public_key: tee_config.public_key,
+                            salt: tee_config.salt,
+                            activates_at: Timestamp::from_seconds(0),
+                            expires_at: None,
+                        },
+                    )?;
+                }
+                CONFIG.save(
+                    deps.storage,
+                    &Config {
+                        tribute_address: config.tribute_address,
+                        zk_config: config.zk_config,
+                        agent_attestation: None,
+                        offer_window_days: DEFAULT_OFFER_WINDOW_DAYS,
+                        obfuscation: None,
+                        check_draft_id: false,
+                    },
+                )?;
+            }
+            // amounts of factories deployed without obfuscation are rounded from now on
+            if let Some(mut config) = CONFIG.may_load(deps.storage)? {
+                if config.obfuscation.is_none() {
+                    config.obfuscation = Some(ObfuscationConfig::default());
+                    CONFIG.save(deps.storage, &config)?;
+                }
+            }
+            // entries used before the sets were keyed by worldwide day are accounted to
+            // the migration day and pruned once its offer window is closed
+            if !LEGACY_USED_DAY.exists(deps.storage)
+                && (USED_TRIBUTE_IDS.has_legacy(deps.storage)
+                    || USED_CU_HASHES.has_legacy(deps.storage))
+            {
+                LEGACY_USED_DAY.save(deps.storage, &normalize_to_date(&env.block.time))?;
+            }
+            Ok(Response::new())
+        }
     }
 }
 
@@ -96,9 +155,80 @@ fn validate_tee_config(tee_setup: &TeeSetup) -> Result<Base58Binary, ContractErr
     Ok(Base58Binary::from(derived_public_key_bytes))
 }
 
-fn validate_deadline(wwd: WorldwideDay, current_time: &Timestamp) -> Result<(), ContractError> {
+fn add_tee_key(
+    storage: &mut dyn Storage,
+    now: &Timestamp,
+    tee_setup: &TeeSetup,
+) -> Result<(), ContractError> {
+    let public_key = validate_tee_config(tee_setup)?;
+    let key_id = tee_setup.key_id;
+    if TEE_KEYS.has(storage, key_id) {
+        return Err(ContractError::TeeKeyExists { key_id });
+    }
+    TEE_KEYS.save(
+        storage,
+        key_id,
+        &TeeKey {
+            key_id,
+            private_key: tee_setup.private_key.clone(),
+            public_key,
+            salt: tee_setup.salt.clone(),
+            activates_at: tee_setup.activates_at.unwrap_or(*now),
+            expires_at: None,
+        },
+    )?;
+    Ok(())
+}
+
+/// Removes the retired keys whose grace period is over
+fn wipe_expired_tee_keys(storage: &mut dyn Storage, now: &Timestamp) -> StdResult<()> {
+    let expired = TEE_KEYS
+        .range(storage, None, None, Order::Ascending)
+        .filter_map(|item| match item {
+            Ok((key_id, key)) => key
+                .expires_at
+                .filter(|expires_at| expires_at <= now)
+                .map(|_| Ok(key_id)),
+            Err(err) => Some(Err(err)),
+        })
+        .collect::<StdResult<Vec<u32>>>()?;
+    for key_id in expired {
+        TEE_KEYS.remove(storage, key_id);
+    }
+    Ok(())
+}
+
+/// Key an offer is encrypted to, it must be active and not past its grace period
+fn load_tee_key(
+    storage: &dyn Storage,
+    now: &Timestamp,
+    key_id: u32,
+) -> Result<TeeKey, ContractError> {
+    let key = TEE_KEYS
+        .may_load(storage, key_id)?
+        .filter(|key| key.expires_at.is_none_or(|expires_at| expires_at > *now))
+        .ok_or(ContractError::UnknownTeeKey { key_id })?;
+    if key.activates_at > *now {
+        return Err(ContractError::TeeKeyNotActive { key_id });
+    }
+    Ok(key)
+}
+
+/// Last day tributes of the worldwide day can be offered
+pub fn offer_window_closes_on(
+    wwd: WorldwideDay,
+    offer_window_days: u32,
+) -> Result<WorldwideDay, DateError> {
+    add_days(wwd, offer_window_days)
+}
+
+fn validate_deadline(
+    wwd: WorldwideDay,
+    current_time: &Timestamp,
+    offer_window_days: u32,
+) -> Result<(), ContractError> {
     let now_wwd = normalize_to_date(current_time);
-    let metadosis_deadline = add_days(wwd, 3)?;
+    let metadosis_deadline = offer_window_closes_on(wwd, offer_window_days)?;
 
     if metadosis_deadline < now_wwd {
         return Err(ContractError::ClosedOfferWindow {});
@@ -106,6 +236,25 @@ fn validate_deadline(wwd: WorldwideDay, current_time: &Timestamp) -> Result<(),
     Ok(())
 }
 
+/// Fails with `ClosedOfferWindow` once tributes of the worldwide day can no longer be offered.
+/// Offers and the `OfferWindow` query both use it, metadosis runs the day after `closes_on`.
+pub(crate) fn check_offer_window(
+    storage: &dyn Storage,
+    wwd: WorldwideDay,
+    current_time: &Timestamp,
+    offer_window_days: u32,
+) -> Result<(), ContractError> {
+    validate_deadline(wwd, current_time, offer_window_days)?;
+    // the used entries of pruned days are gone
+    if PRUNED_BEFORE
+        .may_load(storage)?
+        .is_some_and(|pruned_before| wwd < pruned_before)
+    {
+        return Err(ContractError::ClosedOfferWindow {});
+    }
+    Ok(())
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -113,11 +262,18 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    wipe_expired_tee_keys(deps.storage, &env.block.time)?;
+
     match msg {
         ExecuteMsg::UpdateConfig {
             new_owner,
             new_tribute_address,
             new_tee_config,
+            new_zk_config,
+            new_agent_attestation,
+            new_offer_window_days,
+            new_obfuscation,
+            new_check_draft_id,
         } => execute_update_config(
             deps,
             env,
@@ -125,12 +281,23 @@ pub fn execute(
             new_owner,
             new_tribute_address,
             new_tee_config,
+            new_zk_config,
+            new_agent_attestation,
+            new_offer_window_days,
+            new_obfuscation,
+            new_check_draft_id,
         ),
+        ExecuteMsg::RetireTeeKey {
+            key_id,
+            grace_period_seconds,
+        } => execute_retire_tee_key(deps, env, info, key_id, grace_period_seconds),
         ExecuteMsg::Offer {
             cipher_text,
             nonce,
             ephemeral_pubkey,
+            key_id,
             zk_proof,
+            attestation,
             #[cfg(feature = "demo")]
             tribute_owner_l1,
         } => execute_offer(
@@ -140,7 +307,9 @@ pub fn execute(
             cipher_text,
             nonce,
             ephemeral_pubkey,
+            key_id,
             zk_proof,
+            attestation,
             #[cfg(feature = "demo")]
             tribute_owner_l1,
         ),
@@ -150,18 +319,106 @@ pub fn execute(
             zk_proof,
             tribute_owner_l1,
         } => execute_offer_insecure(deps, env, info, tribute_input, zk_proof, tribute_owner_l1),
+        ExecuteMsg::OfferBatch { offers, mode } => {
+            execute_offer_batch(deps, env, info, offers, mode)
+        }
+        ExecuteMsg::SetL2Root {
+            worldwide_day,
+            merkle_root,
+        } => execute_set_l2_root(deps, info, worldwide_day, merkle_root),
+        ExecuteMsg::Prune { limit } => execute_prune(deps, env, info, limit),
+        #[cfg(feature = "demo")]
         ExecuteMsg::BurnAll {} => execute_burn_all(deps, env, info),
     }
 }
 
+#[cfg(feature = "demo")]
 fn execute_burn_all(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
 ) -> Result<Response, ContractError> {
+    OWNER.assert_owner(deps.storage, &info.sender)?;
     USED_CU_HASHES.clear(deps.storage);
     USED_TRIBUTE_IDS.clear(deps.storage);
-    Ok(Response::new())
+    LEGACY_USED_DAY.remove(deps.storage);
+    Ok(Response::new()
+        .add_attribute("action", "tribute-factory::burn_all")
+        .add_event(
+            Event::new("tribute-factory::burn_all")
+                .add_attribute("sender", info.sender.to_string()),
+        ))
+}
+
+/// Default number of used entries removed by a single `Prune` call
+const DEFAULT_PRUNE_LIMIT: u32 = 500;
+/// Number of used entries removed along with each offer
+const OFFER_PRUNE_LIMIT: u32 = 10;
+/// Days the nominal totals of a day are kept after its offer window is closed, for metadosis
+/// to run the day
+const NOMINAL_TOTALS_RETENTION_DAYS: u32 = 30;
+
+fn execute_prune(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    // only entries of closed offer windows are removed, so anyone can prune
+    let config = CONFIG.load(deps.storage)?;
+    let result = prune_used_state(
+        deps.storage,
+        &env,
+        config.offer_window_days,
+        limit.unwrap_or(DEFAULT_PRUNE_LIMIT),
+    )?;
+
+    Ok(Response::new()
+        .set_data(to_json_binary(&result)?)
+        .add_attribute("action", "tribute-factory::prune")
+        .add_event(
+            Event::new("tribute-factory::prune")
+                .add_attribute("sender", info.sender.to_string())
+                .add_attribute("expired_before", result.expired_before.to_string())
+                .add_attribute("pruned_count", result.pruned.to_string())
+                .add_attribute("finished", result.finished.to_string()),
+        ))
+}
+
+/// Removes at most `limit` used draft ids of days whose offer window is closed. Offers of these
+/// days are rejected from now on, even if the offer window is extended later, so pruning never
+/// lets a draft id be used twice. Consumption unit hashes are kept: nothing binds a hash to the
+/// day it was offered for, so a pruned hash could be offered again under a later day.
+/// Nominal totals are removed `NOMINAL_TOTALS_RETENTION_DAYS` later.
+fn prune_used_state(
+    storage: &mut dyn Storage,
+    env: &Env,
+    offer_window_days: u32,
+    limit: u32,
+) -> Result<PruneResult, ContractError> {
+    let limit = limit.max(1) as usize;
+    let expired_before = subtract_days(normalize_to_date(&env.block.time), offer_window_days)?;
+    let pruned_before = PRUNED_BEFORE.may_load(storage)?.unwrap_or_default();
+    if expired_before > pruned_before {
+        PRUNED_BEFORE.save(storage, &expired_before)?;
+    }
+    // days pruned before the offer window was extended stay closed
+    let expired_before = expired_before.max(pruned_before);
+
+    let totals_expired_before = subtract_days(expired_before, NOMINAL_TOTALS_RETENTION_DAYS)?;
+    let mut pruned = USED_TRIBUTE_IDS.prune(storage, expired_before, limit)?;
+    pruned += prune_nominal_totals(storage, totals_expired_before, limit - pruned)?;
+    let finished = !USED_TRIBUTE_IDS.has_expired(storage, expired_before)?
+        && !has_expired_nominal_totals(storage, totals_expired_before);
+    if finished && !USED_TRIBUTE_IDS.has_legacy(storage) && !USED_CU_HASHES.has_legacy(storage) {
+        LEGACY_USED_DAY.remove(storage);
+    }
+
+    Ok(PruneResult {
+        expired_before,
+        pruned: pruned as u64,
+        finished,
+    })
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -172,15 +429,25 @@ fn execute_offer(
     cipher_text: Base58Binary,
     nonce: Base58Binary,
     ephemeral_pubkey: Base58Binary,
+    key_id: u32,
     zk_proof: ZkProof,
+    attestation: Option<AgentAttestation>,
     #[cfg(feature = "demo")] tribute_owner_l1: Option<Addr>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    let tee_config = config.tee_config.ok_or(ContractError::NotInitialized {})?;
+    let agent = attest_offer(
+        deps.as_ref(),
+        &env,
+        config.agent_attestation.as_ref(),
+        attestation.as_ref(),
+        &cipher_text,
+        &nonce,
+        &ephemeral_pubkey,
+    )?;
+    let tee_key = load_tee_key(deps.storage, &env.block.time, key_id)?;
 
     // Decrypt the tribute input using ECDHE
-    let tribute_input =
-        decrypt_tribute_input(&cipher_text, &nonce, &ephemeral_pubkey, &tee_config)?;
+    let tribute_input = decrypt_tribute_input(&cipher_text, &nonce, &ephemeral_pubkey, &tee_key)?;
 
     // Process the decrypted tribute input (same logic as OfferInsecure)
     execute_offer_logic(
@@ -189,6 +456,7 @@ fn execute_offer(
         info,
         tribute_input,
         zk_proof,
+        agent,
         #[cfg(feature = "demo")]
         tribute_owner_l1,
     )
@@ -198,9 +466,9 @@ pub(crate) fn decrypt_tribute_input(
     cipher_text: &Base58Binary,
     nonce: &Base58Binary,
     ephemeral_pubkey: &Base58Binary,
-    tee_config: &TeeConfig,
+    tee_key: &TeeKey,
 ) -> Result<TributeInputPayload, ContractError> {
-    let private_key_array: [u8; 32] = tee_config
+    let private_key_array: [u8; 32] = tee_key
         .private_key
         .as_slice()
         .try_into()
@@ -221,7 +489,7 @@ pub(crate) fn decrypt_tribute_input(
     let shared_secret = ephemeral_public_key * private_key;
 
     // Use HKDF to derive an encryption key from shared secret and salt
-    let hk = Hkdf::<Sha256>::new(Some(tee_config.salt.as_slice()), &shared_secret.to_bytes());
+    let hk = Hkdf::<Sha256>::new(Some(tee_key.salt.as_slice()), &shared_secret.to_bytes());
     let mut encryption_key = [0u8; 32];
     hk.expand(b"tribute-factory-encryption", &mut encryption_key)
         .map_err(|_| ContractError::DecryptionFailed {})?;
@@ -241,6 +509,7 @@ pub(crate) fn decrypt_tribute_input(
     Ok(tribute_input)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn execute_update_config(
     deps: DepsMut,
     env: Env,
@@ -248,21 +517,47 @@ fn execute_update_config(
     new_owner: Option<Addr>,
     new_tribute_address: Option<Addr>,
     new_tee_config: Option<TeeSetup>,
+    new_zk_config: Option<ZkSetup>,
+    new_agent_attestation: Option<AgentAttestationSetup>,
+    new_offer_window_days: Option<u32>,
+    new_obfuscation: Option<ObfuscationConfig>,
+    new_check_draft_id: Option<bool>,
 ) -> Result<Response, ContractError> {
     OWNER.assert_owner(deps.storage, &info.sender)?;
 
-    if new_tribute_address.is_some() || new_tee_config.is_some() {
+    if let Some(new_tee_config) = new_tee_config {
+        add_tee_key(deps.storage, &env.block.time, &new_tee_config)?;
+    }
+
+    if new_tribute_address.is_some()
+        || new_zk_config.is_some()
+        || new_agent_attestation.is_some()
+        || new_offer_window_days.is_some()
+        || new_obfuscation.is_some()
+        || new_check_draft_id.is_some()
+    {
         let mut config = CONFIG.load(deps.storage)?;
         if let Some(new_tribute_address) = new_tribute_address {
             config.tribute_address = Some(new_tribute_address)
         }
-        if let Some(new_tee_config) = new_tee_config {
-            let pubkey = validate_tee_config(&new_tee_config)?;
-            config.tee_config = Some(TeeConfig {
-                private_key: new_tee_config.private_key,
-                public_key: pubkey,
-                salt: new_tee_config.salt,
-            })
+        if let Some(new_zk_config) = new_zk_config {
+            config.zk_config = Some(validate_zk_config(&new_zk_config)?)
+        }
+        if let Some(new_agent_attestation) = new_agent_attestation {
+            config.agent_attestation = Some(validate_agent_attestation_config(
+                deps.api,
+                &new_agent_attestation,
+            )?)
+        }
+        if let Some(new_offer_window_days) = new_offer_window_days {
+            config.offer_window_days = new_offer_window_days
+        }
+        if let Some(new_obfuscation) = new_obfuscation {
+            validate_obfuscation_config(&new_obfuscation)?;
+            config.obfuscation = Some(new_obfuscation)
+        }
+        if let Some(new_check_draft_id) = new_check_draft_id {
+            config.check_draft_id = new_check_draft_id
         }
         CONFIG.save(deps.storage, &config)?;
     }
@@ -284,6 +579,53 @@ fn execute_update_config(
         .add_event(Event::new("tribute-factory::update_config")))
 }
 
+fn execute_set_l2_root(
+    deps: DepsMut,
+    info: MessageInfo,
+    worldwide_day: WorldwideDay,
+    merkle_root: Base58Binary,
+) -> Result<Response, ContractError> {
+    OWNER.assert_owner(deps.storage, &info.sender)?;
+    L2_ROOTS.save(deps.storage, worldwide_day, &merkle_root)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "tribute-factory::set_l2_root")
+        .add_event(
+            Event::new("tribute-factory::set_l2_root")
+                .add_attribute("worldwide_day", worldwide_day.to_string())
+                .add_attribute("merkle_root", merkle_root.to_base58()),
+        ))
+}
+
+fn execute_retire_tee_key(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    key_id: u32,
+    grace_period_seconds: u64,
+) -> Result<Response, ContractError> {
+    OWNER.assert_owner(deps.storage, &info.sender)?;
+
+    let mut key = TEE_KEYS
+        .may_load(deps.storage, key_id)?
+        .ok_or(ContractError::UnknownTeeKey { key_id })?;
+    let expires_at = env.block.time.plus_seconds(grace_period_seconds);
+    key.expires_at = Some(expires_at);
+    if grace_period_seconds == 0 {
+        TEE_KEYS.remove(deps.storage, key_id);
+    } else {
+        TEE_KEYS.save(deps.storage, key_id, &key)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "tribute-factory::retire_tee_key")
+        .add_event(
+            Event::new("tribute-factory::retire_tee_key")
+                .add_attribute("key_id", key_id.to_string())
+                .add_attribute("expires_at", expires_at.seconds().to_string()),
+        ))
+}
+
 #[cfg(feature = "demo")]
 fn execute_offer_insecure(
     deps: DepsMut,
@@ -293,171 +635,405 @@ fn execute_offer_insecure(
     zk_proof: ZkProof,
     tribute_owner_l1: Option<Addr>,
 ) -> Result<Response, ContractError> {
-    execute_offer_logic(deps, env, info, tribute_input, zk_proof, tribute_owner_l1)
+    // raw data cannot be attested
+    let config = CONFIG.load(deps.storage)?;
+    if config
+        .agent_attestation
+        .is_some_and(|agent_attestation| agent_attestation.required)
+    {
+        return Err(ContractError::AttestationRequired {});
+    }
+    execute_offer_logic(
+        deps,
+        env,
+        info,
+        tribute_input,
+        zk_proof,
+        None,
+        tribute_owner_l1,
+    )
 }
 
 fn execute_offer_logic(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     tribute_input: TributeInputPayload,
-    _zk_proof: ZkProof,
+    zk_proof: ZkProof,
+    agent: Option<Addr>,
     #[cfg(feature = "demo")] tribute_owner_l1: Option<Addr>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let tribute_address = config
         .tribute_address
+        .clone()
+        .ok_or(ContractError::NotInitialized {})?;
+
+    let tribute_owner = tribute_owner(
+        &info,
+        #[cfg(feature = "demo")]
+        tribute_owner_l1,
+    );
+    let (mint, event) = prepare_offer(
+        deps.storage,
+        deps.api,
+        &env,
+        &config,
+        tribute_owner,
+        tribute_input,
+        zk_proof,
+        agent,
+        None,
+    )?;
+    prune_used_state(
+        deps.storage,
+        &env,
+        config.offer_window_days,
+        OFFER_PRUNE_LIMIT,
+    )?;
+
+    let msg = WasmMsg::Execute {
+        contract_addr: tribute_address.to_string(),
+        msg: to_json_binary(&TributeMsg::Mint {
+            token_id: mint.token_id,
+            owner: mint.owner,
+            token_uri: mint.token_uri,
+            extension: Box::new(mint.extension),
+        })?,
+        funds: vec![],
+    };
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("action", "tribute-factory::offer")
+        .add_event(event))
+}
+
+/// Max number of offers of an `OfferBatch`
+const MAX_OFFER_BATCH_SIZE: usize = 100;
+
+fn execute_offer_batch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    offers: Vec<OfferBatchItem>,
+    mode: OfferBatchMode,
+) -> Result<Response, ContractError> {
+    if offers.is_empty() {
+        return Err(ContractError::EmptyBatch {});
+    }
+    if offers.len() > MAX_OFFER_BATCH_SIZE {
+        return Err(ContractError::BatchTooLarge {
+            max: MAX_OFFER_BATCH_SIZE as u32,
+        });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let tribute_address = config
+        .tribute_address
+        .clone()
         .ok_or(ContractError::NotInitialized {})?;
 
+    // skipped offers must not fail the `MintBatch` of the accepted ones
+    let mint_check = match mode {
+        OfferBatchMode::AllOrNothing => None,
+        OfferBatchMode::BestEffort => Some(deps.querier),
+    };
+    let mut tokens = Vec::with_capacity(offers.len());
+    let mut outcomes = Vec::with_capacity(offers.len());
+    let mut events = Vec::with_capacity(offers.len());
+    for (index, offer) in offers.into_iter().enumerate() {
+        let tribute_owner = tribute_owner(
+            &info,
+            #[cfg(feature = "demo")]
+            offer.tribute_owner_l1,
+        );
+        // an offer writes to the storage only once it is accepted, so the skipped offers of a
+        // best effort batch leave no state behind
+        let prepared = attest_offer(
+            deps.as_ref(),
+            &env,
+            config.agent_attestation.as_ref(),
+            offer.attestation.as_ref(),
+            &offer.cipher_text,
+            &offer.nonce,
+            &offer.ephemeral_pubkey,
+        )
+        .and_then(|agent| {
+            let tee_key = load_tee_key(deps.storage, &env.block.time, offer.key_id)?;
+            decrypt_tribute_input(
+                &offer.cipher_text,
+                &offer.nonce,
+                &offer.ephemeral_pubkey,
+                &tee_key,
+            )
+            .map(|tribute_input| (agent, tribute_input))
+        })
+        .and_then(|(agent, tribute_input)| {
+            prepare_offer(
+                deps.storage,
+                deps.api,
+                &env,
+                &config,
+                tribute_owner,
+                tribute_input,
+                offer.zk_proof,
+                agent,
+                mint_check,
+            )
+        });
+        match (prepared, &mode) {
+            (Ok((mint, event)), _) => {
+                outcomes.push(OfferOutcome::Accepted {
+                    tribute_id: mint.token_id.clone(),
+                });
+                tokens.push(mint);
+                events.push(event);
+            }
+            (Err(err), OfferBatchMode::AllOrNothing) => {
+                return Err(ContractError::BatchOfferFailed {
+                    index: index as u32,
+                    error: err.to_string(),
+                })
+            }
+            (Err(err), OfferBatchMode::BestEffort) => outcomes.push(OfferOutcome::Rejected {
+                error: err.to_string(),
+            }),
+        }
+    }
+
+    prune_used_state(
+        deps.storage,
+        &env,
+        config.offer_window_days,
+        OFFER_PRUNE_LIMIT,
+    )?;
+
+    let accepted = tokens.len();
+    let rejected = outcomes.len() - accepted;
+    let mut response = Response::new();
+    if !tokens.is_empty() {
+        response = response.add_message(WasmMsg::Execute {
+            contract_addr: tribute_address.to_string(),
+            msg: to_json_binary(&TributeMsg::MintBatch { tokens })?,
+            funds: vec![],
+        });
+    }
+    Ok(response
+        .set_data(to_json_binary(&OfferBatchResponse { outcomes })?)
+        .add_attribute("action", "tribute-factory::offer_batch")
+        .add_events(events)
+        .add_event(
+            Event::new("tribute-factory::offer_batch")
+                .add_attribute("accepted", accepted.to_string())
+                .add_attribute("rejected", rejected.to_string()),
+        ))
+}
+
+fn tribute_owner(
+    info: &MessageInfo,
+    #[cfg(feature = "demo")] tribute_owner_l1: Option<Addr>,
+) -> Addr {
+    #[cfg(feature = "demo")]
+    {
+        tribute_owner_l1.unwrap_or(info.sender.clone())
+    }
+    #[cfg(not(feature = "demo"))]
+    {
+        info.sender.clone()
+    }
+}
+
+/// Validates an offer, marks its draft id and consumption units as used and returns
+/// the Tribute to mint with the offer event. Nothing is written if the offer is rejected.
+/// With `mint_check` the Tribute is also checked by the tribute contract with `CheckMint`.
+#[allow(clippy::too_many_arguments)]
+fn prepare_offer(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    config: &Config,
+    tribute_owner: Addr,
+    tribute_input: TributeInputPayload,
+    zk_proof: ZkProof,
+    agent: Option<Addr>,
+    mint_check: Option<QuerierWrapper>,
+) -> Result<(TributeMintBatchItem, Event), ContractError> {
     // validate
     if tribute_input.cu_hashes.is_empty() {
         return Err(ContractError::CUEmpty {});
     }
-
-    let tribute_owner = {
-        #[cfg(feature = "demo")]
-        {
-            tribute_owner_l1.unwrap_or(info.sender.clone())
+    match config.zk_config {
+        Some(ref zk_config) => {
+            let l2_root = L2_ROOTS
+                .may_load(storage, tribute_input.worldwide_day)?
+                .ok_or(ContractError::UnknownMerkleRoot {})?;
+            verify_offer(api, zk_config, &l2_root, &zk_proof, &tribute_input)?
         }
+        // TODO: proofs are not required for demo until the L2 circuit is deployed
+        #[cfg(feature = "demo")]
+        None => {}
         #[cfg(not(feature = "demo"))]
-        {
-            info.sender.clone()
-        }
-    };
+        None => return Err(ContractError::NotInitialized {}),
+    }
+
     let wwd = tribute_input.worldwide_day;
     let currency: Currency = Currency::try_from(tribute_input.settlement_currency)?;
 
-    validate_deadline(wwd, &_env.block.time)?;
-
-    let tribute = tee_obfuscate(tribute_input.clone())?;
-    update_used_state(deps.storage, &tribute)?;
+    check_offer_window(storage, wwd, &env.block.time, config.offer_window_days)?;
 
     let tribute_id = generate_tribute_id(
-        &tribute.tribute_draft_id,
+        &tribute_input.tribute_draft_id,
         &tribute_owner,
         tribute_input.worldwide_day,
     );
 
     let settlement_amount = normalize_amount(
-        tribute.settlement_base_amount,
-        tribute.settlement_atto_amount,
+        tribute_input.settlement_base_amount,
+        tribute_input.settlement_atto_amount,
+    )?;
+    let nominal_amount = normalize_amount(
+        tribute_input.nominal_base_amount,
+        tribute_input.nominal_atto_amount,
+    )?;
+    let tribute_price = Decimal::checked_from_ratio(settlement_amount, nominal_amount)
+        .map_err(|_| ContractError::InvalidPayload {})?;
+    let amounts = tee_obfuscate(
+        config.obfuscation.as_ref(),
+        &tribute_input,
+        settlement_amount,
+        nominal_amount,
+        tribute_price,
     )?;
-    let nominal_amount =
-        normalize_amount(tribute.nominal_base_amount, tribute.nominal_atto_amount)?;
-    // todo use safe convertion
-    let tribute_price = Decimal::from_atomics(settlement_amount, 18).unwrap()
-        / Decimal::from_atomics(nominal_amount, 18).unwrap();
-
-    println!("settlement_amount {}", settlement_amount);
-    println!("nominal_amount {}", nominal_amount);
-    println!("tribute_price {}", tribute_price);
 
-    let msg = WasmMsg::Execute {
-        contract_addr: tribute_address.to_string(),
-        msg: to_json_binary(&TributeMsg::Mint {
-            token_id: tribute_id.to_string(),
-            owner: tribute_owner.to_string(),
-            token_uri: None,
-            extension: Box::new(TributeMintExtension {
-                data: TributeMintData {
-                    tribute_id: tribute_id.to_string(),
-                    worldwide_day: wwd,
-                    owner: tribute_owner.to_string(),
-                    settlement_amount_minor: settlement_amount,
-                    settlement_currency: Denom::Fiat(currency),
-                    nominal_amount_minor: nominal_amount,
-                    nominal_price: tribute_price,
-                },
-            }),
-        })?,
-        funds: vec![],
+    let mint = TributeMintBatchItem {
+        token_id: tribute_id.to_string(),
+        owner: tribute_owner.to_string(),
+        token_uri: None,
+        extension: TributeMintExtension {
+            data: TributeMintData {
+                tribute_id: tribute_id.to_string(),
+                worldwide_day: wwd,
+                owner: tribute_owner.to_string(),
+                settlement_amount_minor: amounts.settlement_amount_minor,
+                settlement_currency: Denom::Fiat(currency),
+                nominal_amount_minor: amounts.nominal_amount_minor,
+                nominal_price: amounts.nominal_price,
+                vector_id: tribute_input.vector_id,
+                amounts_commitment: amounts.commitment.clone(),
+            },
+        },
+        reason: None,
     };
+    if let (Some(querier), Some(tribute_address)) = (mint_check, &config.tribute_address) {
+        let checked: tribute::query::CheckMintResponse = querier.query_wasm_smart(
+            tribute_address,
+            &tribute::query::QueryMsg::CheckMint {
+                data: mint.extension.data.clone(),
+            },
+        )?;
+        if let Some(error) = checked.error {
+            return Err(ContractError::MintRejected { error });
+        }
+    }
 
-    Ok(Response::new()
-        .add_message(msg)
-        .add_attribute("action", "tribute-factory::offer")
-        .add_event(
-            Event::new("tribute-factory::offer")
-                .add_attribute(
-                    "tribute_draft_id",
-                    tribute_input.tribute_draft_id.to_base58(),
-                )
-                .add_attribute("tribute_id", tribute_id.to_string())
-                .add_attribute("owner", tribute_input.owner.to_base58())
-                .add_attribute("worldwide_day", tribute_input.worldwide_day.to_string())
-                .add_attribute(
-                    "settlement_currency",
-                    tribute_input.settlement_currency.to_string(),
-                )
-                .add_attribute(
-                    "settlement_base_amount",
-                    tribute_input.settlement_base_amount.to_string(),
-                )
-                .add_attribute(
-                    "settlement_atto_amount",
-                    tribute_input.settlement_atto_amount.to_string(),
-                )
-                .add_attribute(
-                    "nominal_base_amount",
-                    tribute_input.nominal_base_amount.to_string(),
-                )
-                .add_attribute(
-                    "nominal_atto_amount",
-                    tribute_input.nominal_atto_amount.to_string(),
-                ),
-        ))
-}
-
-fn tee_obfuscate(tribute_input: TributeInputPayload) -> Result<TributeInputPayload, ContractError> {
-    // TODO implement tee obfuscation
-
-    Ok(tribute_input)
+    update_used_state(storage, &tribute_input, config.check_draft_id)?;
+    if let Some(ref agent) = agent {
+        AGENT_DAILY_TRIBUTES.update(storage, (agent, wwd), |count| -> StdResult<u64> {
+            Ok(count.unwrap_or_default() + 1)
+        })?;
+    }
+    DAILY_NOMINAL_TOTALS.update(storage, wwd, |totals| -> StdResult<NominalTotals> {
+        let totals = totals.unwrap_or_default();
+        Ok(NominalTotals {
+            exact_minor: totals.exact_minor.checked_add(amounts.totals.exact_minor)?,
+            published_minor: totals
+                .published_minor
+                .checked_add(amounts.totals.published_minor)?,
+        })
+    })?;
+
+    let mut event = Event::new("tribute-factory::offer")
+        .add_attribute(
+            "tribute_draft_id",
+            tribute_input.tribute_draft_id.to_base58(),
+        )
+        .add_attribute("tribute_id", tribute_id.to_string())
+        .add_attribute("worldwide_day", tribute_input.worldwide_day.to_string())
+        .add_attribute(
+            "settlement_currency",
+            tribute_input.settlement_currency.to_string(),
+        )
+        .add_attribute(
+            "settlement_base_amount",
+            (amounts.settlement_amount_minor / FRACTIONAL).to_string(),
+        )
+        .add_attribute(
+            "settlement_atto_amount",
+            (amounts.settlement_amount_minor % FRACTIONAL).to_string(),
+        )
+        .add_attribute(
+            "nominal_base_amount",
+            (amounts.nominal_amount_minor / FRACTIONAL).to_string(),
+        )
+        .add_attribute(
+            "nominal_atto_amount",
+            (amounts.nominal_amount_minor % FRACTIONAL).to_string(),
+        );
+    if let Some(commitment) = amounts.commitment {
+        event = event.add_attribute("amounts_commitment", commitment.to_string());
+    }
+    if let Some(agent) = agent {
+        event = event.add_attribute("agent", agent.to_string());
+    }
+    Ok((mint, event))
 }
 
 fn update_used_state(
     storage: &mut dyn Storage,
     tribute: &TributeInputPayload,
+    check_draft_id: bool,
 ) -> Result<Empty, ContractError> {
-    // TODO: temporary disable validation for demo such as missing Poseidon hashing implementation
-    #[cfg(not(feature = "demo"))]
+    // Validate that provided draft ID matches tribute_draft_id
+    if check_draft_id
+        && tribute.tribute_draft_id
+            != generate_tribute_draft_id_hash(&tribute.owner, tribute.worldwide_day)?
     {
-        let tribute_draft_id = crate::contract::generate_tribute_draft_id_hash(
-            &tribute.owner,
-            &tribute.worldwide_day,
-        )?;
-        // Validate that provided draft ID matches tribute_draft_id
-        if tribute.tribute_draft_id != tribute_draft_id {
-            return Err(ContractError::InvalidDraftId {});
-        }
+        return Err(ContractError::InvalidDraftId {});
     }
 
-    USED_TRIBUTE_IDS.update(
-        storage,
-        tribute.tribute_draft_id.to_base58(),
-        |old| match old {
-            Some(_) => Err(ContractError::IdAlreadyExists {}),
-            None => Ok(Empty::default()),
-        },
-    )?;
+    // everything is checked before writing, so a rejected offer leaves no state behind
+    let tribute_draft_id = tribute.tribute_draft_id.to_base58();
+    if USED_TRIBUTE_IDS
+        .used_on(storage, &tribute_draft_id)?
+        .is_some()
+    {
+        return Err(ContractError::IdAlreadyExists {});
+    }
+    let mut cu_hashes = BTreeSet::new();
+    for cu_hash in tribute.cu_hashes.iter() {
+        let cu_hash = cu_hash.to_base58();
+        if USED_CU_HASHES.used_on(storage, &cu_hash)?.is_some() || !cu_hashes.insert(cu_hash) {
+            return Err(ContractError::CUAlreadyExists {});
+        }
+    }
 
-    for cu_hash in tribute.cu_hashes.clone() {
-        USED_CU_HASHES.update(storage, cu_hash.to_base58(), |old| match old {
-            Some(_) => Err(ContractError::CUAlreadyExists {}),
-            None => Ok(Empty::default()),
-        })?;
+    // the draft id is derived from the day, so it is kept only until the offer window of the
+    // day is closed; consumption unit hashes are kept for good
+    USED_TRIBUTE_IDS.save(storage, &tribute_draft_id, tribute.worldwide_day)?;
+    for cu_hash in cu_hashes {
+        USED_CU_HASHES.save(storage, &cu_hash, tribute.worldwide_day)?;
     }
     Ok(Empty::default())
 }
 
+/// `poseidon(owner, worldwide_day)` over the field of the L2 circuit, see `poseidon::hash2`.
+/// The owner is reduced modulo the field order and the id is the big endian field element.
 pub fn generate_tribute_draft_id_hash(
     owner: &Base58Binary,
     worldwide_day: WorldwideDay,
 ) -> Result<Base58Binary, ContractError> {
-    let hex_bin = gen_hash(vec![
-        owner.as_slice(),
-        worldwide_day.to_le_bytes().as_slice(),
-    ]);
-    Ok(Base58Binary::from(hex_bin.as_slice()))
+    let hash = poseidon::hash2(to_field(owner.as_slice()), Fr::from(worldwide_day));
+    Ok(Base58Binary::from(poseidon::to_bytes(&hash)))
 }
 
 fn generate_tribute_id(
@@ -478,11 +1054,23 @@ fn generate_tribute_id(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::msg::ZkProofPublicData;
-    use crate::test_ecdhe::generate_keypair;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env};
-    use cosmwasm_std::{Uint128, Uint64};
+    use crate::attestation::attestation_hash;
+    use crate::msg::{AmountRounding, ZkProofPublicData};
+    use crate::obfuscation::amounts_commitment;
+    use crate::query::{
+        query, AgentTributesResponse, EncryptionInfoResponse, NominalTotalsResponse,
+        OfferWindowResponse, QueryMsg, UsedResponse,
+    };
+    use crate::test_ecdhe::{encrypt_tribute_input, generate_keypair};
+    use agent_common::msg::AgentResponse;
+    use agent_common::types::{Agent, AgentExt, AgentStatus, AgentType};
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::{
+        from_json, CanonicalAddr, ContractResult, Deps, SystemResult, Uint128, Uint64, WasmQuery,
+    };
     use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+    use cw_storage_plus::Map;
+    use ripemd::{Digest, Ripemd160};
 
     fn tribute_contract() -> Box<dyn Contract<Empty>> {
         let contract = ContractWrapper::new(
@@ -531,10 +1119,10 @@ mod tests {
                 &tribute::msg::InstantiateMsg {
                     name: "tribute".to_string(),
                     symbol: "tt".to_string(),
-                    collection_info_extension: tribute::msg::TributeCollectionExtension {
-                        native_token: Denom::Native("coen".to_string()),
-                        price_oracle: oracle.clone(),
-                    },
+                    collection_info_extension: tribute::msg::TributeCollectionExtension::new(
+                        Denom::Native("coen".to_string()),
+                        oracle.clone(),
+                    ),
                     minter: Some(factory_addr.to_string()),
                     burner: None,
                     creator: None,
@@ -553,6 +1141,11 @@ mod tests {
                 new_tribute_address: Some(tribute_addr.clone()),
                 new_owner: None,
                 new_tee_config: None,
+                new_zk_config: None,
+                new_agent_attestation: None,
+                new_offer_window_days: None,
+                new_obfuscation: None,
+                new_check_draft_id: None,
             },
             &[],
         )
@@ -573,6 +1166,8 @@ mod tests {
             nominal_base_amount: Uint64::new(1000),
             nominal_atto_amount: Uint128::zero(),
             owner,
+            vector_id: None,
+            amounts_blinding: None,
         };
 
         // Execute the insecure offer
@@ -601,15 +1196,256 @@ mod tests {
     }
 
     #[test]
-    fn test_unique_tribute_draft_id() {
-        let mut deps = mock_dependencies();
-        let owner = Base58Binary::from("user1".as_bytes());
-        let worldwide_day: WorldwideDay = 20250322;
-        println!(
-            "id {:?}",
-            generate_tribute_draft_id_hash(&owner, worldwide_day)
-        );
-
+    #[cfg(feature = "demo")]
+    fn test_offer_with_zk_proof() {
+        let mut app = App::default();
+        let owner = app.api().addr_make("owner");
+        let sender = app.api().addr_make("sender");
+        let oracle = app.api().addr_make("oracle");
+        let (zk_setup, tribute_input, zk_proof) = crate::test_zk::load_fixtures();
+
+        let factory_code = ContractWrapper::new(execute, instantiate, crate::query::query);
+        let factory_code_id = app.store_code(Box::new(factory_code));
+        let factory_addr = app
+            .instantiate_contract(
+                factory_code_id,
+                owner.clone(),
+                &InstantiateMsg {
+                    owner: Some(owner.clone()),
+                    tee_config: None,
+                    tribute_address: None,
+                    zk_config: Some(zk_setup),
+                },
+                &[],
+                "tribute-factory",
+                None,
+            )
+            .unwrap();
+
+        let tribute_code_id = app.store_code(tribute_contract());
+        let tribute_addr = app
+            .instantiate_contract(
+                tribute_code_id,
+                owner.clone(),
+                &tribute::msg::InstantiateMsg {
+                    name: "tribute".to_string(),
+                    symbol: "tt".to_string(),
+                    collection_info_extension: tribute::msg::TributeCollectionExtension::new(
+                        Denom::Native("coen".to_string()),
+                        oracle.clone(),
+                    ),
+                    minter: Some(factory_addr.to_string()),
+                    burner: None,
+                    creator: None,
+                },
+                &[],
+                "mock-tribute",
+                None,
+            )
+            .unwrap();
+        app.execute_contract(
+            owner.clone(),
+            factory_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                new_tribute_address: Some(tribute_addr.clone()),
+                new_owner: None,
+                new_tee_config: None,
+                new_zk_config: None,
+                new_agent_attestation: None,
+                new_offer_window_days: None,
+                new_obfuscation: None,
+                new_check_draft_id: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        // no trusted L2 root for the day yet
+        let err = app
+            .execute_contract(
+                sender.clone(),
+                factory_addr.clone(),
+                &ExecuteMsg::OfferInsecure {
+                    tribute_input: tribute_input.clone(),
+                    zk_proof: zk_proof.clone(),
+                    tribute_owner_l1: None,
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::UnknownMerkleRoot {}
+        );
+
+        let set_l2_root = ExecuteMsg::SetL2Root {
+            worldwide_day: 20250322,
+            merkle_root: zk_proof.public_data.merkle_root.clone(),
+        };
+        app.execute_contract(sender.clone(), factory_addr.clone(), &set_l2_root, &[])
+            .unwrap_err();
+        app.execute_contract(owner.clone(), factory_addr.clone(), &set_l2_root, &[])
+            .unwrap();
+
+        // proof of other data is rejected
+        let mut tampered_input = tribute_input.clone();
+        tampered_input.nominal_base_amount = Uint64::new(2000);
+        let err = app
+            .execute_contract(
+                sender.clone(),
+                factory_addr.clone(),
+                &ExecuteMsg::OfferInsecure {
+                    tribute_input: tampered_input,
+                    zk_proof: zk_proof.clone(),
+                    tribute_owner_l1: None,
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidZkProof {}
+        );
+
+        app.execute_contract(
+            sender.clone(),
+            factory_addr.clone(),
+            &ExecuteMsg::OfferInsecure {
+                tribute_input,
+                zk_proof,
+                tribute_owner_l1: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let tokens: tribute::query::TributesPageResponse = app
+            .wrap()
+            .query_wasm_smart(
+                tribute_addr,
+                &tribute::query::QueryMsg::TributesByDay {
+                    date: 20250322,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(tokens.tributes.len(), 1);
+    }
+
+    #[test]
+    fn test_zk_rollout_after_migration() {
+        use crate::state::{ConfigV1, TeeConfigV1};
+
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        // 2025-03-22 00:00:00 UTC
+        env.block.time = Timestamp::from_seconds(1_742_601_600);
+        let owner_info = message_info(&deps.api.addr_make("owner"), &[]);
+        let sender_info = message_info(&deps.api.addr_make("sender"), &[]);
+        let tribute_addr = deps.api.addr_make("tribute");
+        let (private_key, public_key) = generate_keypair();
+        let (zk_setup, tribute_input, zk_proof) = crate::test_zk::load_fixtures();
+
+        // a factory deployed before the offers were proven
+        CONFIG_V1
+            .save(
+                deps.as_mut().storage,
+                &ConfigV1 {
+                    tribute_address: Some(tribute_addr),
+                    tee_config: Some(TeeConfigV1 {
+                        private_key: Base58Binary::from(private_key),
+                        public_key: Base58Binary::from(public_key),
+                        salt: Base58Binary::from([1u8; 32]),
+                    }),
+                    zk_config: None,
+                },
+            )
+            .unwrap();
+        let deps_mut = deps.as_mut();
+        OWNER
+            .initialize_owner(
+                deps_mut.storage,
+                deps_mut.api,
+                Some(owner_info.sender.as_str()),
+            )
+            .unwrap();
+        migrate(deps.as_mut(), env.clone(), MigrateMsg::Migrate {}).unwrap();
+
+        let (cipher_text, nonce, ephemeral_pubkey) =
+            encrypt_tribute_input(&tribute_input, &public_key, &[1u8; 32]).unwrap();
+        let offer = ExecuteMsg::Offer {
+            cipher_text,
+            nonce,
+            ephemeral_pubkey,
+            key_id: 0,
+            zk_proof: zk_proof.clone(),
+            attestation: None,
+            #[cfg(feature = "demo")]
+            tribute_owner_l1: None,
+        };
+
+        // offers are rejected until the verification keys are configured
+        #[cfg(not(feature = "demo"))]
+        {
+            let err = execute(
+                deps.as_mut(),
+                env.clone(),
+                sender_info.clone(),
+                offer.clone(),
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::NotInitialized {});
+        }
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            owner_info.clone(),
+            ExecuteMsg::UpdateConfig {
+                new_owner: None,
+                new_tribute_address: None,
+                new_tee_config: None,
+                new_zk_config: Some(zk_setup),
+                new_agent_attestation: None,
+                new_offer_window_days: None,
+                new_obfuscation: None,
+                new_check_draft_id: None,
+            },
+        )
+        .unwrap();
+
+        // and for each day until its L2 root is set
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            sender_info.clone(),
+            offer.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::UnknownMerkleRoot {});
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            owner_info,
+            ExecuteMsg::SetL2Root {
+                worldwide_day: 20250322,
+                merkle_root: zk_proof.public_data.merkle_root,
+            },
+        )
+        .unwrap();
+        execute(deps.as_mut(), env, sender_info, offer).unwrap();
+    }
+
+    #[test]
+    fn test_unique_tribute_draft_id() {
+        let mut deps = mock_dependencies();
+        let owner = Base58Binary::from("user1".as_bytes());
+        let worldwide_day: WorldwideDay = 20250322;
+        println!(
+            "id {:?}",
+            generate_tribute_draft_id_hash(&owner, worldwide_day)
+        );
+
         let tribute = TributeInputPayload {
             tribute_draft_id: generate_tribute_draft_id_hash(&owner, worldwide_day).unwrap(),
             cu_hashes: vec![Base58Binary::from([11; 32])],
@@ -620,13 +1456,15 @@ mod tests {
             nominal_base_amount: Uint64::new(1000),
             nominal_atto_amount: Uint128::zero(),
             owner,
+            vector_id: None,
+            amounts_blinding: None,
         };
 
         // first call
-        update_used_state(deps.as_mut().storage, &tribute).unwrap();
+        update_used_state(deps.as_mut().storage, &tribute, true).unwrap();
 
         // second call - IdAlreadyExists
-        let err = update_used_state(deps.as_mut().storage, &tribute).unwrap_err();
+        let err = update_used_state(deps.as_mut().storage, &tribute, true).unwrap_err();
         assert!(matches!(err, ContractError::IdAlreadyExists {}));
     }
     #[test]
@@ -638,6 +1476,25 @@ mod tests {
         let result2 = generate_tribute_draft_id_hash(&owner, worldwide_day).unwrap();
 
         assert_eq!(result1, result2);
+        // regression vectors computed with ts-examples/lib/poseidon.ts, a port of the same
+        // scheme, so they do not check it against the circuit: only the permutation is checked
+        // against the reference implementation, see `poseidon::tests`
+        assert_eq!(
+            result1.to_base58(),
+            "8ag448PSajpTK1SSzsRfoJ5i2wEfKgrgJYJZoHWhfCZx"
+        );
+        assert_eq!(
+            generate_tribute_draft_id_hash(&Base58Binary::from([1u8; 32]), 20250101)
+                .unwrap()
+                .to_base58(),
+            "6abXDrxiVkDJhHNV2RstzGq1kswYjitqPABdcUngpcXn"
+        );
+        assert_eq!(
+            generate_tribute_draft_id_hash(&Base58Binary::from("user1".as_bytes()), 20250322)
+                .unwrap()
+                .to_base58(),
+            "8H4JzftMw2ViNn1a7XroFzQJcfpwcuQ7taZQ19TJ3LgR"
+        );
     }
 
     #[test]
@@ -658,6 +1515,8 @@ mod tests {
             nominal_base_amount: Uint64::new(1000),
             nominal_atto_amount: Uint128::zero(),
             owner,
+            vector_id: None,
+            amounts_blinding: None,
         };
 
         // Change worldwide_day && tribute_draft_id
@@ -667,15 +1526,22 @@ mod tests {
             generate_tribute_draft_id_hash(&tribute2.owner, tribute2.worldwide_day).unwrap();
 
         // first call
-        update_used_state(deps.as_mut().storage, &tribute1).unwrap();
+        update_used_state(deps.as_mut().storage, &tribute1, true).unwrap();
 
         // second call - CUAlreadyExists
-        let err = update_used_state(deps.as_mut().storage, &tribute2).unwrap_err();
+        let err = update_used_state(deps.as_mut().storage, &tribute2, true).unwrap_err();
         assert!(matches!(err, ContractError::CUAlreadyExists {}));
+        // the draft id of the rejected tribute is not marked as used
+        assert!(USED_TRIBUTE_IDS
+            .used_on(
+                deps.as_ref().storage,
+                &tribute2.tribute_draft_id.to_base58()
+            )
+            .unwrap()
+            .is_none());
     }
 
     #[test]
-    #[ignore] // tmp disable and wait for poseidon hashing
     fn test_invalid_tribute_draft_id() {
         let mut deps = mock_dependencies();
         let tribute = TributeInputPayload {
@@ -688,10 +1554,14 @@ mod tests {
             nominal_base_amount: Uint64::new(1000),
             nominal_atto_amount: Uint128::zero(),
             owner: Base58Binary::from("user1".as_bytes()),
+            vector_id: None,
+            amounts_blinding: None,
         };
 
-        let err = update_used_state(deps.as_mut().storage, &tribute).unwrap_err();
+        let err = update_used_state(deps.as_mut().storage, &tribute, true).unwrap_err();
         assert!(matches!(err, ContractError::InvalidDraftId {}));
+        // the derivation is only checked once enabled in the config
+        update_used_state(deps.as_mut().storage, &tribute, false).unwrap();
     }
 
     #[test]
@@ -721,6 +1591,8 @@ mod tests {
             nominal_base_amount: Uint64::new(500),
             nominal_atto_amount: Uint128::zero(),
             owner: Base58Binary::from("test_owner".as_bytes()),
+            vector_id: None,
+            amounts_blinding: None,
         };
 
         // Encrypt tribute input (client side simulation)
@@ -751,17 +1623,20 @@ mod tests {
         let ciphertext = cipher.encrypt(nonce, plaintext.as_ref()).unwrap();
 
         // Test decryption
-        let tee_config = TeeConfig {
+        let tee_key = TeeKey {
+            key_id: 0,
             private_key: Base58Binary::from(private_key_bytes),
             public_key: Base58Binary::from(public_key_bytes),
             salt: Base58Binary::from(salt_bytes),
+            activates_at: Timestamp::from_seconds(0),
+            expires_at: None,
         };
 
         let decrypted_input = decrypt_tribute_input(
             &Base58Binary::from(ciphertext),
             &Base58Binary::from(nonce_bytes),
             &Base58Binary::from(ephemeral_public_bytes),
-            &tee_config,
+            &tee_key,
         )
         .unwrap();
 
@@ -798,8 +1673,10 @@ mod tests {
         let salt = [1u8; 32];
 
         let tee_setup = TeeSetup {
+            key_id: 0,
             private_key: Base58Binary::from(private_key),
             salt: Base58Binary::from(salt),
+            activates_at: None,
         };
 
         let instantiate_msg = InstantiateMsg {
@@ -824,8 +1701,10 @@ mod tests {
         };
 
         let tee_setup = TeeSetup {
+            key_id: 0,
             private_key: Base58Binary::from([1u8; 16]), // Invalid length
             salt: Base58Binary::from([1u8; 32]),
+            activates_at: None,
         };
 
         let instantiate_msg = InstantiateMsg {
@@ -852,8 +1731,10 @@ mod tests {
         let (private_key, _) = generate_keypair();
 
         let tee_setup = TeeSetup {
+            key_id: 0,
             private_key: Base58Binary::from(private_key),
             salt: Base58Binary::from([1u8; 16]), // Invalid length
+            activates_at: None,
         };
 
         let instantiate_msg = InstantiateMsg {
@@ -896,15 +1777,19 @@ mod tests {
 
         // Test valid config
         let valid_config = TeeSetup {
+            key_id: 0,
             private_key: Base58Binary::from(private_key),
             salt: Base58Binary::from(salt),
+            activates_at: None,
         };
         assert!(validate_tee_config(&valid_config).is_ok());
 
         // Test invalid private key length
         let invalid_private_key = TeeSetup {
+            key_id: 0,
             private_key: Base58Binary::from([1u8; 16]), // Invalid length
             salt: Base58Binary::from(salt),
+            activates_at: None,
         };
         assert!(matches!(
             validate_tee_config(&invalid_private_key),
@@ -913,8 +1798,10 @@ mod tests {
 
         // Test invalid salt length
         let invalid_salt = TeeSetup {
+            key_id: 0,
             private_key: Base58Binary::from(private_key),
             salt: Base58Binary::from([1u8; 16]), // Invalid length
+            activates_at: None,
         };
         assert!(matches!(
             validate_tee_config(&invalid_salt),
@@ -922,6 +1809,1236 @@ mod tests {
         ));
     }
 
+    fn offer_to_key(key_id: u32, public_key: &[u8; 32], owner: &str) -> ExecuteMsg {
+        let item = batch_item(key_id, public_key, owner);
+        ExecuteMsg::Offer {
+            cipher_text: item.cipher_text,
+            nonce: item.nonce,
+            ephemeral_pubkey: item.ephemeral_pubkey,
+            key_id: item.key_id,
+            zk_proof: item.zk_proof,
+            attestation: item.attestation,
+            #[cfg(feature = "demo")]
+            tribute_owner_l1: item.tribute_owner_l1,
+        }
+    }
+
+    fn batch_item(key_id: u32, public_key: &[u8; 32], owner: &str) -> OfferBatchItem {
+        batch_item_with_vector(key_id, public_key, owner, None)
+    }
+
+    fn batch_item_with_vector(
+        key_id: u32,
+        public_key: &[u8; 32],
+        owner: &str,
+        vector_id: Option<u16>,
+    ) -> OfferBatchItem {
+        let owner = Base58Binary::from(owner.as_bytes());
+        let worldwide_day = 20250322;
+        let tribute_input = TributeInputPayload {
+            tribute_draft_id: generate_tribute_draft_id_hash(&owner, worldwide_day).unwrap(),
+            cu_hashes: vec![Base58Binary::from(owner.as_slice())],
+            worldwide_day,
+            settlement_currency: Currency::Usd.into(),
+            settlement_base_amount: Uint64::new(500),
+            settlement_atto_amount: Uint128::zero(),
+            nominal_base_amount: Uint64::new(1000),
+            nominal_atto_amount: Uint128::zero(),
+            owner,
+            vector_id,
+            amounts_blinding: None,
+        };
+        let (cipher_text, nonce, ephemeral_pubkey) =
+            encrypt_tribute_input(&tribute_input, public_key, &[1u8; 32]).unwrap();
+        OfferBatchItem {
+            cipher_text,
+            nonce,
+            ephemeral_pubkey,
+            key_id,
+            zk_proof: ZkProof {
+                proof: Default::default(),
+                public_data: ZkProofPublicData {
+                    public_key: Default::default(),
+                    merkle_root: Default::default(),
+                },
+                verification_key: Default::default(),
+            },
+            attestation: None,
+            #[cfg(feature = "demo")]
+            tribute_owner_l1: None,
+        }
+    }
+
+    fn encryption_info(deps: Deps, env: Env) -> EncryptionInfoResponse {
+        from_json(query(deps, env, QueryMsg::EncryptionInfo {}).unwrap()).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "demo")]
+    fn test_tee_key_rotation() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let owner = deps.api.addr_make("owner");
+        let owner_info = message_info(&owner, &[]);
+        let sender_info = message_info(&deps.api.addr_make("sender"), &[]);
+        let tribute_addr = deps.api.addr_make("tribute");
+        let (private_key_0, public_key_0) = generate_keypair();
+        let (private_key_1, public_key_1) = generate_keypair();
+
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            owner_info.clone(),
+            InstantiateMsg {
+                tribute_address: Some(tribute_addr),
+                owner: None,
+                tee_config: Some(TeeSetup {
+                    key_id: 0,
+                    private_key: Base58Binary::from(private_key_0),
+                    salt: Base58Binary::from([1u8; 32]),
+                    activates_at: None,
+                }),
+                zk_config: None,
+            },
+        )
+        .unwrap();
+
+        // the next key is added ahead of its activation
+        let activates_at = env.block.time.plus_days(1);
+        let add_key_1 = ExecuteMsg::UpdateConfig {
+            new_owner: None,
+            new_tribute_address: None,
+            new_tee_config: Some(TeeSetup {
+                key_id: 1,
+                private_key: Base58Binary::from(private_key_1),
+                salt: Base58Binary::from([1u8; 32]),
+                activates_at: Some(activates_at),
+            }),
+            new_zk_config: None,
+            new_agent_attestation: None,
+            new_offer_window_days: None,
+            new_obfuscation: None,
+            new_check_draft_id: None,
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            owner_info.clone(),
+            add_key_1.clone(),
+        )
+        .unwrap();
+        let err = execute(deps.as_mut(), env.clone(), owner_info.clone(), add_key_1).unwrap_err();
+        assert_eq!(err, ContractError::TeeKeyExists { key_id: 1 });
+
+        let info = encryption_info(deps.as_ref(), env.clone());
+        assert_eq!(info.current.unwrap().key_id, 0);
+        assert_eq!(info.upcoming.len(), 1);
+        assert_eq!(info.upcoming[0].key_id, 1);
+        assert_eq!(
+            info.upcoming[0].public_key,
+            Base58Binary::from(public_key_1)
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            sender_info.clone(),
+            offer_to_key(1, &public_key_1, "user1"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::TeeKeyNotActive { key_id: 1 });
+
+        // the next key activates and the previous one is retired with a grace period
+        env.block.time = activates_at;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            owner_info.clone(),
+            ExecuteMsg::RetireTeeKey {
+                key_id: 0,
+                grace_period_seconds: 3600,
+            },
+        )
+        .unwrap();
+
+        let info = encryption_info(deps.as_ref(), env.clone());
+        assert_eq!(info.current.unwrap().key_id, 1);
+        assert!(info.upcoming.is_empty());
+
+        // offers in flight to the retired key are still accepted
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            sender_info.clone(),
+            offer_to_key(0, &public_key_0, "user1"),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            sender_info.clone(),
+            offer_to_key(1, &public_key_1, "user2"),
+        )
+        .unwrap();
+
+        // the retired key is wiped after its grace period
+        env.block.time = activates_at.plus_seconds(3600);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            sender_info.clone(),
+            offer_to_key(1, &public_key_1, "user3"),
+        )
+        .unwrap();
+        assert!(!TEE_KEYS.has(deps.as_ref().storage, 0));
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            sender_info.clone(),
+            offer_to_key(0, &public_key_0, "user4"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::UnknownTeeKey { key_id: 0 });
+    }
+
+    #[test]
+    #[cfg(feature = "demo")]
+    fn test_offer_batch() {
+        let mut app = App::default();
+        let owner = app.api().addr_make("owner");
+        let sender = app.api().addr_make("sender");
+        let oracle = app.api().addr_make("oracle");
+        let (private_key, public_key) = generate_keypair();
+
+        let tribute_code_id = app.store_code(tribute_contract());
+        let factory_code = ContractWrapper::new(execute, instantiate, crate::query::query);
+        let factory_code_id = app.store_code(Box::new(factory_code));
+        let factory_addr = app
+            .instantiate_contract(
+                factory_code_id,
+                owner.clone(),
+                &InstantiateMsg {
+                    owner: Some(owner.clone()),
+                    tee_config: Some(TeeSetup {
+                        key_id: 0,
+                        private_key: Base58Binary::from(private_key),
+                        salt: Base58Binary::from([1u8; 32]),
+                        activates_at: None,
+                    }),
+                    tribute_address: None,
+                    zk_config: None,
+                },
+                &[],
+                "tribute-factory",
+                None,
+            )
+            .unwrap();
+        let tribute_addr = app
+            .instantiate_contract(
+                tribute_code_id,
+                owner.clone(),
+                &tribute::msg::InstantiateMsg {
+                    name: "tribute".to_string(),
+                    symbol: "tt".to_string(),
+                    collection_info_extension: tribute::msg::TributeCollectionExtension::new(
+                        Denom::Native("coen".to_string()),
+                        oracle.clone(),
+                    ),
+                    minter: Some(factory_addr.to_string()),
+                    burner: None,
+                    creator: None,
+                },
+                &[],
+                "mock-tribute",
+                None,
+            )
+            .unwrap();
+        app.execute_contract(
+            owner.clone(),
+            factory_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                new_tribute_address: Some(tribute_addr.clone()),
+                new_owner: None,
+                new_tee_config: None,
+                new_zk_config: None,
+                new_agent_attestation: None,
+                new_offer_window_days: None,
+                new_obfuscation: None,
+                new_check_draft_id: None,
+            },
+            &[],
+        )
+        .unwrap();
+        let tributes_count = |app: &App| -> usize {
+            let tokens: tribute::query::TributesPageResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    tribute_addr.clone(),
+                    &tribute::query::QueryMsg::TributesByDay {
+                        date: 20250322,
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            tokens.tributes.len()
+        };
+
+        let err = app
+            .execute_contract(
+                sender.clone(),
+                factory_addr.clone(),
+                &ExecuteMsg::OfferBatch {
+                    offers: vec![],
+                    mode: OfferBatchMode::AllOrNothing,
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::EmptyBatch {}
+        );
+
+        // a duplicate offer fails the whole batch
+        let err = app
+            .execute_contract(
+                sender.clone(),
+                factory_addr.clone(),
+                &ExecuteMsg::OfferBatch {
+                    offers: vec![
+                        batch_item(0, &public_key, "user1"),
+                        batch_item(0, &public_key, "user1"),
+                    ],
+                    mode: OfferBatchMode::AllOrNothing,
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::BatchOfferFailed {
+                index: 1,
+                error: ContractError::IdAlreadyExists {}.to_string(),
+            }
+        );
+        assert_eq!(tributes_count(&app), 0);
+
+        let res = app
+            .execute_contract(
+                sender.clone(),
+                factory_addr.clone(),
+                &ExecuteMsg::OfferBatch {
+                    offers: vec![
+                        batch_item(0, &public_key, "user1"),
+                        batch_item(0, &public_key, "user2"),
+                    ],
+                    mode: OfferBatchMode::AllOrNothing,
+                },
+                &[],
+            )
+            .unwrap();
+        let response: OfferBatchResponse = from_json(res.data.unwrap()).unwrap();
+        assert!(response
+            .outcomes
+            .iter()
+            .all(|outcome| matches!(outcome, OfferOutcome::Accepted { .. })));
+        assert_eq!(tributes_count(&app), 2);
+
+        // failed offers are skipped and reported in best effort mode
+        let res = app
+            .execute_contract(
+                sender.clone(),
+                factory_addr.clone(),
+                &ExecuteMsg::OfferBatch {
+                    offers: vec![
+                        batch_item(0, &public_key, "user2"),
+                        batch_item(0, &public_key, "user3"),
+                        batch_item(7, &public_key, "user4"),
+                    ],
+                    mode: OfferBatchMode::BestEffort,
+                },
+                &[],
+            )
+            .unwrap();
+        let response: OfferBatchResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(
+            response.outcomes[0],
+            OfferOutcome::Rejected {
+                error: ContractError::IdAlreadyExists {}.to_string()
+            }
+        );
+        assert!(matches!(
+            response.outcomes[1],
+            OfferOutcome::Accepted { .. }
+        ));
+        assert_eq!(
+            response.outcomes[2],
+            OfferOutcome::Rejected {
+                error: ContractError::UnknownTeeKey { key_id: 7 }.to_string()
+            }
+        );
+        assert_eq!(tributes_count(&app), 3);
+
+        // the rejected offer can be offered again
+        app.execute_contract(
+            sender.clone(),
+            factory_addr.clone(),
+            &offer_to_key(0, &public_key, "user4"),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(tributes_count(&app), 4);
+
+        // offers the tribute contract would reject are skipped as well
+        let res = app
+            .execute_contract(
+                sender.clone(),
+                factory_addr.clone(),
+                &ExecuteMsg::OfferBatch {
+                    offers: vec![
+                        batch_item_with_vector(0, &public_key, "user5", Some(1)),
+                        batch_item(0, &public_key, "user6"),
+                    ],
+                    mode: OfferBatchMode::BestEffort,
+                },
+                &[],
+            )
+            .unwrap();
+        let response: OfferBatchResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(
+            response.outcomes[0],
+            OfferOutcome::Rejected {
+                error: ContractError::MintRejected {
+                    error: tribute::error::ContractError::VectorNotConfigured {}.to_string()
+                }
+                .to_string()
+            }
+        );
+        assert!(matches!(
+            response.outcomes[1],
+            OfferOutcome::Accepted { .. }
+        ));
+        assert_eq!(tributes_count(&app), 5);
+        app.execute_contract(
+            sender.clone(),
+            factory_addr.clone(),
+            &offer_to_key(0, &public_key, "user5"),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(tributes_count(&app), 6);
+    }
+
+    fn registry_agent(wallet: &Addr, agent_type: AgentType, status: AgentStatus) -> Agent {
+        Agent {
+            wallet: wallet.clone(),
+            agent_type,
+            name: "agent".to_string(),
+            email: None,
+            jurisdictions: vec![],
+            endpoint: None,
+            metadata_json: None,
+            docs_uri: vec![],
+            discord: None,
+            status,
+            avg_cu: None,
+            submitted_at: Timestamp::from_seconds(0),
+            updated_at: Timestamp::from_seconds(0),
+            ext: AgentExt::Nra {},
+        }
+    }
+
+    fn attest(
+        msg: ExecuteMsg,
+        env: &Env,
+        agent: &Addr,
+        secret_key: &secp256k1::SecretKey,
+    ) -> ExecuteMsg {
+        let ExecuteMsg::Offer {
+            cipher_text,
+            nonce,
+            ephemeral_pubkey,
+            key_id,
+            zk_proof,
+            #[cfg(feature = "demo")]
+            tribute_owner_l1,
+            ..
+        } = msg
+        else {
+            panic!("not an offer");
+        };
+        let secp = secp256k1::Secp256k1::new();
+        let hash = attestation_hash(
+            &env.contract.address,
+            &cipher_text,
+            &nonce,
+            &ephemeral_pubkey,
+        );
+        let signature = secp.sign_ecdsa(&secp256k1::Message::from_digest(hash), secret_key);
+        ExecuteMsg::Offer {
+            cipher_text,
+            nonce,
+            ephemeral_pubkey,
+            key_id,
+            zk_proof,
+            attestation: Some(AgentAttestation {
+                agent: agent.clone(),
+                public_key: Base58Binary::from(secret_key.public_key(&secp).serialize()),
+                signature: Base58Binary::from(signature.serialize_compact()),
+            }),
+            #[cfg(feature = "demo")]
+            tribute_owner_l1,
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "demo")]
+    fn test_agent_attestation() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let owner_info = message_info(&deps.api.addr_make("owner"), &[]);
+        let sender_info = message_info(&deps.api.addr_make("sender"), &[]);
+        let cra_registry = deps.api.addr_make("cra_registry");
+        let iba_registry = deps.api.addr_make("iba_registry");
+        let tribute_addr = deps.api.addr_make("tribute");
+        let (private_key, public_key) = generate_keypair();
+
+        // agent addresses are derived from their secp256k1 keys
+        let secp = secp256k1::Secp256k1::new();
+        let agent_key = |seed: u8| {
+            let secret_key = secp256k1::SecretKey::from_slice(&[seed; 32]).unwrap();
+            let key_hash =
+                Ripemd160::digest(Sha256::digest(secret_key.public_key(&secp).serialize()));
+            let address = deps
+                .api
+                .addr_humanize(&CanonicalAddr::from(key_hash.as_slice()))
+                .unwrap();
+            (secret_key, address)
+        };
+        let (cra_key, cra_agent) = agent_key(1);
+        let (iba_key, iba_agent) = agent_key(2);
+        let (unknown_key, unknown_agent) = agent_key(3);
+
+        let agents = [
+            (
+                cra_registry.clone(),
+                registry_agent(&cra_agent, AgentType::Cra, AgentStatus::Active),
+            ),
+            (
+                iba_registry.clone(),
+                registry_agent(&iba_agent, AgentType::Iba, AgentStatus::OnHold),
+            ),
+        ];
+        deps.querier.update_wasm(move |query| {
+            let WasmQuery::Smart { contract_addr, msg } = query else {
+                return SystemResult::Ok(ContractResult::Err("unsupported".to_string()));
+            };
+            let agent_cra::query::QueryMsg::GetAgentByAddress { address } = from_json(msg).unwrap()
+            else {
+                return SystemResult::Ok(ContractResult::Err("unsupported".to_string()));
+            };
+            let agent = agents.iter().find(|(registry, agent)| {
+                registry.as_str() == contract_addr && agent.wallet == address
+            });
+            SystemResult::Ok(match agent {
+                Some((_, agent)) => ContractResult::Ok(
+                    to_json_binary(&AgentResponse {
+                        agent: agent.clone(),
+                    })
+                    .unwrap(),
+                ),
+                None => ContractResult::Err("agent not found".to_string()),
+            })
+        });
+
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            owner_info.clone(),
+            InstantiateMsg {
+                tribute_address: Some(tribute_addr),
+                owner: None,
+                tee_config: Some(TeeSetup {
+                    key_id: 0,
+                    private_key: Base58Binary::from(private_key),
+                    salt: Base58Binary::from([1u8; 32]),
+                    activates_at: None,
+                }),
+                zk_config: None,
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            owner_info,
+            ExecuteMsg::UpdateConfig {
+                new_owner: None,
+                new_tribute_address: None,
+                new_tee_config: None,
+                new_zk_config: None,
+                new_agent_attestation: Some(AgentAttestationSetup {
+                    required: true,
+                    cra_registry,
+                    iba_registry,
+                }),
+                new_offer_window_days: None,
+                new_obfuscation: None,
+                new_check_draft_id: None,
+            },
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            sender_info.clone(),
+            offer_to_key(0, &public_key, "user1"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AttestationRequired {});
+
+        // signed with a key of another address
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            sender_info.clone(),
+            attest(
+                offer_to_key(0, &public_key, "user1"),
+                &env,
+                &cra_agent,
+                &iba_key,
+            ),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidAttestation {});
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            sender_info.clone(),
+            attest(
+                offer_to_key(0, &public_key, "user1"),
+                &env,
+                &unknown_agent,
+                &unknown_key,
+            ),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UnknownAgent {
+                agent: unknown_agent.to_string()
+            }
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            sender_info.clone(),
+            attest(
+                offer_to_key(0, &public_key, "user1"),
+                &env,
+                &iba_agent,
+                &iba_key,
+            ),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AgentNotActive {
+                agent: iba_agent.to_string()
+            }
+        );
+
+        for user in ["user1", "user2"] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                sender_info.clone(),
+                attest(
+                    offer_to_key(0, &public_key, user),
+                    &env,
+                    &cra_agent,
+                    &cra_key,
+                ),
+            )
+            .unwrap();
+        }
+        let response: AgentTributesResponse = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::AgentTributes {
+                    agent: cra_agent.clone(),
+                    worldwide_day: 20250322,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(response.count, 2);
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            sender_info,
+            ExecuteMsg::OfferInsecure {
+                tribute_input: TributeInputPayload {
+                    tribute_draft_id: Base58Binary::from([0u8; 32]),
+                    owner: Base58Binary::from("user3".as_bytes()),
+                    worldwide_day: 20250322,
+                    settlement_currency: Currency::Usd.into(),
+                    settlement_base_amount: Uint64::new(500),
+                    settlement_atto_amount: Uint128::zero(),
+                    nominal_base_amount: Uint64::new(1000),
+                    nominal_atto_amount: Uint128::zero(),
+                    cu_hashes: vec![Base58Binary::from([3u8; 32])],
+                    vector_id: None,
+                    amounts_blinding: None,
+                },
+                zk_proof: ZkProof {
+                    proof: Default::default(),
+                    public_data: ZkProofPublicData {
+                        public_key: Default::default(),
+                        merkle_root: Default::default(),
+                    },
+                    verification_key: Default::default(),
+                },
+                tribute_owner_l1: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AttestationRequired {});
+    }
+
+    #[test]
+    #[cfg(feature = "demo")]
+    fn test_offer_window() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let owner_info = message_info(&deps.api.addr_make("owner"), &[]);
+        let sender_info = message_info(&deps.api.addr_make("sender"), &[]);
+        let tribute_addr = deps.api.addr_make("tribute");
+        let (private_key, public_key) = generate_keypair();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            owner_info.clone(),
+            InstantiateMsg {
+                tribute_address: Some(tribute_addr),
+                owner: None,
+                tee_config: Some(TeeSetup {
+                    key_id: 0,
+                    private_key: Base58Binary::from(private_key),
+                    salt: Base58Binary::from([1u8; 32]),
+                    activates_at: None,
+                }),
+                zk_config: None,
+            },
+        )
+        .unwrap();
+        let offer_window = |deps: Deps, env: Env| -> OfferWindowResponse {
+            from_json(query(deps, env, QueryMsg::OfferWindow { day: 20250322 }).unwrap()).unwrap()
+        };
+
+        // 2025-03-25 23:59:59 UTC, the last day of the default window of 20250322
+        env.block.time = Timestamp::from_seconds(1_742_947_199);
+        assert!(offer_window(deps.as_ref(), env.clone()).open);
+
+        // 2025-03-26 00:00:00 UTC, the window closed the day before and metadosis runs the day
+        env.block.time = Timestamp::from_seconds(1_742_947_200);
+        let window = offer_window(deps.as_ref(), env.clone());
+        assert_eq!(window.offer_window_days, DEFAULT_OFFER_WINDOW_DAYS);
+        assert_eq!(window.closes_on, 20250325);
+        assert!(!window.open);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            sender_info.clone(),
+            offer_to_key(0, &public_key, "user1"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ClosedOfferWindow {});
+
+        let update_window = ExecuteMsg::UpdateConfig {
+            new_owner: None,
+            new_tribute_address: None,
+            new_tee_config: None,
+            new_zk_config: None,
+            new_agent_attestation: None,
+            new_offer_window_days: Some(5),
+            new_obfuscation: None,
+            new_check_draft_id: None,
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            sender_info.clone(),
+            update_window.clone(),
+        )
+        .unwrap_err();
+        execute(deps.as_mut(), env.clone(), owner_info, update_window).unwrap();
+
+        let window = offer_window(deps.as_ref(), env.clone());
+        assert_eq!(window.offer_window_days, 5);
+        assert_eq!(window.closes_on, 20250327);
+        assert!(window.open);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            sender_info,
+            offer_to_key(0, &public_key, "user1"),
+        )
+        .unwrap();
+    }
+
+    #[cfg(feature = "demo")]
+    #[test]
+    fn test_burn_all_owner_only() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let owner_info = message_info(&deps.api.addr_make("owner"), &[]);
+        let sender_info = message_info(&deps.api.addr_make("sender"), &[]);
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            owner_info.clone(),
+            InstantiateMsg {
+                tribute_address: None,
+                owner: None,
+                tee_config: None,
+                zk_config: None,
+            },
+        )
+        .unwrap();
+        USED_TRIBUTE_IDS
+            .save(deps.as_mut().storage, "draft", 20250322)
+            .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            sender_info,
+            ExecuteMsg::BurnAll {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Ownership(_)));
+        assert!(USED_TRIBUTE_IDS
+            .used_on(deps.as_ref().storage, "draft")
+            .unwrap()
+            .is_some());
+
+        execute(deps.as_mut(), env, owner_info, ExecuteMsg::BurnAll {}).unwrap();
+        assert!(USED_TRIBUTE_IDS
+            .used_on(deps.as_ref().storage, "draft")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "demo")]
+    fn test_prune_used_state() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let owner_info = message_info(&deps.api.addr_make("owner"), &[]);
+        let sender_info = message_info(&deps.api.addr_make("sender"), &[]);
+        let tribute_addr = deps.api.addr_make("tribute");
+        let (private_key, public_key) = generate_keypair();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            owner_info.clone(),
+            InstantiateMsg {
+                tribute_address: Some(tribute_addr),
+                owner: None,
+                tee_config: Some(TeeSetup {
+                    key_id: 0,
+                    private_key: Base58Binary::from(private_key),
+                    salt: Base58Binary::from([1u8; 32]),
+                    activates_at: None,
+                }),
+                zk_config: None,
+            },
+        )
+        .unwrap();
+        let owner = Base58Binary::from("user1".as_bytes());
+        let tribute_draft_id = generate_tribute_draft_id_hash(&owner, 20250322).unwrap();
+        let used = |deps: Deps, env: Env| -> (UsedResponse, UsedResponse) {
+            let draft_id = query(
+                deps,
+                env.clone(),
+                QueryMsg::UsedTributeDraftId {
+                    tribute_draft_id: tribute_draft_id.clone(),
+                },
+            )
+            .unwrap();
+            let cu_hash = query(
+                deps,
+                env,
+                QueryMsg::UsedCuHash {
+                    cu_hash: owner.clone(),
+                },
+            )
+            .unwrap();
+            (from_json(draft_id).unwrap(), from_json(cu_hash).unwrap())
+        };
+        let prune = |deps: DepsMut, env: Env, limit: Option<u32>| -> PruneResult {
+            let res = execute(
+                deps,
+                env,
+                message_info(&Addr::unchecked("anyone"), &[]),
+                ExecuteMsg::Prune { limit },
+            )
+            .unwrap();
+            from_json(res.data.unwrap()).unwrap()
+        };
+
+        // 2025-03-22 00:00:00 UTC
+        env.block.time = Timestamp::from_seconds(1_742_601_600);
+        let (draft_id, cu_hash) = used(deps.as_ref(), env.clone());
+        assert!(!draft_id.used && !cu_hash.used);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            sender_info.clone(),
+            offer_to_key(0, &public_key, "user1"),
+        )
+        .unwrap();
+        let (draft_id, cu_hash) = used(deps.as_ref(), env.clone());
+        assert_eq!(draft_id.worldwide_day, Some(20250322));
+        assert_eq!(cu_hash.worldwide_day, Some(20250322));
+
+        // an entry used before the sets were keyed by day is accounted to the migration day
+        Map::<String, Empty>::new("used_ids")
+            .save(deps.as_mut().storage, "legacy".to_string(), &Empty {})
+            .unwrap();
+        LEGACY_USED_DAY
+            .save(deps.as_mut().storage, &20250322)
+            .unwrap();
+        assert_eq!(
+            USED_TRIBUTE_IDS
+                .used_on(deps.as_ref().storage, "legacy")
+                .unwrap(),
+            Some(20250322)
+        );
+
+        // 2025-03-25 00:00:00 UTC, the window of 20250322 is still open
+        env.block.time = Timestamp::from_seconds(1_742_860_800);
+        let result = prune(deps.as_mut(), env.clone(), None);
+        assert_eq!(result.expired_before, 20250322);
+        assert_eq!(result.pruned, 0);
+        assert!(result.finished);
+        assert!(used(deps.as_ref(), env.clone()).0.used);
+
+        // 2025-03-26 00:00:00 UTC, the window is closed
+        env.block.time = Timestamp::from_seconds(1_742_947_200);
+        let result = prune(deps.as_mut(), env.clone(), Some(1));
+        assert_eq!(result.expired_before, 20250323);
+        assert_eq!(result.pruned, 1);
+        assert!(!result.finished);
+        let result = prune(deps.as_mut(), env.clone(), Some(2));
+        assert_eq!(result.pruned, 1);
+        assert!(result.finished);
+        // consumption unit hashes are never pruned, nor indexed by day
+        let (draft_id, cu_hash) = used(deps.as_ref(), env.clone());
+        assert!(!draft_id.used);
+        assert_eq!(cu_hash.worldwide_day, Some(20250322));
+        assert!(
+            Map::<(WorldwideDay, String), Empty>::new("used_cu_hashes_by_day")
+                .is_empty(deps.as_ref().storage)
+        );
+        assert!(!LEGACY_USED_DAY.exists(deps.as_ref().storage));
+
+        // extending the window does not reopen the pruned day
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            owner_info,
+            ExecuteMsg::UpdateConfig {
+                new_owner: None,
+                new_tribute_address: None,
+                new_tee_config: None,
+                new_zk_config: None,
+                new_agent_attestation: None,
+                new_offer_window_days: Some(5),
+                new_obfuscation: None,
+                new_check_draft_id: None,
+            },
+        )
+        .unwrap();
+        let window: OfferWindowResponse = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::OfferWindow { day: 20250322 },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(!window.open);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            sender_info,
+            offer_to_key(0, &public_key, "user1"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ClosedOfferWindow {});
+
+        // the nominal totals of the day are kept a while longer for metadosis
+        assert!(DAILY_NOMINAL_TOTALS.has(deps.as_ref().storage, 20250322));
+        // 2025-04-28 00:00:00 UTC
+        env.block.time = Timestamp::from_seconds(1_745_798_400);
+        let result = prune(deps.as_mut(), env, None);
+        assert_eq!(result.pruned, 1);
+        assert!(result.finished);
+        assert!(!DAILY_NOMINAL_TOTALS.has(deps.as_ref().storage, 20250322));
+    }
+
+    #[test]
+    #[cfg(feature = "demo")]
+    fn test_tee_obfuscate() {
+        let mut app = App::default();
+        let owner = app.api().addr_make("owner");
+        let sender = app.api().addr_make("sender");
+        let oracle = app.api().addr_make("oracle");
+        let (private_key, public_key) = generate_keypair();
+
+        let tribute_code_id = app.store_code(tribute_contract());
+        let factory_code = ContractWrapper::new(execute, instantiate, crate::query::query);
+        let factory_code_id = app.store_code(Box::new(factory_code));
+        let factory_addr = app
+            .instantiate_contract(
+                factory_code_id,
+                owner.clone(),
+                &InstantiateMsg {
+                    owner: Some(owner.clone()),
+                    tee_config: Some(TeeSetup {
+                        key_id: 0,
+                        private_key: Base58Binary::from(private_key),
+                        salt: Base58Binary::from([1u8; 32]),
+                        activates_at: None,
+                    }),
+                    tribute_address: None,
+                    zk_config: None,
+                },
+                &[],
+                "tribute-factory",
+                None,
+            )
+            .unwrap();
+        let tribute_addr = app
+            .instantiate_contract(
+                tribute_code_id,
+                owner.clone(),
+                &tribute::msg::InstantiateMsg {
+                    name: "tribute".to_string(),
+                    symbol: "tt".to_string(),
+                    collection_info_extension: tribute::msg::TributeCollectionExtension::new(
+                        Denom::Native("coen".to_string()),
+                        oracle.clone(),
+                    ),
+                    minter: Some(factory_addr.to_string()),
+                    burner: None,
+                    creator: None,
+                },
+                &[],
+                "mock-tribute",
+                None,
+            )
+            .unwrap();
+        let update_config = |new_obfuscation| ExecuteMsg::UpdateConfig {
+            new_tribute_address: Some(tribute_addr.clone()),
+            new_owner: None,
+            new_tee_config: None,
+            new_zk_config: None,
+            new_agent_attestation: None,
+            new_offer_window_days: None,
+            new_obfuscation: Some(new_obfuscation),
+            new_check_draft_id: None,
+        };
+        let err = app
+            .execute_contract(
+                owner.clone(),
+                factory_addr.clone(),
+                &update_config(ObfuscationConfig {
+                    rounding: Some(AmountRounding::SignificantDigits { digits: 0 }),
+                    commitment: false,
+                }),
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidRounding {}
+        );
+        app.execute_contract(
+            owner.clone(),
+            factory_addr.clone(),
+            &update_config(ObfuscationConfig {
+                rounding: Some(AmountRounding::SignificantDigits { digits: 2 }),
+                commitment: true,
+            }),
+            &[],
+        )
+        .unwrap();
+
+        let offer = |tribute_input: &TributeInputPayload| -> ExecuteMsg {
+            let (cipher_text, nonce, ephemeral_pubkey) =
+                encrypt_tribute_input(tribute_input, &public_key, &[1u8; 32]).unwrap();
+            let ExecuteMsg::Offer { zk_proof, .. } = offer_to_key(0, &public_key, "user1") else {
+                unreachable!()
+            };
+            ExecuteMsg::Offer {
+                cipher_text,
+                nonce,
+                ephemeral_pubkey,
+                key_id: 0,
+                zk_proof,
+                attestation: None,
+                #[cfg(feature = "demo")]
+                tribute_owner_l1: None,
+            }
+        };
+        let l2_owner = Base58Binary::from("user1".as_bytes());
+        let mut tribute_input = TributeInputPayload {
+            tribute_draft_id: generate_tribute_draft_id_hash(&l2_owner, 20250322).unwrap(),
+            owner: l2_owner.clone(),
+            worldwide_day: 20250322,
+            settlement_currency: Currency::Usd.into(),
+            settlement_base_amount: Uint64::new(500),
+            settlement_atto_amount: Uint128::new(123_456_000_000_000_000),
+            nominal_base_amount: Uint64::new(1000),
+            nominal_atto_amount: Uint128::zero(),
+            cu_hashes: vec![Base58Binary::from([7u8; 32])],
+            vector_id: None,
+            amounts_blinding: None,
+        };
+
+        let err = app
+            .execute_contract(
+                sender.clone(),
+                factory_addr.clone(),
+                &offer(&tribute_input),
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::BlindingRequired {}
+        );
+
+        let blinding = Base58Binary::from([9u8; 32]);
+        tribute_input.amounts_blinding = Some(blinding.clone());
+        let res = app
+            .execute_contract(sender, factory_addr.clone(), &offer(&tribute_input), &[])
+            .unwrap();
+
+        let settlement_amount = Uint128::new(500_123_456_000_000_000_000);
+        let nominal_amount = Uint128::new(1_000_000_000_000_000_000_000);
+        let commitment =
+            amounts_commitment(&tribute_input, settlement_amount, nominal_amount, &blinding);
+        let event = res
+            .events
+            .iter()
+            .find(|event| event.ty == "wasm-tribute-factory::offer")
+            .unwrap();
+        let attribute = |key: &str| {
+            event
+                .attributes
+                .iter()
+                .find(|attribute| attribute.key == key)
+                .map(|attribute| attribute.value.clone())
+        };
+        assert_eq!(attribute("owner"), None);
+        assert_eq!(attribute("settlement_base_amount").unwrap(), "500");
+        assert_eq!(attribute("settlement_atto_amount").unwrap(), "0");
+        assert_eq!(
+            attribute("amounts_commitment").unwrap(),
+            commitment.to_string()
+        );
+
+        let tributes: tribute::query::TributesPageResponse = app
+            .wrap()
+            .query_wasm_smart(
+                tribute_addr.clone(),
+                &tribute::query::QueryMsg::TributesByDay {
+                    date: 20250322,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        let tribute = &tributes.tributes[0].data;
+        assert_eq!(
+            tribute.settlement_amount_minor,
+            Uint128::new(500_000_000_000_000_000_000)
+        );
+        assert_eq!(tribute.amounts_commitment, Some(commitment));
+        // the price of the exact amounts is 0.500123456
+        assert_eq!(
+            tribute.nominal_price,
+            Decimal::from_atomics(500_123u128, 6).unwrap()
+        );
+
+        // the rounded tributes and the factory totals add up to the exact total interest
+        let interest: tribute::query::TotalInterestResponse = app
+            .wrap()
+            .query_wasm_smart(
+                tribute_addr,
+                &tribute::query::QueryMsg::TotalInterest { date: 20250322 },
+            )
+            .unwrap();
+        let totals: NominalTotalsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                factory_addr,
+                &QueryMsg::NominalTotals {
+                    worldwide_day: 20250322,
+                },
+            )
+            .unwrap();
+        assert_eq!(totals.published_minor, interest.total_nominal_amount);
+        assert_eq!(totals.exact_minor, nominal_amount);
+        assert_ne!(totals.exact_minor, totals.published_minor);
+    }
+
+    #[test]
+    fn test_default_obfuscation() {
+        let mut deps = mock_dependencies();
+        let info = message_info(&deps.api.addr_make("owner"), &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InstantiateMsg {
+                tribute_address: None,
+                owner: None,
+                tee_config: None,
+                zk_config: None,
+            },
+        )
+        .unwrap();
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!(config.obfuscation, Some(ObfuscationConfig::default()));
+
+        // factories deployed without obfuscation round the amounts after migration
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config {
+                    obfuscation: None,
+                    ..config
+                },
+            )
+            .unwrap();
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::Migrate {}).unwrap();
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!(config.obfuscation, Some(ObfuscationConfig::default()));
+    }
+
     #[test]
     fn test_validate_deadline() {
         // WorldwideDay = 2025-09-01 00:00:00 UTC
@@ -929,12 +3046,12 @@ mod tests {
 
         //  Before deadline: 2025-09-03 00:00:00 UTC (still valid)
         let current_time_ok = Timestamp::from_seconds(1_756_857_600);
-        let res_ok = validate_deadline(wwd_ts, &current_time_ok);
+        let res_ok = validate_deadline(wwd_ts, &current_time_ok, DEFAULT_OFFER_WINDOW_DAYS);
         assert!(res_ok.is_ok(), "expected Ok inside deadline");
 
         //  After deadline: 2025-09-05 00:00:00 UTC (should fail)
         let current_time_fail = Timestamp::from_seconds(1_757_030_400);
-        let res_fail = validate_deadline(wwd_ts, &current_time_fail);
+        let res_fail = validate_deadline(wwd_ts, &current_time_fail, DEFAULT_OFFER_WINDOW_DAYS);
         assert!(
             matches!(res_fail, Err(ContractError::ClosedOfferWindow {})),
             "expected ClosedOfferWindow error after deadline"
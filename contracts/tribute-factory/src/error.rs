@@ -33,12 +33,48 @@ pub enum ContractError {
     DecryptionFailed {},
     #[error("Invalid payload format")]
     InvalidPayload {},
+    #[error("TEE key {key_id} already exists")]
+    TeeKeyExists { key_id: u32 },
+    #[error("Unknown TEE key {key_id}")]
+    UnknownTeeKey { key_id: u32 },
+    #[error("TEE key {key_id} is not active yet")]
+    TeeKeyNotActive { key_id: u32 },
     #[error("Invalid salt length")]
     InvalidSalt {},
     #[error("Invalid nonce")]
     InvalidNonce {},
     #[error("Tribute date window is closed")]
     ClosedOfferWindow {},
+    #[error("Invalid zero knowledge proof")]
+    InvalidZkProof {},
+    #[error("Invalid zero knowledge verification key")]
+    InvalidVerificationKey {},
+    #[error("Unknown zero knowledge verification key")]
+    UnknownVerificationKey {},
+    #[error("Proof is not made against the L2 merkle root of the day")]
+    UnknownMerkleRoot {},
+    #[error("Proof is not made by the owner of the tribute")]
+    ProofOwnerMismatch {},
+    #[error("Offer batch is empty")]
+    EmptyBatch {},
+    #[error("Offer batch exceeds {max} offers")]
+    BatchTooLarge { max: u32 },
+    #[error("Offer {index} of the batch failed: {error}")]
+    BatchOfferFailed { index: u32, error: String },
+    #[error("Tribute contract rejects the tribute: {error}")]
+    MintRejected { error: String },
+    #[error("Offer must be attested by an agent")]
+    AttestationRequired {},
+    #[error("Invalid agent attestation")]
+    InvalidAttestation {},
+    #[error("Agent {agent} is not a CRA or IBA agent")]
+    UnknownAgent { agent: String },
+    #[error("Agent {agent} is not active")]
+    AgentNotActive { agent: String },
+    #[error("Invalid amount rounding")]
+    InvalidRounding {},
+    #[error("Offer must provide a 32 bytes amounts blinding")]
+    BlindingRequired {},
     #[error(transparent)]
     CurrencyError(#[from] CurrencyError),
 }
@@ -1,9 +1,15 @@
+pub mod attestation;
 pub mod contract;
 pub mod error;
 pub mod msg;
+pub mod obfuscation;
+pub mod poseidon;
 pub mod query;
 mod state;
 pub mod types;
+pub mod zk;
 
 #[cfg(test)]
 mod test_ecdhe;
+#[cfg(test)]
+mod test_zk;
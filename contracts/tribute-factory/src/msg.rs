@@ -1,11 +1,14 @@
+#[cfg(feature = "demo")]
 use crate::types::TributeInputPayload;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Timestamp, Uint128};
+use outbe_utils::date::WorldwideDay;
 use outbe_utils::Base58Binary;
 
 pub type TributeMsg = tribute::msg::ExecuteMsg;
 pub type TributeMintExtension = tribute::msg::MintExtension;
 pub type TributeMintData = tribute::msg::TributeMintData;
+pub type TributeMintBatchItem = outbe_nft::msg::MintBatchItem<TributeMintExtension>;
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -15,6 +18,7 @@ pub struct InstantiateMsg {
     pub owner: Option<Addr>,
     /// Trusted execution environment config
     pub tee_config: Option<TeeSetup>,
+    /// Zero knowledge proofs config
     pub zk_config: Option<ZkSetup>,
 }
 
@@ -25,16 +29,75 @@ pub enum MigrateMsg {
 
 #[cw_serde]
 pub struct TeeSetup {
+    /// Id of the key in the TEE keyring, offers refer to the key they are encrypted to by id
+    #[serde(default)]
+    pub key_id: u32,
     /// X25519 private key for ECDHE encryption
     pub private_key: Base58Binary,
     /// Salt to be used in hashing operations
     pub salt: Base58Binary,
+    /// Time the key can be used from, defaults to the time it is added
+    #[serde(default)]
+    pub activates_at: Option<Timestamp>,
 }
 
 #[cw_serde]
 pub struct ZkSetup {
-    /// ZK circuit used to verify proofs
-    pub circuit: Base58Binary,
+    /// PlonK verification keys the offers can be proven with, see `zk::VerificationKey`
+    pub verification_keys: Vec<Base58Binary>,
+}
+
+#[cw_serde]
+pub struct AgentAttestationSetup {
+    /// Rejects offers without an attestation, otherwise attestations are checked if present
+    pub required: bool,
+    /// Registry of the CRA agents, see `agent-cra`
+    pub cra_registry: Addr,
+    /// Registry of the IBA agents, see `agent-iba`
+    pub iba_registry: Addr,
+}
+
+/// Privacy step applied to the offered tributes, see `tee_obfuscate`
+#[cw_serde]
+pub struct ObfuscationConfig {
+    /// Rounding of the settlement amount published on the Tribute and in the offer event.
+    /// The nominal price is rounded to `PRICE_SIGNIFICANT_DIGITS` and the nominal amount follows
+    /// at that price. Amounts and price are exact if not set.
+    pub rounding: Option<AmountRounding>,
+    /// Mints the Tributes with a commitment to the exact amounts, offers must then provide
+    /// `amounts_blinding`
+    #[serde(default)]
+    pub commitment: bool,
+}
+
+/// Applied from instantiation and migration on, until the owner configures another one
+impl Default for ObfuscationConfig {
+    fn default() -> Self {
+        ObfuscationConfig {
+            rounding: Some(AmountRounding::SignificantDigits { digits: 3 }),
+            commitment: false,
+        }
+    }
+}
+
+#[cw_serde]
+pub enum AmountRounding {
+    /// Rounds to the nearest multiple of `step_minor`, and never below one step
+    Step { step_minor: Uint128 },
+    /// Rounds to the nearest amount with `digits` significant digits
+    SignificantDigits { digits: u32 },
+}
+
+/// Signature of the agent that relays an offer, over
+/// `sha256("tribute-factory:attestation" || ":" || factory || ":" || cipher_text || ":" || nonce || ":" || ephemeral_pubkey)`
+#[cw_serde]
+pub struct AgentAttestation {
+    /// Address of an Active CRA or IBA agent
+    pub agent: Addr,
+    /// Compressed secp256k1 public key of the agent address
+    pub public_key: Base58Binary,
+    /// Compact secp256k1 signature
+    pub signature: Base58Binary,
 }
 
 #[cw_serde]
@@ -42,7 +105,34 @@ pub enum ExecuteMsg {
     UpdateConfig {
         new_owner: Option<Addr>,
         new_tribute_address: Option<Addr>,
+        /// Adds a key to the TEE keyring, the keys already there stay usable until retired
         new_tee_config: Option<TeeSetup>,
+        new_zk_config: Option<ZkSetup>,
+        #[serde(default)]
+        new_agent_attestation: Option<AgentAttestationSetup>,
+        /// Days after a worldwide day its tributes can still be offered, metadosis runs
+        /// a day once its offer window is over
+        #[serde(default)]
+        new_offer_window_days: Option<u32>,
+        #[serde(default)]
+        new_obfuscation: Option<ObfuscationConfig>,
+        /// Checks the draft id of the offers against their owner and day, see `Config`
+        #[serde(default)]
+        new_check_draft_id: Option<bool>,
+    },
+
+    /// Retires a key of the TEE keyring. Offers encrypted to the key are still accepted during
+    /// the grace period, after which the key is wiped.
+    RetireTeeKey {
+        key_id: u32,
+        grace_period_seconds: u64,
+    },
+
+    /// Sets the trusted merkle root of the L2 state of a worldwide day, the proofs of
+    /// the offers of the day have to be made against it. Owner only.
+    SetL2Root {
+        worldwide_day: WorldwideDay,
+        merkle_root: Base58Binary,
     },
 
     /// Accepts encrypted data and mints a new Tribute
@@ -53,12 +143,25 @@ pub enum ExecuteMsg {
         nonce: Base58Binary,
         /// Ephemeral public key to decrypt the data
         ephemeral_pubkey: Base58Binary,
+        /// Id of the TEE key the data is encrypted to, see `QueryMsg::EncryptionInfo`
+        key_id: u32,
         /// Zero knowledge proof
         zk_proof: ZkProof,
+        /// Signature of the relaying agent, see `AgentAttestationSetup`
+        #[serde(default)]
+        attestation: Option<AgentAttestation>,
         #[cfg(feature = "demo")]
         tribute_owner_l1: Option<Addr>,
     },
 
+    /// Accepts several encrypted offers and mints their Tributes with a single `MintBatch`.
+    /// The outcome of each offer is set as response data, see `OfferBatchResponse`.
+    OfferBatch {
+        offers: Vec<OfferBatchItem>,
+        #[serde(default)]
+        mode: OfferBatchMode,
+    },
+
     /// Accepts raw tribute data and mints a new Tribute
     /// TEST PURPOSE ONLY
     #[cfg(feature = "demo")]
@@ -67,16 +170,70 @@ pub enum ExecuteMsg {
         zk_proof: ZkProof,
         tribute_owner_l1: Option<Addr>,
     },
+
+    /// Removes at most `limit` used draft ids and consumption unit hashes of worldwide days
+    /// whose offer window is closed. Offers prune a few entries as well.
+    /// The response data tells whether expired entries are left, see `PruneResult`.
+    Prune { limit: Option<u32> },
+    /// Wipes the used draft ids and consumption unit hashes, owner only
+    #[cfg(feature = "demo")]
     BurnAll {},
 }
 
+/// Encrypted offer of an `OfferBatch`, same fields as `ExecuteMsg::Offer`
+#[cw_serde]
+pub struct OfferBatchItem {
+    pub cipher_text: Base58Binary,
+    pub nonce: Base58Binary,
+    pub ephemeral_pubkey: Base58Binary,
+    pub key_id: u32,
+    pub zk_proof: ZkProof,
+    #[serde(default)]
+    pub attestation: Option<AgentAttestation>,
+    #[cfg(feature = "demo")]
+    pub tribute_owner_l1: Option<Addr>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum OfferBatchMode {
+    /// The batch fails if any offer fails
+    #[default]
+    AllOrNothing,
+    /// Failed offers are skipped and reported, the others are minted
+    BestEffort,
+}
+
+/// Result of an `OfferBatch` call, set as response data
+#[cw_serde]
+pub struct OfferBatchResponse {
+    /// Outcomes in the order of the offers
+    pub outcomes: Vec<OfferOutcome>,
+}
+
+#[cw_serde]
+pub enum OfferOutcome {
+    Accepted { tribute_id: String },
+    Rejected { error: String },
+}
+
+/// Result of a `Prune` call, set as response data
+#[cw_serde]
+pub struct PruneResult {
+    /// Entries of days before this one are expired, offers for them are rejected
+    pub expired_before: WorldwideDay,
+    pub pruned: u64,
+    /// False if expired entries are left and `Prune` should be called again
+    pub finished: bool,
+}
+
 #[cw_serde]
 pub struct ZkProof {
     /// Zero knowledge proof as Structured Reference String and based on PlonK algorithm
     pub proof: Base58Binary,
     /// ZK public data
     pub public_data: ZkProofPublicData,
-    /// ZK verification key
+    /// ZK verification key, one of the keys of `ZkSetup`
     pub verification_key: Base58Binary,
 }
 
@@ -0,0 +1,220 @@
+use crate::error::ContractError;
+use crate::msg::{AmountRounding, ObfuscationConfig};
+use crate::state::NominalTotals;
+use crate::types::TributeInputPayload;
+use cosmwasm_std::{Decimal, HexBinary, StdError, Uint128};
+use outbe_utils::{gen_compound_hash, Base58Binary};
+use tribute::contract::calc_nominal_amount;
+
+/// Significant digits of the largest `Uint128`
+const MAX_SIGNIFICANT_DIGITS: u32 = 39;
+/// Significant digits of the published nominal price when amounts are rounded. The exact price
+/// is the ratio of the exact amounts, they could be recovered from its 18 decimals.
+pub const PRICE_SIGNIFICANT_DIGITS: u32 = 6;
+
+pub fn validate_obfuscation_config(config: &ObfuscationConfig) -> Result<(), ContractError> {
+    match config.rounding {
+        Some(AmountRounding::Step { step_minor }) if step_minor.is_zero() => {
+            Err(ContractError::InvalidRounding {})
+        }
+        Some(AmountRounding::SignificantDigits { digits })
+            if digits == 0 || digits > MAX_SIGNIFICANT_DIGITS =>
+        {
+            Err(ContractError::InvalidRounding {})
+        }
+        _ => Ok(()),
+    }
+}
+
+impl AmountRounding {
+    /// Rounds half up, zero stays zero
+    pub fn round(&self, amount: Uint128) -> Result<Uint128, ContractError> {
+        if amount.is_zero() {
+            return Ok(amount);
+        }
+        let step = match self {
+            AmountRounding::Step { step_minor } => *step_minor,
+            AmountRounding::SignificantDigits { digits } => {
+                let amount_digits = amount.u128().ilog10() + 1;
+                if amount_digits <= *digits {
+                    return Ok(amount);
+                }
+                Uint128::new(10)
+                    .checked_pow(amount_digits - digits)
+                    .map_err(StdError::from)?
+            }
+        };
+        let steps = amount
+            .checked_add(step / Uint128::new(2))
+            .map_err(StdError::from)?
+            / step;
+        Ok(steps
+            .max(Uint128::one())
+            .checked_mul(step)
+            .map_err(StdError::from)?)
+    }
+}
+
+/// Amounts of a tribute published on the Tribute and in the offer event
+pub struct ObfuscatedAmounts {
+    pub settlement_amount_minor: Uint128,
+    pub nominal_amount_minor: Uint128,
+    pub nominal_price: Decimal,
+    pub commitment: Option<HexBinary>,
+    /// Nominal amount the Tribute records, for the exact and the published settlement amount
+    pub totals: NominalTotals,
+}
+
+/// Privacy step of an offer: rounds the published amounts and price and commits to the exact
+/// amounts. The Tribute derives its nominal amount from the settlement amount at the nominal
+/// price, the rounded nominal amount is derived the same way so that `totals` matches the Tribute.
+pub fn tee_obfuscate(
+    config: Option<&ObfuscationConfig>,
+    tribute_input: &TributeInputPayload,
+    settlement_amount: Uint128,
+    nominal_amount: Uint128,
+    nominal_price: Decimal,
+) -> Result<ObfuscatedAmounts, ContractError> {
+    if nominal_price.is_zero() {
+        return Err(ContractError::InvalidPayload {});
+    }
+    let exact_nominal_minor = calc_nominal_amount(settlement_amount, nominal_price);
+    let mut amounts = ObfuscatedAmounts {
+        settlement_amount_minor: settlement_amount,
+        nominal_amount_minor: nominal_amount,
+        nominal_price,
+        commitment: None,
+        totals: NominalTotals {
+            exact_minor: exact_nominal_minor,
+            published_minor: exact_nominal_minor,
+        },
+    };
+    let Some(config) = config else {
+        return Ok(amounts);
+    };
+
+    if let Some(ref rounding) = config.rounding {
+        let settlement_amount_minor = rounding.round(settlement_amount)?;
+        let published_price = Decimal::new(
+            AmountRounding::SignificantDigits {
+                digits: PRICE_SIGNIFICANT_DIGITS,
+            }
+            .round(nominal_price.atomics())?,
+        );
+        let published_minor = calc_nominal_amount(settlement_amount_minor, published_price);
+        amounts.settlement_amount_minor = settlement_amount_minor;
+        amounts.nominal_amount_minor = published_minor;
+        amounts.nominal_price = published_price;
+        amounts.totals.published_minor = published_minor;
+    }
+    if config.commitment {
+        let blinding = tribute_input
+            .amounts_blinding
+            .as_ref()
+            .filter(|blinding| blinding.len() == 32)
+            .ok_or(ContractError::BlindingRequired {})?;
+        amounts.commitment = Some(amounts_commitment(
+            tribute_input,
+            settlement_amount,
+            nominal_amount,
+            blinding,
+        ));
+    }
+    Ok(amounts)
+}
+
+/// `sha256("tribute-factory:amounts_commitment" || ":" || tribute_draft_id || ":" || settlement_currency || ":" || settlement_amount || ":" || nominal_amount || ":" || blinding)`
+/// with big endian numbers, the amounts being the normalized offered ones
+pub fn amounts_commitment(
+    tribute_input: &TributeInputPayload,
+    settlement_amount: Uint128,
+    nominal_amount: Uint128,
+    blinding: &Base58Binary,
+) -> HexBinary {
+    gen_compound_hash(
+        Some("tribute-factory:amounts_commitment"),
+        vec![
+            tribute_input.tribute_draft_id.as_slice(),
+            tribute_input.settlement_currency.to_be_bytes().as_slice(),
+            settlement_amount.to_be_bytes().as_slice(),
+            nominal_amount.to_be_bytes().as_slice(),
+            blinding.as_slice(),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_step() {
+        let rounding = AmountRounding::Step {
+            step_minor: Uint128::new(1000),
+        };
+        assert_eq!(rounding.round(Uint128::zero()).unwrap(), Uint128::zero());
+        assert_eq!(rounding.round(Uint128::new(1)).unwrap(), Uint128::new(1000));
+        assert_eq!(
+            rounding.round(Uint128::new(1499)).unwrap(),
+            Uint128::new(1000)
+        );
+        assert_eq!(
+            rounding.round(Uint128::new(1500)).unwrap(),
+            Uint128::new(2000)
+        );
+        assert_eq!(
+            rounding.round(Uint128::new(3000)).unwrap(),
+            Uint128::new(3000)
+        );
+        assert!(AmountRounding::Step {
+            step_minor: Uint128::new(2)
+        }
+        .round(Uint128::MAX)
+        .is_err());
+    }
+
+    #[test]
+    fn test_round_significant_digits() {
+        let rounding = AmountRounding::SignificantDigits { digits: 2 };
+        assert_eq!(rounding.round(Uint128::new(7)).unwrap(), Uint128::new(7));
+        assert_eq!(rounding.round(Uint128::new(99)).unwrap(), Uint128::new(99));
+        assert_eq!(
+            rounding.round(Uint128::new(1234)).unwrap(),
+            Uint128::new(1200)
+        );
+        assert_eq!(
+            rounding.round(Uint128::new(1250)).unwrap(),
+            Uint128::new(1300)
+        );
+        assert_eq!(
+            rounding.round(Uint128::new(996)).unwrap(),
+            Uint128::new(1000)
+        );
+    }
+
+    #[test]
+    fn test_validate_obfuscation_config() {
+        let config = |rounding| ObfuscationConfig {
+            rounding: Some(rounding),
+            commitment: false,
+        };
+        validate_obfuscation_config(&config(AmountRounding::Step {
+            step_minor: Uint128::one(),
+        }))
+        .unwrap();
+        validate_obfuscation_config(&config(AmountRounding::SignificantDigits { digits: 39 }))
+            .unwrap();
+        for rounding in [
+            AmountRounding::Step {
+                step_minor: Uint128::zero(),
+            },
+            AmountRounding::SignificantDigits { digits: 0 },
+            AmountRounding::SignificantDigits { digits: 40 },
+        ] {
+            assert_eq!(
+                validate_obfuscation_config(&config(rounding)).unwrap_err(),
+                ContractError::InvalidRounding {}
+            );
+        }
+    }
+}
@@ -1,6 +1,16 @@
-use crate::state::CONFIG;
+use crate::contract::{check_offer_window, offer_window_closes_on};
+use crate::error::ContractError;
+use crate::state::{
+    TeeKey, AGENT_DAILY_TRIBUTES, CONFIG, DAILY_NOMINAL_TOTALS, L2_ROOTS, TEE_KEYS, USED_CU_HASHES,
+    USED_TRIBUTE_IDS,
+};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{entry_point, to_json_binary, Addr, Binary, Deps, Env, StdResult};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, Env, Order, StdError, StdResult, Timestamp, Uint128,
+};
+use outbe_utils::date::WorldwideDay;
 use outbe_utils::Base58Binary;
 
 #[cw_serde]
@@ -9,9 +19,31 @@ pub enum QueryMsg {
     /// Returns config
     #[returns(ConfigResponse)]
     GetConfig {},
-    /// Returns TEE encryption info
+    /// Returns the TEE keys offers can be encrypted to
     #[returns(EncryptionInfoResponse)]
     EncryptionInfo {},
+    /// Returns the number of tributes an agent relayed for a worldwide day
+    #[returns(AgentTributesResponse)]
+    AgentTributes {
+        agent: Addr,
+        worldwide_day: WorldwideDay,
+    },
+    /// Returns whether tributes of a worldwide day can still be offered
+    #[returns(OfferWindowResponse)]
+    OfferWindow { day: WorldwideDay },
+    /// Returns the nominal amounts of the tributes offered for a worldwide day, exact and as
+    /// recorded on the rounded Tributes
+    #[returns(NominalTotalsResponse)]
+    NominalTotals { worldwide_day: WorldwideDay },
+    /// Returns the trusted L2 merkle root of a worldwide day
+    #[returns(L2RootResponse)]
+    L2Root { worldwide_day: WorldwideDay },
+    /// Returns whether a consumption unit hash has been offered
+    #[returns(UsedResponse)]
+    UsedCuHash { cu_hash: Base58Binary },
+    /// Returns whether a tribute draft id has been offered
+    #[returns(UsedResponse)]
+    UsedTributeDraftId { tribute_draft_id: Base58Binary },
 }
 
 #[cw_serde]
@@ -21,30 +53,159 @@ pub struct ConfigResponse {
 
 #[cw_serde]
 pub struct EncryptionInfoResponse {
+    /// Key to encrypt offers to, the latest activated key that is not retired
+    pub current: Option<TeePublicKey>,
+    /// Keys activating later, ordered by activation time
+    pub upcoming: Vec<TeePublicKey>,
+}
+
+#[cw_serde]
+pub struct AgentTributesResponse {
+    pub agent: Addr,
+    pub worldwide_day: WorldwideDay,
+    pub count: u64,
+}
+
+#[cw_serde]
+pub struct OfferWindowResponse {
+    pub day: WorldwideDay,
+    /// Days after a worldwide day its tributes can still be offered
+    pub offer_window_days: u32,
+    /// Last day tributes of the day can be offered
+    pub closes_on: WorldwideDay,
+    pub open: bool,
+}
+
+#[cw_serde]
+pub struct NominalTotalsResponse {
+    pub worldwide_day: WorldwideDay,
+    pub exact_minor: Uint128,
+    /// Sum of `nominal_amount_minor` of the minted Tributes, `exact_minor` minus it is
+    /// the amount lost or gained by rounding
+    pub published_minor: Uint128,
+}
+
+#[cw_serde]
+pub struct L2RootResponse {
+    pub worldwide_day: WorldwideDay,
+    pub merkle_root: Option<Base58Binary>,
+}
+
+#[cw_serde]
+pub struct UsedResponse {
+    pub used: bool,
+    /// Worldwide day of the tribute it was offered with. Entries are pruned once
+    /// the offer window of the day is closed, offers for such days are rejected anyway.
+    pub worldwide_day: Option<WorldwideDay>,
+}
+
+#[cw_serde]
+pub struct TeePublicKey {
+    pub key_id: u32,
     pub public_key: Base58Binary,
     pub salt: Base58Binary,
+    pub activates_at: Timestamp,
+}
+
+impl From<Option<WorldwideDay>> for UsedResponse {
+    fn from(worldwide_day: Option<WorldwideDay>) -> Self {
+        UsedResponse {
+            used: worldwide_day.is_some(),
+            worldwide_day,
+        }
+    }
+}
+
+impl From<TeeKey> for TeePublicKey {
+    fn from(key: TeeKey) -> Self {
+        TeePublicKey {
+            key_id: key.key_id,
+            public_key: key.public_key,
+            salt: key.salt,
+            activates_at: key.activates_at,
+        }
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     let config = CONFIG.load(deps.storage)?;
     match msg {
         QueryMsg::GetConfig {} => to_json_binary(&ConfigResponse {
             tribute_address: config.tribute_address,
         }),
-        QueryMsg::EncryptionInfo {} => {
-            let result = config
-                .tee_config
-                .map(|it| EncryptionInfoResponse {
-                    public_key: it.public_key,
-                    salt: it.salt,
-                })
-                .unwrap_or(EncryptionInfoResponse {
-                    public_key: Base58Binary::default(),
-                    salt: Base58Binary::default(),
-                });
-
-            to_json_binary(&result)
+        QueryMsg::EncryptionInfo {} => to_json_binary(&query_encryption_info(deps, env)?),
+        QueryMsg::OfferWindow { day } => {
+            let closes_on = offer_window_closes_on(day, config.offer_window_days)
+                .map_err(|err| StdError::generic_err(err.to_string()))?;
+            to_json_binary(&OfferWindowResponse {
+                day,
+                offer_window_days: config.offer_window_days,
+                closes_on,
+                open: match check_offer_window(
+                    deps.storage,
+                    day,
+                    &env.block.time,
+                    config.offer_window_days,
+                ) {
+                    Ok(()) => true,
+                    Err(ContractError::ClosedOfferWindow {}) => false,
+                    Err(err) => return Err(StdError::generic_err(err.to_string())),
+                },
+            })
+        }
+        QueryMsg::NominalTotals { worldwide_day } => {
+            let totals = DAILY_NOMINAL_TOTALS
+                .may_load(deps.storage, worldwide_day)?
+                .unwrap_or_default();
+            to_json_binary(&NominalTotalsResponse {
+                worldwide_day,
+                exact_minor: totals.exact_minor,
+                published_minor: totals.published_minor,
+            })
         }
+        QueryMsg::L2Root { worldwide_day } => to_json_binary(&L2RootResponse {
+            worldwide_day,
+            merkle_root: L2_ROOTS.may_load(deps.storage, worldwide_day)?,
+        }),
+        QueryMsg::UsedCuHash { cu_hash } => to_json_binary(&UsedResponse::from(
+            USED_CU_HASHES.used_on(deps.storage, &cu_hash.to_base58())?,
+        )),
+        QueryMsg::UsedTributeDraftId { tribute_draft_id } => to_json_binary(&UsedResponse::from(
+            USED_TRIBUTE_IDS.used_on(deps.storage, &tribute_draft_id.to_base58())?,
+        )),
+        QueryMsg::AgentTributes {
+            agent,
+            worldwide_day,
+        } => to_json_binary(&AgentTributesResponse {
+            count: AGENT_DAILY_TRIBUTES
+                .may_load(deps.storage, (&agent, worldwide_day))?
+                .unwrap_or_default(),
+            agent,
+            worldwide_day,
+        }),
     }
 }
+
+fn query_encryption_info(deps: Deps, env: Env) -> StdResult<EncryptionInfoResponse> {
+    let now = env.block.time;
+    let keys = TEE_KEYS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, key)| key))
+        // retired keys are still accepted during their grace period but not advertised
+        .filter(|item| item.as_ref().map_or(true, |key| key.expires_at.is_none()))
+        .collect::<StdResult<Vec<TeeKey>>>()?;
+
+    let (active, mut upcoming): (Vec<TeeKey>, Vec<TeeKey>) =
+        keys.into_iter().partition(|key| key.activates_at <= now);
+    let current = active
+        .into_iter()
+        .max_by_key(|key| (key.activates_at, key.key_id))
+        .map(TeePublicKey::from);
+    upcoming.sort_by_key(|key| (key.activates_at, key.key_id));
+
+    Ok(EncryptionInfoResponse {
+        current,
+        upcoming: upcoming.into_iter().map(TeePublicKey::from).collect(),
+    })
+}
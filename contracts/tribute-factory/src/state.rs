@@ -1,7 +1,9 @@
+use crate::msg::ObfuscationConfig;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Empty};
+use cosmwasm_std::{Addr, Empty, Order, StdResult, Storage, Timestamp, Uint128};
 use cw_ownable::{OwnershipStore, OWNERSHIP_KEY};
-use cw_storage_plus::{Item, Map};
+use cw_storage_plus::{Bound, Item, Map};
+use outbe_utils::date::WorldwideDay;
 use outbe_utils::Base58Binary;
 
 pub const OWNER: OwnershipStore = OwnershipStore::new(OWNERSHIP_KEY);
@@ -9,17 +11,267 @@ pub const OWNER: OwnershipStore = OwnershipStore::new(OWNERSHIP_KEY);
 #[cw_serde]
 pub struct Config {
     pub tribute_address: Option<Addr>,
-    pub tee_config: Option<TeeConfig>,
+    #[serde(default)]
+    pub zk_config: Option<ZkConfig>,
+    #[serde(default)]
+    pub agent_attestation: Option<AgentAttestationConfig>,
+    /// Days after a worldwide day its tributes can still be offered, see `offer_window_closes_on`
+    #[serde(default = "default_offer_window_days")]
+    pub offer_window_days: u32,
+    #[serde(default)]
+    pub obfuscation: Option<ObfuscationConfig>,
+    /// Rejects offers whose draft id is not `generate_tribute_draft_id_hash` of their owner and
+    /// day. Off until the derivation is confirmed against vectors of the L2 circuit.
+    #[serde(default)]
+    pub check_draft_id: bool,
 }
 
+pub const DEFAULT_OFFER_WINDOW_DAYS: u32 = 3;
+
+fn default_offer_window_days() -> u32 {
+    DEFAULT_OFFER_WINDOW_DAYS
+}
+
+/// Config layout before the TEE keyring, kept to migrate its TEE key
 #[cw_serde]
-pub struct TeeConfig {
+pub struct ConfigV1 {
+    pub tribute_address: Option<Addr>,
+    pub tee_config: Option<TeeConfigV1>,
+    #[serde(default)]
+    pub zk_config: Option<ZkConfig>,
+}
+
+#[cw_serde]
+pub struct TeeConfigV1 {
     pub private_key: Base58Binary,
     pub public_key: Base58Binary,
     pub salt: Base58Binary,
 }
 
+/// X25519 key of the TEE keyring
+#[cw_serde]
+pub struct TeeKey {
+    pub key_id: u32,
+    pub private_key: Base58Binary,
+    pub public_key: Base58Binary,
+    pub salt: Base58Binary,
+    /// Offers can be encrypted to the key from this time
+    pub activates_at: Timestamp,
+    /// Set when the key is retired, the key is wiped once this time has passed
+    pub expires_at: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct ZkConfig {
+    pub verification_keys: Vec<Base58Binary>,
+}
+
+/// Registries of the agents that can attest offers
+#[cw_serde]
+pub struct AgentAttestationConfig {
+    /// Offers without an attestation are rejected if set
+    pub required: bool,
+    pub cra_registry: Addr,
+    pub iba_registry: Addr,
+}
+
 pub const CONFIG: Item<Config> = Item::new("config");
-pub const USED_TRIBUTE_IDS: Map<String, Empty> = Map::new("used_ids");
+pub const CONFIG_V1: Item<ConfigV1> = Item::new("config");
+pub const TEE_KEYS: Map<u32, TeeKey> = Map::new("tee_keys");
+
+/// Tribute draft ids already offered, by the worldwide day of their tribute
+pub const USED_TRIBUTE_IDS: UsedSet =
+    UsedSet::new("used_tribute_ids", "used_tribute_ids_by_day", "used_ids");
+/// Consumption unit hashes already offered, with the worldwide day of their tribute. A CU hash
+/// does not commit to a day, so they are never pruned and not indexed by day.
+pub const USED_CU_HASHES: UsedSet = UsedSet::unindexed("used_cu_hash_days", "used_cu_hashes");
+/// Offers of days before this one are rejected, their used entries may be pruned
+pub const PRUNED_BEFORE: Item<WorldwideDay> = Item::new("pruned_before");
+/// Day the entries used before the sets were keyed by worldwide day are accounted to,
+/// set on migration
+pub const LEGACY_USED_DAY: Item<WorldwideDay> = Item::new("legacy_used_day");
+
+/// Set of keys used by offers, indexed by worldwide day so the entries of days whose offer
+/// window is closed can be pruned. An unindexed set is never pruned.
+pub struct UsedSet {
+    days: Map<String, WorldwideDay>,
+    by_day: Option<Map<(WorldwideDay, String), Empty>>,
+    legacy: Map<String, Empty>,
+}
+
+impl UsedSet {
+    pub const fn new(
+        days_namespace: &'static str,
+        by_day_namespace: &'static str,
+        legacy_namespace: &'static str,
+    ) -> Self {
+        UsedSet {
+            days: Map::new(days_namespace),
+            by_day: Some(Map::new(by_day_namespace)),
+            legacy: Map::new(legacy_namespace),
+        }
+    }
+
+    pub const fn unindexed(days_namespace: &'static str, legacy_namespace: &'static str) -> Self {
+        UsedSet {
+            days: Map::new(days_namespace),
+            by_day: None,
+            legacy: Map::new(legacy_namespace),
+        }
+    }
+
+    /// Returns the worldwide day the key was used for
+    pub fn used_on(&self, storage: &dyn Storage, key: &str) -> StdResult<Option<WorldwideDay>> {
+        if let Some(day) = self.days.may_load(storage, key.to_string())? {
+            return Ok(Some(day));
+        }
+        if self.legacy.has(storage, key.to_string()) {
+            return Ok(Some(LEGACY_USED_DAY.may_load(storage)?.unwrap_or_default()));
+        }
+        Ok(None)
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage, key: &str, day: WorldwideDay) -> StdResult<()> {
+        self.days.save(storage, key.to_string(), &day)?;
+        match &self.by_day {
+            Some(by_day) => by_day.save(storage, (day, key.to_string()), &Empty {}),
+            None => Ok(()),
+        }
+    }
+
+    /// Removes up to `limit` entries of days before `expired_before`, returns the number removed
+    pub fn prune(
+        &self,
+        storage: &mut dyn Storage,
+        expired_before: WorldwideDay,
+        limit: usize,
+    ) -> StdResult<usize> {
+        let Some(by_day) = &self.by_day else {
+            return Ok(0);
+        };
+        let expired = by_day
+            .keys(
+                storage,
+                None,
+                Some(Bound::exclusive((expired_before, String::new()))),
+                Order::Ascending,
+            )
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (day, key) in expired.iter() {
+            by_day.remove(storage, (*day, key.clone()));
+            self.days.remove(storage, key.clone());
+        }
+        let mut pruned = expired.len();
+
+        if pruned < limit
+            && LEGACY_USED_DAY
+                .may_load(storage)?
+                .is_some_and(|day| day < expired_before)
+        {
+            let legacy = self
+                .legacy
+                .keys(storage, None, None, Order::Ascending)
+                .take(limit - pruned)
+                .collect::<StdResult<Vec<_>>>()?;
+            for key in legacy.iter() {
+                self.legacy.remove(storage, key.clone());
+            }
+            pruned += legacy.len();
+        }
+        Ok(pruned)
+    }
+
+    /// Returns true if entries of days before `expired_before` are left
+    pub fn has_expired(
+        &self,
+        storage: &dyn Storage,
+        expired_before: WorldwideDay,
+    ) -> StdResult<bool> {
+        let Some(by_day) = &self.by_day else {
+            return Ok(false);
+        };
+        let expired = by_day
+            .keys(
+                storage,
+                None,
+                Some(Bound::exclusive((expired_before, String::new()))),
+                Order::Ascending,
+            )
+            .next()
+            .is_some();
+        let legacy_expired = LEGACY_USED_DAY
+            .may_load(storage)?
+            .is_some_and(|day| day < expired_before)
+            && self.has_legacy(storage);
+        Ok(expired || legacy_expired)
+    }
+
+    pub fn has_legacy(&self, storage: &dyn Storage) -> bool {
+        self.legacy
+            .keys_raw(storage, None, None, Order::Ascending)
+            .next()
+            .is_some()
+    }
+
+    pub fn clear(&self, storage: &mut dyn Storage) {
+        self.days.clear(storage);
+        if let Some(by_day) = &self.by_day {
+            by_day.clear(storage);
+        }
+        self.legacy.clear(storage);
+    }
+}
+
+/// Nominal amounts of the tributes offered for a worldwide day, exact and as recorded on
+/// the Tributes. Metadosis adds the difference to the total interest of the day, offered
+/// Tributes can not be burned so the totals keep matching it.
+#[cw_serde]
+#[derive(Default)]
+pub struct NominalTotals {
+    pub exact_minor: Uint128,
+    pub published_minor: Uint128,
+}
+
+pub const DAILY_NOMINAL_TOTALS: Map<WorldwideDay, NominalTotals> = Map::new("daily_nominal_totals");
+
+/// Removes up to `limit` nominal totals of days before `expired_before`, returns the number
+/// removed
+pub fn prune_nominal_totals(
+    storage: &mut dyn Storage,
+    expired_before: WorldwideDay,
+    limit: usize,
+) -> StdResult<usize> {
+    let expired = DAILY_NOMINAL_TOTALS
+        .keys(
+            storage,
+            None,
+            Some(Bound::exclusive(expired_before)),
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    for day in expired.iter() {
+        DAILY_NOMINAL_TOTALS.remove(storage, *day);
+    }
+    Ok(expired.len())
+}
+
+/// Returns true if nominal totals of days before `expired_before` are left
+pub fn has_expired_nominal_totals(storage: &dyn Storage, expired_before: WorldwideDay) -> bool {
+    DAILY_NOMINAL_TOTALS
+        .keys_raw(
+            storage,
+            None,
+            Some(Bound::exclusive(expired_before)),
+            Order::Ascending,
+        )
+        .next()
+        .is_some()
+}
+
+/// Trusted merkle root of the L2 state of a worldwide day, offer proofs are checked against it
+pub const L2_ROOTS: Map<WorldwideDay, Base58Binary> = Map::new("l2_roots");
 
-pub const USED_CU_HASHES: Map<String, Empty> = Map::new("used_cu_hashes");
+/// Number of tributes relayed by an agent for a worldwide day
+pub const AGENT_DAILY_TRIBUTES: Map<(&Addr, WorldwideDay), u64> = Map::new("agent_daily_tributes");
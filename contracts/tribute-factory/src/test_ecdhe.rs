@@ -1,9 +1,9 @@
 use crate::error::ContractError;
-use crate::state::TeeConfig;
+use crate::state::TeeKey;
 use crate::types::TributeInputPayload;
 use chacha20poly1305::aead::Aead;
 use chacha20poly1305::{ChaCha20Poly1305, KeyInit, Nonce};
-use cosmwasm_std::{Uint128, Uint64};
+use cosmwasm_std::{Timestamp, Uint128, Uint64};
 use curve25519_dalek::{MontgomeryPoint, Scalar};
 use hkdf::Hkdf;
 use outbe_utils::denom::Currency;
@@ -24,7 +24,7 @@ pub(crate) fn generate_keypair() -> ([u8; 32], [u8; 32]) {
     (private_key_bytes, public_key_bytes)
 }
 
-fn encrypt_tribute_input(
+pub(crate) fn encrypt_tribute_input(
     tribute_input: &TributeInputPayload,
     contract_public_key: &[u8; 32],
     salt: &[u8; 32],
@@ -89,27 +89,28 @@ fn test_decrypt_tribute_input() {
         nominal_base_amount: Uint64::new(500),
         nominal_atto_amount: Uint128::zero(),
         owner: Base58Binary::from("test_owner".as_bytes()),
+        vector_id: None,
+        amounts_blinding: None,
     };
 
-    // Create TeeConfig with salt
+    // Create TeeKey with salt
     let salt = [1u8; 32];
-    let tee_config = TeeConfig {
+    let tee_key = TeeKey {
+        key_id: 0,
         private_key: Base58Binary::from(private_key),
         public_key: Base58Binary::from(public_key),
         salt: Base58Binary::from(salt),
+        activates_at: Timestamp::from_seconds(0),
+        expires_at: None,
     };
 
     // Encrypt tribute input (client side)
     let (cipher_text, nonce, ephemeral_pubkey) =
         encrypt_tribute_input(&tribute_input, &public_key, &salt).unwrap();
 
-    let decrypted_input = crate::contract::decrypt_tribute_input(
-        &cipher_text,
-        &nonce,
-        &ephemeral_pubkey,
-        &tee_config,
-    )
-    .unwrap();
+    let decrypted_input =
+        crate::contract::decrypt_tribute_input(&cipher_text, &nonce, &ephemeral_pubkey, &tee_key)
+            .unwrap();
 
     // Verify decryption worked correctly
     assert_eq!(decrypted_input, tribute_input);
@@ -117,17 +118,20 @@ fn test_decrypt_tribute_input() {
 
 #[test]
 fn test_decrypt_tribute_input_invalid_key_size() {
-    let tee_config = TeeConfig {
+    let tee_key = TeeKey {
+        key_id: 0,
         private_key: Base58Binary::from([1u8; 16]), // Invalid size
         public_key: Base58Binary::from([1u8; 32]),
         salt: Base58Binary::from([1u8; 32]),
+        activates_at: Timestamp::from_seconds(0),
+        expires_at: None,
     };
 
     let result = crate::contract::decrypt_tribute_input(
         &Base58Binary::from([1u8; 32]),
         &Base58Binary::from([1u8; 12]),
         &Base58Binary::from([1u8; 32]),
-        &tee_config,
+        &tee_key,
     );
 
     assert!(matches!(result, Err(ContractError::InvalidKey {})));
@@ -135,17 +139,20 @@ fn test_decrypt_tribute_input_invalid_key_size() {
 
 #[test]
 fn test_decrypt_tribute_input_invalid_nonce_size() {
-    let tee_config = TeeConfig {
+    let tee_key = TeeKey {
+        key_id: 0,
         private_key: Base58Binary::from([1u8; 32]),
         public_key: Base58Binary::from([1u8; 32]),
         salt: Base58Binary::from([1u8; 32]),
+        activates_at: Timestamp::from_seconds(0),
+        expires_at: None,
     };
 
     let result = crate::contract::decrypt_tribute_input(
         &Base58Binary::from([1u8; 32]),
         &Base58Binary::from([1u8; 8]), // Invalid size
         &Base58Binary::from([1u8; 32]),
-        &tee_config,
+        &tee_key,
     );
 
     assert!(matches!(result, Err(ContractError::InvalidNonce {})));
@@ -0,0 +1,483 @@
+//! Test prover of the offer circuit, used to produce the fixtures of `fixtures/`.
+//! Its setup is built from a known secret, so it must never be used to produce real keys.
+use crate::contract::generate_tribute_draft_id_hash;
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, ZkProof, ZkProofPublicData, ZkSetup};
+use crate::state::ZkConfig;
+use crate::types::TributeInputPayload;
+use crate::zk::{
+    public_inputs, validate_zk_config, verify_offer, Proof, Transcript, VerificationKey,
+};
+use ark_bls12_381::{Fr, G1Affine, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{Field, One, Zero};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{
+    DenseUVPolynomial, EvaluationDomain, Evaluations, Polynomial, Radix2EvaluationDomain,
+};
+use ark_serialize::CanonicalSerialize;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::testing::mock_dependencies;
+use cosmwasm_std::{from_json, Addr, Uint128, Uint64};
+use outbe_utils::denom::Currency;
+use outbe_utils::Base58Binary;
+
+/// Secret of the insecure setup
+const TAU: u64 = 0x5eed;
+/// Rows: the three public inputs, then `public_key * payload_commitment`
+const N: usize = 4;
+
+type Poly = DensePolynomial<Fr>;
+
+pub(crate) const OFFER_FIXTURE: &str = include_str!("../fixtures/offer-insecure-zk.json");
+pub(crate) const ZK_CONFIG_FIXTURE: &str = include_str!("../fixtures/update-zk-config.json");
+
+/// Circuit with the public inputs on the first rows and a gate multiplying the owner public
+/// key by the payload commitment, both wired to their public input rows
+struct Circuit {
+    domain: Radix2EvaluationDomain<Fr>,
+    k: [Fr; 3],
+    q_m: Poly,
+    q_l: Poly,
+    q_r: Poly,
+    q_o: Poly,
+    q_c: Poly,
+    s_sigma: [Poly; 3],
+    /// Evaluations of the permutation on the domain
+    sigma: [Vec<Fr>; 3],
+}
+
+impl Circuit {
+    fn new() -> Self {
+        let domain = Radix2EvaluationDomain::<Fr>::new(N).unwrap();
+        let k = [Fr::one(), Fr::from(7u64), Fr::from(49u64)];
+        let id = |column: usize, row: usize| k[column] * domain.element(row);
+
+        // copies: a[0] <-> a[3] and a[2] <-> b[3]
+        let copy = |column: usize, row: usize| match (column, row) {
+            (0, 0) => (0, 3),
+            (0, 3) => (0, 0),
+            (0, 2) => (1, 3),
+            (1, 3) => (0, 2),
+            other => other,
+        };
+        let sigma: [Vec<Fr>; 3] = core::array::from_fn(|column| {
+            (0..N)
+                .map(|row| {
+                    let (column, row) = copy(column, row);
+                    id(column, row)
+                })
+                .collect()
+        });
+
+        let one = Fr::one();
+        let zero = Fr::zero();
+        Self {
+            domain,
+            k,
+            q_m: interpolate(domain, vec![zero, zero, zero, one]),
+            q_l: interpolate(domain, vec![one, one, one, zero]),
+            q_r: interpolate(domain, vec![zero; N]),
+            q_o: interpolate(domain, vec![zero, zero, zero, -one]),
+            q_c: interpolate(domain, vec![zero; N]),
+            s_sigma: sigma.clone().map(|values| interpolate(domain, values)),
+            sigma,
+        }
+    }
+
+    fn verification_key(&self) -> VerificationKey {
+        VerificationKey {
+            n: N as u64,
+            num_public_inputs: 3,
+            k1: self.k[1],
+            k2: self.k[2],
+            q_m: commit(&self.q_m),
+            q_l: commit(&self.q_l),
+            q_r: commit(&self.q_r),
+            q_o: commit(&self.q_o),
+            q_c: commit(&self.q_c),
+            s_sigma1: commit(&self.s_sigma[0]),
+            s_sigma2: commit(&self.s_sigma[1]),
+            s_sigma3: commit(&self.s_sigma[2]),
+            x_2: (G2Affine::generator() * Fr::from(TAU)).into_affine(),
+        }
+    }
+
+    fn prove(&self, vk_bytes: &[u8], inputs: &[Fr; 3]) -> Proof {
+        let domain = self.domain;
+        let [x0, x1, x2] = *inputs;
+        let zero = Fr::zero();
+        let wires: [Vec<Fr>; 3] = [
+            vec![x0, x1, x2, x0],
+            vec![zero, zero, zero, x2],
+            vec![zero, zero, zero, x0 * x2],
+        ];
+        let [a, b, c] = wires.clone().map(|values| interpolate(domain, values));
+
+        let mut transcript = Transcript::new(vk_bytes, inputs);
+        let (a_commit, b_commit, c_commit) = (commit(&a), commit(&b), commit(&c));
+        transcript.append(&a_commit);
+        transcript.append(&b_commit);
+        transcript.append(&c_commit);
+        let beta = transcript.challenge(b"beta");
+        let gamma = transcript.challenge(b"gamma");
+
+        // permutation accumulator
+        let mut z_values = vec![Fr::one()];
+        for row in 0..N - 1 {
+            let mut numerator = Fr::one();
+            let mut denominator = Fr::one();
+            for (column, wire) in wires.iter().map(|values| values[row]).enumerate() {
+                numerator *= wire + beta * self.k[column] * domain.element(row) + gamma;
+                denominator *= wire + beta * self.sigma[column][row] + gamma;
+            }
+            z_values.push(z_values[row] * numerator * denominator.inverse().unwrap());
+        }
+        let z = interpolate(domain, z_values);
+        let z_commit = commit(&z);
+        transcript.append(&z_commit);
+        let alpha = transcript.challenge(b"alpha");
+
+        // quotient
+        let x = Poly::from_coefficients_vec(vec![zero, Fr::one()]);
+        let pi = interpolate(domain, vec![-x0, -x1, -x2, zero]);
+        let l0 = interpolate(domain, vec![Fr::one(), zero, zero, zero]);
+        let omega = domain.group_gen();
+        let z_shifted = Poly::from_coefficients_vec(
+            z.coeffs
+                .iter()
+                .enumerate()
+                .map(|(i, coeff)| *coeff * omega.pow([i as u64]))
+                .collect(),
+        );
+        let gate = &(&(&(&(&(&a * &b) * &self.q_m) + &(&a * &self.q_l)) + &(&b * &self.q_r))
+            + &(&c * &self.q_o))
+            + &(&pi + &self.q_c);
+        let permutation_id = &(&(&(&a + &(&x * beta)) + &constant(gamma))
+            * &(&(&b + &(&x * (beta * self.k[1]))) + &constant(gamma)))
+            * &(&(&(&c + &(&x * (beta * self.k[2]))) + &constant(gamma)) * &z);
+        let permutation_sigma = &(&(&(&a + &(&self.s_sigma[0] * beta)) + &constant(gamma))
+            * &(&(&b + &(&self.s_sigma[1] * beta)) + &constant(gamma)))
+            * &(&(&(&c + &(&self.s_sigma[2] * beta)) + &constant(gamma)) * &z_shifted);
+        let first_row = &(&z - &constant(Fr::one())) * &l0;
+        let numerator = &(&gate + &(&(&permutation_id - &permutation_sigma) * alpha))
+            + &(&first_row * alpha.square());
+        let (t, remainder) = numerator.divide_by_vanishing_poly(domain).unwrap();
+        assert!(remainder.is_zero(), "witness does not satisfy the circuit");
+        let mut t_parts = t.coeffs.chunks(N).map(Poly::from_coefficients_slice);
+        let t_lo = t_parts.next().unwrap_or_default();
+        let t_mid = t_parts.next().unwrap_or_default();
+        let t_hi = t_parts.next().unwrap_or_default();
+        assert!(t_parts.next().is_none());
+        let (t_lo_commit, t_mid_commit, t_hi_commit) =
+            (commit(&t_lo), commit(&t_mid), commit(&t_hi));
+        transcript.append(&t_lo_commit);
+        transcript.append(&t_mid_commit);
+        transcript.append(&t_hi_commit);
+        let zeta = transcript.challenge(b"zeta");
+
+        // evaluations
+        let a_eval = a.evaluate(&zeta);
+        let b_eval = b.evaluate(&zeta);
+        let c_eval = c.evaluate(&zeta);
+        let s_sigma1_eval = self.s_sigma[0].evaluate(&zeta);
+        let s_sigma2_eval = self.s_sigma[1].evaluate(&zeta);
+        let z_omega_eval = z.evaluate(&(zeta * omega));
+        for eval in [
+            &a_eval,
+            &b_eval,
+            &c_eval,
+            &s_sigma1_eval,
+            &s_sigma2_eval,
+            &z_omega_eval,
+        ] {
+            transcript.append(eval);
+        }
+        let v = transcript.challenge(b"v");
+
+        // linearisation and openings
+        let zeta_n = zeta.pow([N as u64]);
+        let zh_zeta = zeta_n - Fr::one();
+        let l0_zeta = l0.evaluate(&zeta);
+        let sigma_part = alpha
+            * (a_eval + beta * s_sigma1_eval + gamma)
+            * (b_eval + beta * s_sigma2_eval + gamma)
+            * z_omega_eval;
+        let r = &(&(&(&(&(&self.q_m * (a_eval * b_eval)) + &(&self.q_l * a_eval))
+            + &(&self.q_r * b_eval))
+            + &(&self.q_o * c_eval))
+            + &(&self.q_c + &constant(pi.evaluate(&zeta))))
+            + &(&(&(&z
+                * (alpha
+                    * (a_eval + beta * zeta + gamma)
+                    * (b_eval + beta * self.k[1] * zeta + gamma)
+                    * (c_eval + beta * self.k[2] * zeta + gamma)))
+                - &(&(&(&self.s_sigma[2] * beta) + &constant(c_eval + gamma)) * sigma_part))
+                + &(&(&(&z - &constant(Fr::one())) * (alpha.square() * l0_zeta))
+                    - &(&(&(&t_lo + &(&t_mid * zeta_n)) + &(&t_hi * zeta_n.square())) * zh_zeta)));
+        assert!(r.evaluate(&zeta).is_zero());
+
+        let mut opening = r;
+        let mut power = Fr::one();
+        for (poly, eval) in [
+            (&a, a_eval),
+            (&b, b_eval),
+            (&c, c_eval),
+            (&self.s_sigma[0], s_sigma1_eval),
+            (&self.s_sigma[1], s_sigma2_eval),
+        ] {
+            power *= v;
+            opening = &opening + &(&(poly - &constant(eval)) * power);
+        }
+        let w_zeta = commit(&divide_by_linear(&opening, zeta));
+        let w_zeta_omega = commit(&divide_by_linear(
+            &(&z - &constant(z_omega_eval)),
+            zeta * omega,
+        ));
+
+        Proof {
+            a: a_commit,
+            b: b_commit,
+            c: c_commit,
+            z: z_commit,
+            t_lo: t_lo_commit,
+            t_mid: t_mid_commit,
+            t_hi: t_hi_commit,
+            w_zeta,
+            w_zeta_omega,
+            a_eval,
+            b_eval,
+            c_eval,
+            s_sigma1_eval,
+            s_sigma2_eval,
+            z_omega_eval,
+        }
+    }
+}
+
+fn interpolate(domain: Radix2EvaluationDomain<Fr>, values: Vec<Fr>) -> Poly {
+    Evaluations::from_vec_and_domain(values, domain).interpolate()
+}
+
+fn constant(value: Fr) -> Poly {
+    Poly::from_coefficients_vec(vec![value])
+}
+
+fn commit(poly: &Poly) -> G1Affine {
+    (G1Affine::generator() * poly.evaluate(&Fr::from(TAU))).into_affine()
+}
+
+/// Quotient of `poly / (X - root)`, the remainder is dropped
+fn divide_by_linear(poly: &Poly, root: Fr) -> Poly {
+    let mut quotient = vec![Fr::zero(); poly.coeffs.len().saturating_sub(1)];
+    let mut carry = Fr::zero();
+    for i in (1..poly.coeffs.len()).rev() {
+        carry = poly.coeffs[i] + carry * root;
+        quotient[i - 1] = carry;
+    }
+    Poly::from_coefficients_vec(quotient)
+}
+
+fn to_base58(item: &impl CanonicalSerialize) -> Base58Binary {
+    let mut bytes = vec![];
+    item.serialize_compressed(&mut bytes).unwrap();
+    Base58Binary::from(bytes)
+}
+
+fn fixture_tribute_input() -> TributeInputPayload {
+    let owner = Base58Binary::from([8u8; 32]);
+    TributeInputPayload {
+        tribute_draft_id: generate_tribute_draft_id_hash(&owner, 20250322).unwrap(),
+        owner,
+        worldwide_day: 20250322,
+        settlement_currency: Currency::Usd.into(),
+        settlement_base_amount: Uint64::new(500),
+        settlement_atto_amount: Uint128::zero(),
+        nominal_base_amount: Uint64::new(1000),
+        nominal_atto_amount: Uint128::zero(),
+        cu_hashes: vec![Base58Binary::from([9u8; 32])],
+        vector_id: None,
+        amounts_blinding: None,
+    }
+}
+
+/// `ExecuteMsg::OfferInsecure` of the offer fixture, readable without the `demo` feature
+#[cw_serde]
+enum OfferFixture {
+    OfferInsecure {
+        tribute_input: TributeInputPayload,
+        zk_proof: ZkProof,
+        tribute_owner_l1: Option<Addr>,
+    },
+}
+
+/// Proves the fixture offer, returns the `UpdateConfig` and `OfferInsecure` messages
+fn generate_fixtures() -> (ExecuteMsg, OfferFixture) {
+    let circuit = Circuit::new();
+    let verification_key = to_base58(&circuit.verification_key());
+    let tribute_input = fixture_tribute_input();
+    let mut zk_proof = ZkProof {
+        proof: Base58Binary::default(),
+        public_data: ZkProofPublicData {
+            public_key: tribute_input.owner.clone(),
+            merkle_root: Base58Binary::from([4u8; 32]),
+        },
+        verification_key: verification_key.clone(),
+    };
+    let inputs = public_inputs(&zk_proof, &tribute_input);
+    zk_proof.proof = to_base58(&circuit.prove(verification_key.as_slice(), &inputs));
+
+    (
+        ExecuteMsg::UpdateConfig {
+            new_owner: None,
+            new_tribute_address: None,
+            new_tee_config: None,
+            new_zk_config: Some(ZkSetup {
+                verification_keys: vec![verification_key],
+            }),
+            new_agent_attestation: None,
+            new_offer_window_days: None,
+            new_obfuscation: None,
+            new_check_draft_id: None,
+        },
+        OfferFixture::OfferInsecure {
+            tribute_input,
+            zk_proof,
+            tribute_owner_l1: None,
+        },
+    )
+}
+
+/// Verification keys, tribute input and proof of the fixtures
+pub(crate) fn load_fixtures() -> (ZkSetup, TributeInputPayload, ZkProof) {
+    let ExecuteMsg::UpdateConfig {
+        new_zk_config: Some(zk_setup),
+        ..
+    } = from_json(ZK_CONFIG_FIXTURE).unwrap()
+    else {
+        panic!("unexpected zk config fixture");
+    };
+    let OfferFixture::OfferInsecure {
+        tribute_input,
+        zk_proof,
+        ..
+    } = from_json(OFFER_FIXTURE).unwrap();
+    (zk_setup, tribute_input, zk_proof)
+}
+
+#[test]
+fn test_fixtures_are_up_to_date() {
+    let (update_config, offer) = generate_fixtures();
+    assert_eq!(
+        from_json::<ExecuteMsg>(ZK_CONFIG_FIXTURE).unwrap(),
+        update_config,
+        "update fixtures/update-zk-config.json with {}",
+        cosmwasm_std::to_json_string(&update_config).unwrap()
+    );
+    assert_eq!(
+        from_json::<OfferFixture>(OFFER_FIXTURE).unwrap(),
+        offer,
+        "update fixtures/offer-insecure-zk.json with {}",
+        cosmwasm_std::to_json_string(&offer).unwrap()
+    );
+}
+
+#[test]
+fn test_verify_fixture_proof() {
+    let deps = mock_dependencies();
+    let (zk_setup, tribute_input, zk_proof) = load_fixtures();
+    let zk_config = validate_zk_config(&zk_setup).unwrap();
+
+    let l2_root = zk_proof.public_data.merkle_root.clone();
+
+    verify_offer(&deps.api, &zk_config, &l2_root, &zk_proof, &tribute_input).unwrap();
+
+    // payload differs from the committed one
+    let mut tampered_input = tribute_input.clone();
+    tampered_input.settlement_base_amount = Uint64::new(501);
+    let err =
+        verify_offer(&deps.api, &zk_config, &l2_root, &zk_proof, &tampered_input).unwrap_err();
+    assert_eq!(err, ContractError::InvalidZkProof {});
+
+    // proof made against another tree than the trusted one
+    let other_root = Base58Binary::from([5u8; 32]);
+    let err = verify_offer(
+        &deps.api,
+        &zk_config,
+        &other_root,
+        &zk_proof,
+        &tribute_input,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::UnknownMerkleRoot {});
+
+    // proof made by someone else than the owner
+    let mut tampered_proof = zk_proof.clone();
+    tampered_proof.public_data.public_key = Base58Binary::from([3u8; 32]);
+    let err = verify_offer(
+        &deps.api,
+        &zk_config,
+        &l2_root,
+        &tampered_proof,
+        &tribute_input,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ProofOwnerMismatch {});
+
+    // public data differs from the proven one
+    let mut tampered_proof = zk_proof.clone();
+    tampered_proof.public_data.merkle_root = other_root.clone();
+    let err = verify_offer(
+        &deps.api,
+        &zk_config,
+        &other_root,
+        &tampered_proof,
+        &tribute_input,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidZkProof {});
+
+    // proof is not a proof
+    let mut tampered_proof = zk_proof.clone();
+    tampered_proof.proof = Base58Binary::from([1u8; 16]);
+    let err = verify_offer(
+        &deps.api,
+        &zk_config,
+        &l2_root,
+        &tampered_proof,
+        &tribute_input,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidZkProof {});
+
+    // verification key is not configured
+    let err = verify_offer(
+        &deps.api,
+        &ZkConfig {
+            verification_keys: vec![],
+        },
+        &l2_root,
+        &zk_proof,
+        &tribute_input,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::UnknownVerificationKey {});
+}
+
+#[test]
+fn test_validate_zk_config() {
+    let (zk_setup, _, _) = load_fixtures();
+    assert!(validate_zk_config(&zk_setup).is_ok());
+
+    let err = validate_zk_config(&ZkSetup {
+        verification_keys: vec![],
+    })
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidVerificationKey {});
+
+    let err = validate_zk_config(&ZkSetup {
+        verification_keys: vec![Base58Binary::from([1u8; 32])],
+    })
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidVerificationKey {});
+}
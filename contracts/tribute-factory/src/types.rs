@@ -23,4 +23,10 @@ pub struct TributeInputPayload {
     /// Amount expressed in fractional units, `0 >= nominal_atto_amount < 1e18`
     pub nominal_atto_amount: Uint128,
     pub cu_hashes: Vec<Base58Binary>,
+    /// Vector the tribute joins, see the vector contract
+    #[serde(default)]
+    pub vector_id: Option<u16>,
+    /// 32 random bytes blinding the commitment to the amounts, see `ObfuscationConfig`
+    #[serde(default)]
+    pub amounts_blinding: Option<Base58Binary>,
 }
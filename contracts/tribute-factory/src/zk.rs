@@ -0,0 +1,319 @@
+use crate::error::ContractError;
+use crate::msg::{ZkProof, ZkSetup};
+use crate::state::ZkConfig;
+use crate::types::TributeInputPayload;
+use ark_bls12_381::{Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{FftField, Field, One, PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use cosmwasm_std::Api;
+use outbe_utils::Base58Binary;
+use sha2::{Digest, Sha256};
+
+/// Public inputs of the offer circuit, in this order:
+/// owner public key, L2 merkle root and payload commitment
+pub const NUM_PUBLIC_INPUTS: u64 = 3;
+
+/// Label of the Fiat-Shamir transcript. The transcript and the encodings are those of this
+/// contract, only checked against the test prover of `test_zk` so far, not the L2 prover.
+const TRANSCRIPT_LABEL: &[u8] = b"outbe:tribute-factory:plonk";
+
+/// PlonK verification key of a circuit over BLS12-381 with KZG commitments,
+/// serialized with the compressed `ark-serialize` encoding
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerificationKey {
+    /// Size of the evaluation domain, a power of two
+    pub n: u64,
+    pub num_public_inputs: u64,
+    /// Coset shifts of the second and third wire columns
+    pub k1: Fr,
+    pub k2: Fr,
+    pub q_m: G1Affine,
+    pub q_l: G1Affine,
+    pub q_r: G1Affine,
+    pub q_o: G1Affine,
+    pub q_c: G1Affine,
+    pub s_sigma1: G1Affine,
+    pub s_sigma2: G1Affine,
+    pub s_sigma3: G1Affine,
+    /// `[x]_2` of the structured reference string
+    pub x_2: G2Affine,
+}
+
+/// PlonK proof, serialized with the compressed `ark-serialize` encoding
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof {
+    pub a: G1Affine,
+    pub b: G1Affine,
+    pub c: G1Affine,
+    pub z: G1Affine,
+    pub t_lo: G1Affine,
+    pub t_mid: G1Affine,
+    pub t_hi: G1Affine,
+    pub w_zeta: G1Affine,
+    pub w_zeta_omega: G1Affine,
+    pub a_eval: Fr,
+    pub b_eval: Fr,
+    pub c_eval: Fr,
+    pub s_sigma1_eval: Fr,
+    pub s_sigma2_eval: Fr,
+    pub z_omega_eval: Fr,
+}
+
+impl VerificationKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ContractError> {
+        let vk = Self::deserialize_compressed(bytes)
+            .map_err(|_| ContractError::InvalidVerificationKey {})?;
+        if !vk.n.is_power_of_two()
+            || vk.num_public_inputs != NUM_PUBLIC_INPUTS
+            || vk.num_public_inputs > vk.n
+        {
+            return Err(ContractError::InvalidVerificationKey {});
+        }
+        Ok(vk)
+    }
+
+    fn omega(&self) -> Result<Fr, ContractError> {
+        Fr::get_root_of_unity(self.n).ok_or(ContractError::InvalidVerificationKey {})
+    }
+}
+
+impl Proof {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ContractError> {
+        Self::deserialize_compressed(bytes).map_err(|_| ContractError::InvalidZkProof {})
+    }
+}
+
+/// Fiat-Shamir transcript, each challenge is `sha256(transcript || label)` reduced modulo
+/// the scalar field order and is appended to the transcript in turn
+pub struct Transcript {
+    data: Vec<u8>,
+}
+
+impl Transcript {
+    pub fn new(verification_key: &[u8], public_inputs: &[Fr]) -> Self {
+        let mut transcript = Self {
+            data: TRANSCRIPT_LABEL.to_vec(),
+        };
+        transcript.data.extend_from_slice(verification_key);
+        for input in public_inputs {
+            transcript.append(input);
+        }
+        transcript
+    }
+
+    pub fn append(&mut self, item: &impl CanonicalSerialize) {
+        item.serialize_compressed(&mut self.data)
+            .expect("serialization into a vector never fails");
+    }
+
+    pub fn challenge(&mut self, label: &[u8]) -> Fr {
+        let digest = Sha256::new()
+            .chain_update(&self.data)
+            .chain_update(label)
+            .finalize();
+        self.data.extend_from_slice(&digest);
+        Fr::from_le_bytes_mod_order(&digest)
+    }
+}
+
+/// Maps a public value to the scalar field, reducing it modulo the field order like the circuit
+pub fn to_field(bytes: &[u8]) -> Fr {
+    Fr::from_be_bytes_mod_order(bytes)
+}
+
+/// `sha256` over the fields of the payload, lengths are big endian `u32`
+/// and numbers are big endian
+pub fn payload_commitment(payload: &TributeInputPayload) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for bytes in [
+        payload.tribute_draft_id.as_slice(),
+        payload.owner.as_slice(),
+    ] {
+        hasher.update((bytes.len() as u32).to_be_bytes());
+        hasher.update(bytes);
+    }
+    hasher.update(payload.worldwide_day.to_be_bytes());
+    hasher.update(payload.settlement_currency.to_be_bytes());
+    hasher.update(payload.settlement_base_amount.u64().to_be_bytes());
+    hasher.update(payload.settlement_atto_amount.u128().to_be_bytes());
+    hasher.update(payload.nominal_base_amount.u64().to_be_bytes());
+    hasher.update(payload.nominal_atto_amount.u128().to_be_bytes());
+    hasher.update((payload.cu_hashes.len() as u32).to_be_bytes());
+    for cu_hash in payload.cu_hashes.iter() {
+        hasher.update((cu_hash.len() as u32).to_be_bytes());
+        hasher.update(cu_hash.as_slice());
+    }
+    hasher.finalize().into()
+}
+
+/// Public inputs the proof of an offer is checked against
+pub fn public_inputs(zk_proof: &ZkProof, payload: &TributeInputPayload) -> [Fr; 3] {
+    [
+        to_field(zk_proof.public_data.public_key.as_slice()),
+        to_field(zk_proof.public_data.merkle_root.as_slice()),
+        to_field(&payload_commitment(payload)),
+    ]
+}
+
+pub fn validate_zk_config(zk_setup: &ZkSetup) -> Result<ZkConfig, ContractError> {
+    if zk_setup.verification_keys.is_empty() {
+        return Err(ContractError::InvalidVerificationKey {});
+    }
+    for key in zk_setup.verification_keys.iter() {
+        VerificationKey::from_bytes(key.as_slice())?;
+    }
+    Ok(ZkConfig {
+        verification_keys: zk_setup.verification_keys.clone(),
+    })
+}
+
+/// Verifies the proof of an offer with its verification key, which has to be one of the
+/// configured keys. The proof has to be made by the owner of the tribute against `l2_root`,
+/// the trusted L2 merkle root of the worldwide day.
+pub fn verify_offer(
+    api: &dyn Api,
+    zk_config: &ZkConfig,
+    l2_root: &Base58Binary,
+    zk_proof: &ZkProof,
+    payload: &TributeInputPayload,
+) -> Result<(), ContractError> {
+    if zk_proof.public_data.public_key != payload.owner {
+        return Err(ContractError::ProofOwnerMismatch {});
+    }
+    if zk_proof.public_data.merkle_root != *l2_root {
+        return Err(ContractError::UnknownMerkleRoot {});
+    }
+    if !zk_config
+        .verification_keys
+        .contains(&zk_proof.verification_key)
+    {
+        return Err(ContractError::UnknownVerificationKey {});
+    }
+    let vk = VerificationKey::from_bytes(zk_proof.verification_key.as_slice())?;
+    let proof = Proof::from_bytes(zk_proof.proof.as_slice())?;
+    let inputs = public_inputs(zk_proof, payload);
+
+    if verify(
+        api,
+        &vk,
+        zk_proof.verification_key.as_slice(),
+        &proof,
+        &inputs,
+    )? {
+        Ok(())
+    } else {
+        Err(ContractError::InvalidZkProof {})
+    }
+}
+
+/// PlonK verifier of the paper by Gabizon, Williamson and Ciobotaru, with public inputs
+/// on the first rows. The final pairing check is done by the host.
+pub fn verify(
+    api: &dyn Api,
+    vk: &VerificationKey,
+    vk_bytes: &[u8],
+    proof: &Proof,
+    public_inputs: &[Fr],
+) -> Result<bool, ContractError> {
+    if public_inputs.len() as u64 != vk.num_public_inputs {
+        return Ok(false);
+    }
+    let n = vk.n;
+    let omega = vk.omega()?;
+
+    let mut transcript = Transcript::new(vk_bytes, public_inputs);
+    transcript.append(&proof.a);
+    transcript.append(&proof.b);
+    transcript.append(&proof.c);
+    let beta = transcript.challenge(b"beta");
+    let gamma = transcript.challenge(b"gamma");
+    transcript.append(&proof.z);
+    let alpha = transcript.challenge(b"alpha");
+    transcript.append(&proof.t_lo);
+    transcript.append(&proof.t_mid);
+    transcript.append(&proof.t_hi);
+    let zeta = transcript.challenge(b"zeta");
+    for eval in [
+        &proof.a_eval,
+        &proof.b_eval,
+        &proof.c_eval,
+        &proof.s_sigma1_eval,
+        &proof.s_sigma2_eval,
+        &proof.z_omega_eval,
+    ] {
+        transcript.append(eval);
+    }
+    let v = transcript.challenge(b"v");
+    transcript.append(&proof.w_zeta);
+    transcript.append(&proof.w_zeta_omega);
+    let u = transcript.challenge(b"u");
+
+    // vanishing polynomial and Lagrange basis at zeta
+    let zeta_n = zeta.pow([n]);
+    let zh_zeta = zeta_n - Fr::one();
+    let n_inv = Fr::from(n)
+        .inverse()
+        .ok_or(ContractError::InvalidVerificationKey {})?;
+    let lagrange = |omega_i: Fr| -> Option<Fr> {
+        (zeta - omega_i)
+            .inverse()
+            .map(|inv| omega_i * zh_zeta * n_inv * inv)
+    };
+    let mut pi_zeta = Fr::zero();
+    let mut omega_i = Fr::one();
+    let mut l0_zeta = Fr::zero();
+    for (i, input) in public_inputs.iter().enumerate() {
+        let Some(l_i) = lagrange(omega_i) else {
+            return Ok(false);
+        };
+        if i == 0 {
+            l0_zeta = l_i;
+        }
+        pi_zeta -= *input * l_i;
+        omega_i *= omega;
+    }
+
+    let (a, b, c) = (proof.a_eval, proof.b_eval, proof.c_eval);
+    let (s1, s2) = (proof.s_sigma1_eval, proof.s_sigma2_eval);
+    let z_omega = proof.z_omega_eval;
+    let alpha_2 = alpha.square();
+    let sigma_part = (a + beta * s1 + gamma) * (b + beta * s2 + gamma);
+
+    let r0 = pi_zeta - l0_zeta * alpha_2 - alpha * sigma_part * (c + gamma) * z_omega;
+
+    let z_scalar = (a + beta * zeta + gamma)
+        * (b + beta * vk.k1 * zeta + gamma)
+        * (c + beta * vk.k2 * zeta + gamma)
+        * alpha
+        + l0_zeta * alpha_2
+        + u;
+    let d = vk.q_m * (a * b) + vk.q_l * a + vk.q_r * b + vk.q_o * c + vk.q_c + proof.z * z_scalar
+        - vk.s_sigma3 * (sigma_part * alpha * beta * z_omega)
+        - (proof.t_lo + proof.t_mid * zeta_n + proof.t_hi * zeta_n.square()) * zh_zeta;
+
+    let v2 = v.square();
+    let v3 = v2 * v;
+    let v4 = v3 * v;
+    let v5 = v4 * v;
+    let f = d + proof.a * v + proof.b * v2 + proof.c * v3 + vk.s_sigma1 * v4 + vk.s_sigma2 * v5;
+    let e =
+        G1Affine::generator() * (-r0 + v * a + v2 * b + v3 * c + v4 * s1 + v5 * s2 + u * z_omega);
+
+    let lhs: G1Projective = proof.w_zeta + proof.w_zeta_omega * u;
+    let rhs: G1Projective = proof.w_zeta * zeta + proof.w_zeta_omega * (u * zeta * omega) + f - e;
+
+    Ok(api.bls12_381_pairing_equality(
+        &to_bytes(&lhs.into_affine()),
+        &to_bytes(&vk.x_2),
+        &to_bytes(&rhs.into_affine()),
+        &to_bytes(&G2Affine::generator()),
+    )?)
+}
+
+fn to_bytes(item: &impl CanonicalSerialize) -> Vec<u8> {
+    let mut bytes = vec![];
+    item.serialize_compressed(&mut bytes)
+        .expect("serialization into a vector never fails");
+    bytes
+}
@@ -1,24 +1,43 @@
 use crate::error::ContractError;
+use crate::merkle::{self, assert_not_sealed};
 use crate::msg::{
     ExecuteMsg, InstantiateMsg, MigrateMsg, MintExtension, TributeCollectionExtension,
+    TributeMintData, TributeRecognition,
 };
-use crate::types::{TributeConfig, TributeData, TributeNft};
+use crate::price::{PriceChecker, SettlementNormalizer};
+use crate::stats::DailyStatsIndex;
+use crate::types::{
+    NormalizedSettlement, TributeCollection, TributeConfig, TributeData, TributeDataV1,
+    TributeIndexes, TributeIndexesV1, TributeNft, TributeState, TributeStatus,
+};
+use crate::vector::VectorChecker;
+use cosmwasm_schema::cw_serde;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{Decimal, DepsMut, Env, Event, MessageInfo, Order, Response, Uint128};
-use cw_storage_plus::Index;
-use outbe_nft::execute::assert_minter;
-use outbe_nft::msg::CollectionInfoMsg;
-use outbe_nft::state::{CollectionInfo, Cw721Config};
+use cosmwasm_std::{
+    to_json_binary, Api, Decimal, Deps, DepsMut, Env, Event, MessageInfo, Order, Response,
+    StdResult, Storage, Uint128,
+};
+use cw_ownable::OwnershipError;
+use cw_storage_plus::Bound;
+use outbe_nft::collection::Cw721Collection;
+use outbe_nft::error::Cw721ContractError;
+use outbe_nft::execute::{assert_burner, assert_minter, Cw721ExecuteMsg};
+use outbe_nft::migration::{
+    assert_storage_version, ensure_storage_version, initialize_storage_version, migrate_nft_infos,
+    storage_version, MigrationProgress, VersionedExtension,
+};
+use outbe_nft::msg::{CollectionInfoMsg, MintBatchItem};
+use outbe_nft::state::{HistoryContext, TokenAction};
 use outbe_utils::consts::DECIMAL_PLACES;
-use outbe_utils::date::WorldwideDay;
+use outbe_utils::date::{normalize_to_date, subtract_days, WorldwideDay};
 
 const CONTRACT_NAME: &str = "outbe.net:tribute";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
@@ -28,58 +47,103 @@ pub fn instantiate(
     let cfg = TributeConfig {
         native_token: msg.collection_info_extension.native_token.clone(),
         price_oracle: msg.collection_info_extension.price_oracle.clone(),
+        transfer_policy: msg
+            .collection_info_extension
+            .transfer_policy
+            .clone()
+            .unwrap_or_default(),
+        track_history: msg
+            .collection_info_extension
+            .track_history
+            .unwrap_or_default(),
+        render_token_uri: msg
+            .collection_info_extension
+            .render_token_uri
+            .unwrap_or_default(),
+        price_check: msg
+            .collection_info_extension
+            .price_check
+            .clone()
+            .unwrap_or_default(),
+        retention_days: msg.collection_info_extension.retention_days,
+        vector: msg.collection_info_extension.vector.clone(),
+        reference_currency: msg.collection_info_extension.reference_currency,
     };
 
-    let collection_info = CollectionInfo {
-        name: msg.name,
-        symbol: msg.symbol,
-        updated_at: env.block.time,
-    };
-
-    let config = Cw721Config::<TributeData, TributeConfig>::default();
-    config.collection_config.save(deps.storage, &cfg)?;
-    config
-        .collection_info
-        .save(deps.storage, &collection_info)?;
-
-    // ---- set minter and creator ----
-    // use info.sender if None is passed
-    let minter: &str = match msg.minter.as_deref() {
-        Some(minter) => minter,
-        None => info.sender.as_str(),
-    };
-    outbe_nft::execute::initialize_minter(deps.storage, deps.api, Some(minter))?;
-
-    // use info.sender if None is passed
-    let burner: &str = match msg.burner.as_deref() {
-        Some(burner) => burner,
-        None => info.sender.as_str(),
-    };
-    outbe_nft::execute::initialize_burner(deps.storage, deps.api, Some(burner))?;
-
-    // use info.sender if None is passed
-    let creator: &str = match msg.creator.as_deref() {
-        Some(creator) => creator,
-        None => info.sender.as_str(),
-    };
-    outbe_nft::execute::initialize_creator(deps.storage, deps.api, Some(creator))?;
+    let roles = TributeCollection::instantiate_collection(deps.branch(), &env, &info, &msg, &cfg)?;
+    initialize_storage_version::<TributeData>(deps.storage)?;
+    daily_stats().initialize(deps.storage)?;
 
     Ok(Response::default()
         .add_attribute("action", "tribute::instantiate")
         .add_event(
             Event::new("tribute::instantiate")
-                .add_attribute("minter", minter)
-                .add_attribute("creator", creator),
+                .add_attribute("minter", roles.minter)
+                .add_attribute("creator", roles.creator)
+                .add_attribute("burner", roles.burner),
         ))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
-    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    cw2::ensure_from_older_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     match msg {
         MigrateMsg::Migrate {} => Ok(Response::new()),
+        MigrateMsg::MigrateTokens { limit } => {
+            let limit = limit.unwrap_or(DEFAULT_MIGRATE_TOKENS_LIMIT).max(1) as usize;
+            let progress = migrate_tokens(deps.storage, limit)?;
+            Ok(Response::new()
+                .set_data(to_json_binary(&progress)?)
+                .add_attribute("action", "tribute::migrate_tokens")
+                .add_attribute("storage_version", progress.version.to_string())
+                .add_attribute("migrated", progress.migrated.to_string())
+                .add_attribute("finished", progress.finished.to_string()))
+        }
+        MigrateMsg::RebuildStats { limit } => {
+            // stats are built from tributes of the current layout
+            assert_storage_version::<TributeData>(deps.storage)?;
+            let limit = limit.unwrap_or(DEFAULT_MIGRATE_TOKENS_LIMIT).max(1) as usize;
+            let progress =
+                daily_stats().rebuild::<TributeData, TributeIndexes>(deps.storage, limit)?;
+            Ok(Response::new()
+                .set_data(to_json_binary(&progress)?)
+                .add_attribute("action", "tribute::rebuild_stats")
+                .add_attribute("rebuilt", progress.rebuilt.to_string())
+                .add_attribute("finished", progress.finished.to_string()))
+        }
+    }
+}
+
+fn daily_stats() -> DailyStatsIndex {
+    TributeCollection::config()
+        .nft_info
+        .idx
+        .extension
+        .daily_stats
+}
+
+/// Default number of tributes handled by a single `MigrateTokens` or `RebuildStats` call
+const DEFAULT_MIGRATE_TOKENS_LIMIT: u32 = 500;
+
+/// Brings stored tributes to the layout of `TributeData`.
+/// A layout change keeps the previous type as `TributeDataV<n>` and adds a
+/// `migrate_nft_infos::<TributeDataV<n>, TributeIndexesV<n>, TributeData, TributeIndexes>` step
+/// in front of the final check.
+fn migrate_tokens(
+    storage: &mut dyn Storage,
+    limit: usize,
+) -> Result<MigrationProgress, ContractError> {
+    if storage_version(storage)? == TributeDataV1::VERSION {
+        // v2: tributes got a state, all of them are still offered
+        return Ok(migrate_nft_infos::<
+            TributeDataV1,
+            TributeIndexesV1,
+            TributeData,
+            TributeIndexes,
+        >(storage, limit)?);
     }
+    Ok(ensure_storage_version::<TributeData>(storage)?)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -89,6 +153,12 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    // tributes are only read and written once `MigrateTokens` and `RebuildStats` have finished
+    assert_storage_version::<TributeData>(deps.storage)?;
+    if daily_stats().is_rebuilding(deps.storage) {
+        return Err(ContractError::StatsRebuildPending {});
+    }
+
     match msg {
         ExecuteMsg::Mint {
             token_id,
@@ -96,47 +166,165 @@ pub fn execute(
             token_uri,
             extension,
         } => execute_mint(deps, &env, &info, token_id, owner, token_uri, *extension),
-        ExecuteMsg::Burn { token_id } => execute_burn(deps, &env, &info, token_id),
+        ExecuteMsg::MintBatch { tokens } => execute_mint_batch(deps, &env, &info, tokens),
         #[cfg(feature = "demo")]
         ExecuteMsg::BurnAll { batch_size } => execute_burn_all(deps, &env, &info, batch_size),
-        ExecuteMsg::BurnForDay { date } => execute_burn_for_day(deps, &env, &info, date),
-
-        ExecuteMsg::UpdateMinterOwnership(action) => Ok(
-            outbe_nft::execute::update_minter_ownership(deps, &env, &info, action)?,
+        ExecuteMsg::BurnForDay { date, limit } => {
+            execute_burn_for_day(deps, &env, &info, date, limit)
+        }
+        ExecuteMsg::SelectVector {
+            token_id,
+            vector_id,
+        } => execute_select_vector(deps, &env, &info, token_id, vector_id),
+        ExecuteMsg::Recognize { tributes } => execute_recognize(deps, &env, &info, tributes),
+        ExecuteMsg::SealDay { date } => execute_seal_day(deps, &env, &info, date),
+        ExecuteMsg::ConcludeDay { date, limit } => {
+            execute_conclude_day(deps, &env, &info, date, limit)
+        }
+        ExecuteMsg::ArchiveDay { date, limit } => {
+            execute_archive_day(deps, &env, &info, date, limit)
+        }
+        ExecuteMsg::Prune { limit } => execute_prune(deps, &env, &info, limit),
+        ExecuteMsg::UpdateCollectionInfo { collection_info } => {
+            update_collection_info(deps, collection_info)
+        }
+        ExecuteMsg::UpdateMinterOwnership(action) => execute_cw721(
+            deps,
+            &env,
+            &info,
+            Cw721ExecuteMsg::UpdateMinterOwnership(action),
         ),
-        ExecuteMsg::UpdateCreatorOwnership(action) => Ok(
-            outbe_nft::execute::update_creator_ownership(deps, &env, &info, action)?,
+        ExecuteMsg::UpdateCreatorOwnership(action) => execute_cw721(
+            deps,
+            &env,
+            &info,
+            Cw721ExecuteMsg::UpdateCreatorOwnership(action),
         ),
-        ExecuteMsg::UpdateBurnerOwnership(action) => Ok(
-            outbe_nft::execute::update_burner_ownership(deps, &env, &info, action)?,
+        ExecuteMsg::UpdateBurnerOwnership(action) => execute_cw721(
+            deps,
+            &env,
+            &info,
+            Cw721ExecuteMsg::UpdateBurnerOwnership(action),
         ),
-        ExecuteMsg::UpdateCollectionInfo { collection_info } => {
-            update_collection_info(deps, collection_info)
+        ExecuteMsg::Burn { token_id } => {
+            assert_concluded(deps.storage, std::slice::from_ref(&token_id))?;
+            execute_cw721(deps, &env, &info, Cw721ExecuteMsg::Burn { token_id })
+        }
+        ExecuteMsg::BurnBatch { token_ids } => {
+            assert_concluded(deps.storage, &token_ids)?;
+            execute_cw721(deps, &env, &info, Cw721ExecuteMsg::BurnBatch { token_ids })
+        }
+        ExecuteMsg::TransferNft {
+            recipient,
+            token_id,
+        } => execute_cw721(
+            deps,
+            &env,
+            &info,
+            Cw721ExecuteMsg::TransferNft {
+                recipient,
+                token_id,
+            },
+        ),
+        ExecuteMsg::SendNft {
+            contract,
+            token_id,
+            msg,
+        } => execute_cw721(
+            deps,
+            &env,
+            &info,
+            Cw721ExecuteMsg::SendNft {
+                contract,
+                token_id,
+                msg,
+            },
+        ),
+        ExecuteMsg::Approve {
+            spender,
+            token_id,
+            expires,
+        } => execute_cw721(
+            deps,
+            &env,
+            &info,
+            Cw721ExecuteMsg::Approve {
+                spender,
+                token_id,
+                expires,
+            },
+        ),
+        ExecuteMsg::Revoke { spender, token_id } => execute_cw721(
+            deps,
+            &env,
+            &info,
+            Cw721ExecuteMsg::Revoke { spender, token_id },
+        ),
+        ExecuteMsg::ApproveAll { operator, expires } => execute_cw721(
+            deps,
+            &env,
+            &info,
+            Cw721ExecuteMsg::ApproveAll { operator, expires },
+        ),
+        ExecuteMsg::RevokeAll { operator } => {
+            execute_cw721(deps, &env, &info, Cw721ExecuteMsg::RevokeAll { operator })
+        }
+    }
+}
+
+/// Offered tributes take part in the run of their day and in the nominal totals of the tribute
+/// factory, so they are only burned once their day is concluded
+fn assert_concluded(storage: &dyn Storage, token_ids: &[String]) -> Result<(), ContractError> {
+    let config = TributeCollection::config();
+    for token_id in token_ids {
+        if let Some(token) = config.nft_info.may_load(storage, token_id)? {
+            let date = token.extension.worldwide_day;
+            if token.extension.state.status() == TributeStatus::Offered {
+                return Err(ContractError::NotConcluded { date });
+            }
+            merkle::assert_not_running(storage, date)?;
         }
     }
+    Ok(())
+}
+
+fn execute_cw721(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    msg: Cw721ExecuteMsg,
+) -> Result<Response, ContractError> {
+    Ok(TributeCollection::execute_cw721(deps, env, info, msg)?)
 }
 
 pub fn update_collection_info(
     deps: DepsMut,
     msg: CollectionInfoMsg<Option<TributeCollectionExtension>>,
 ) -> Result<Response, ContractError> {
-    let config = Cw721Config::<TributeData, TributeConfig>::default();
+    let config = TributeCollection::config();
 
     let mut collection_info = config.collection_info.load(deps.storage)?;
-    if msg.name.is_some() {
-        collection_info.name = msg.name.unwrap();
+    if let Some(name) = msg.name {
+        collection_info.name = name;
     }
-    if msg.symbol.is_some() {
-        collection_info.symbol = msg.symbol.unwrap();
+    if let Some(symbol) = msg.symbol {
+        collection_info.symbol = symbol;
     }
 
-    if msg.extension.is_some() {
-        let data = msg.extension.unwrap();
+    if let Some(data) = msg.extension {
+        let current = config.collection_config.load(deps.storage)?;
         config.collection_config.save(
             deps.storage,
             &TributeConfig {
                 native_token: data.native_token,
                 price_oracle: data.price_oracle,
+                transfer_policy: data.transfer_policy.unwrap_or(current.transfer_policy),
+                track_history: data.track_history.unwrap_or(current.track_history),
+                render_token_uri: data.render_token_uri.unwrap_or(current.render_token_uri),
+                price_check: data.price_check.unwrap_or(current.price_check),
+                retention_days: data.retention_days.or(current.retention_days),
+                vector: data.vector.or(current.vector),
+                reference_currency: data.reference_currency.or(current.reference_currency),
             },
         )?;
     }
@@ -156,56 +344,34 @@ fn execute_mint(
     extension: MintExtension,
 ) -> Result<Response, ContractError> {
     assert_minter(deps.storage, &info.sender)?;
-    // validate owner
-    let owner_addr = deps.api.addr_validate(&owner)?;
-
-    let entity = extension.data;
-    if entity.tribute_id != token_id || entity.owner != owner {
-        return Err(ContractError::WrongInput {});
-    }
-
-    if entity.settlement_amount_minor == Uint128::zero()
-        || entity.nominal_amount_minor == Uint128::zero()
-        || entity.nominal_price == Decimal::zero()
-    {
-        return Err(ContractError::WrongInput {});
-    }
-
-    let config = Cw721Config::<TributeData, TributeConfig>::default();
-
-    // TODO here we need to check that given exchange_rate is in daily bounds
-    // let exchange_rate: price_oracle::types::TokenPairPrice = deps.querier.query_wasm_smart(
-    //     &col_config.price_oracle,
-    //     &price_oracle::query::QueryMsg::GetPrice {},
-    // )?;
-    //
 
-    let nominal_amount = calc_nominal_amount(entity.settlement_amount_minor, entity.nominal_price);
-
-    // create the token
-    let data = TributeData {
-        settlement_amount_minor: entity.settlement_amount_minor,
-        settlement_currency: entity.settlement_currency,
-        nominal_price: entity.nominal_price,
-        nominal_amount_minor: nominal_amount,
-        worldwide_day: entity.worldwide_day,
-        created_at: env.block.time,
-    };
+    let config = TributeCollection::config()
+        .collection_config
+        .load(deps.storage)?;
+    let normalized_settlement =
+        MintChecker::new(deps.as_ref(), &config).check(deps.storage, &extension.data)?;
 
-    let token = TributeNft {
-        owner: owner_addr,
+    let token = new_tribute(
+        deps.api,
+        env,
+        &token_id,
+        &owner,
         token_uri,
-        extension: data.clone(),
-    };
-
-    config
-        .nft_info
-        .update(deps.storage, &token_id, |old| match old {
-            Some(_) => Err(ContractError::AlreadyExists {}),
-            None => Ok(token),
-        })?;
+        extension,
+        normalized_settlement,
+    )?;
+    let data = token.extension.clone();
 
-    config.increment_tokens(deps.storage)?;
+    TributeCollection::mint(
+        deps.storage,
+        &HistoryContext::new(env, info),
+        &token_id,
+        token,
+    )
+    .map_err(|err| match err {
+        Cw721ContractError::Claimed {} => ContractError::AlreadyExists {},
+        err => err.into(),
+    })?;
 
     Ok(Response::new()
         .add_attribute("action", "tribute::mint")
@@ -227,50 +393,150 @@ fn execute_mint(
         ))
 }
 
-fn calc_nominal_amount(settlement_amount: Uint128, exchange_rate: Decimal) -> Uint128 {
-    let settlement_value_dec = Decimal::from_atomics(settlement_amount, DECIMAL_PLACES).unwrap();
-    let nominal_amount = settlement_value_dec / exchange_rate;
-
-    println!("settlement_value: {}", settlement_amount);
-    println!("exchange_rate: {}", exchange_rate);
-    println!("nominal_amount: {}", nominal_amount);
-
-    nominal_amount.atomics()
-}
-
-fn execute_burn(
+fn execute_mint_batch(
     deps: DepsMut,
-    _env: &Env,
+    env: &Env,
     info: &MessageInfo,
-    token_id: String,
+    tokens: Vec<MintBatchItem<MintExtension>>,
 ) -> Result<Response, ContractError> {
-    let config = Cw721Config::<TributeData, TributeConfig>::default();
-    // TODO verify ownership
-    // let token = config.nft_info.load(deps.storage, &token_id)?;
-    // check_can_send(deps.as_ref(), env, info.sender.as_str(), &token)?;
+    assert_minter(deps.storage, &info.sender)?;
+
+    let mut token_ids: Vec<String> = Vec::with_capacity(tokens.len());
+    let mut nominal_amount_total = Uint128::zero();
+    let mut batch: Vec<(String, TributeNft, Option<String>)> = Vec::with_capacity(tokens.len());
+    let config = TributeCollection::config()
+        .collection_config
+        .load(deps.storage)?;
+    let mut checker = MintChecker::new(deps.as_ref(), &config);
+    for item in tokens {
+        let normalized_settlement = checker.check(deps.storage, &item.extension.data)?;
+        let token = new_tribute(
+            deps.api,
+            env,
+            &item.token_id,
+            &item.owner,
+            item.token_uri,
+            item.extension,
+            normalized_settlement,
+        )?;
+        nominal_amount_total += token.extension.nominal_amount_minor;
+        token_ids.push(item.token_id.clone());
+        batch.push((item.token_id, token, item.reason));
+    }
 
-    config.nft_info.remove(deps.storage, &token_id)?;
-    config.decrement_tokens(deps.storage)?;
+    TributeCollection::mint_batch(deps.storage, &HistoryContext::new(env, info), batch)?;
 
     Ok(Response::new()
-        .add_attribute("action", "tribute::burn")
+        .add_attribute("action", "tribute::mint_batch")
         .add_event(
-            Event::new("tribute::burn")
-                .add_attribute("sender", info.sender.to_string())
-                .add_attribute("token_id", token_id),
+            Event::new("tribute::mint_batch")
+                .add_attribute("count", token_ids.len().to_string())
+                .add_attribute("token_ids", token_ids.join(","))
+                .add_attribute("nominal_amount_minor", nominal_amount_total.to_string()),
         ))
 }
 
+/// Checks of mint data against the price oracle, the vectors and the sealed days, shared by
+/// `Mint`, `MintBatch` and the `CheckMint` query
+pub(crate) struct MintChecker<'a> {
+    prices: PriceChecker<'a>,
+    vectors: VectorChecker<'a>,
+    normalizer: SettlementNormalizer<'a>,
+}
+
+impl<'a> MintChecker<'a> {
+    pub fn new(deps: Deps<'a>, config: &'a TributeConfig) -> Self {
+        Self {
+            prices: PriceChecker::new(deps, config),
+            vectors: VectorChecker::new(deps, config),
+            normalizer: SettlementNormalizer::new(deps, config),
+        }
+    }
+
+    /// Returns the normalized settlement of the Tribute to mint
+    pub fn check(
+        &mut self,
+        storage: &dyn Storage,
+        data: &TributeMintData,
+    ) -> Result<Option<NormalizedSettlement>, ContractError> {
+        self.prices
+            .check(&data.settlement_currency, data.nominal_price)?;
+        self.vectors.check(data.vector_id)?;
+        assert_not_sealed(storage, data.worldwide_day)?;
+        self.normalizer
+            .normalize(&data.settlement_currency, data.settlement_amount_minor)
+    }
+}
+
+/// Validates mint input and builds a new Tribute token out of it
+fn new_tribute(
+    api: &dyn Api,
+    env: &Env,
+    token_id: &str,
+    owner: &str,
+    token_uri: Option<String>,
+    extension: MintExtension,
+    normalized_settlement: Option<NormalizedSettlement>,
+) -> Result<TributeNft, ContractError> {
+    // validate owner
+    let owner_addr = api.addr_validate(owner)?;
+
+    let entity = extension.data;
+    if entity.tribute_id != token_id || entity.owner != owner {
+        return Err(ContractError::WrongInput {});
+    }
+
+    if entity.settlement_amount_minor == Uint128::zero()
+        || entity.nominal_amount_minor == Uint128::zero()
+        || entity.nominal_price == Decimal::zero()
+    {
+        return Err(ContractError::WrongInput {});
+    }
+
+    let nominal_amount = calc_nominal_amount(entity.settlement_amount_minor, entity.nominal_price);
+
+    // create the token
+    Ok(TributeNft {
+        owner: owner_addr,
+        token_uri,
+        approvals: vec![],
+        extension: TributeData {
+            settlement_amount_minor: entity.settlement_amount_minor,
+            settlement_currency: entity.settlement_currency,
+            normalized_settlement,
+            nominal_price: entity.nominal_price,
+            nominal_amount_minor: nominal_amount,
+            worldwide_day: entity.worldwide_day,
+            created_at: env.block.time,
+            vector_id: entity.vector_id,
+            amounts_commitment: entity.amounts_commitment,
+            state: TributeState::Offered,
+        },
+    })
+}
+
+/// Nominal amount recorded on a minted Tribute, the settlement amount at the nominal price
+pub fn calc_nominal_amount(settlement_amount: Uint128, exchange_rate: Decimal) -> Uint128 {
+    let settlement_value_dec = Decimal::from_atomics(settlement_amount, DECIMAL_PLACES).unwrap();
+    let nominal_amount = settlement_value_dec / exchange_rate;
+    nominal_amount.atomics()
+}
+
 #[cfg(feature = "demo")]
 fn execute_burn_all(
     deps: DepsMut,
-    _env: &Env,
+    env: &Env,
     info: &MessageInfo,
     batch_size: Option<usize>,
 ) -> Result<Response, ContractError> {
-    let config = Cw721Config::<TributeData, TributeConfig>::default();
+    assert_burner(deps.storage, &info.sender)?;
+    let config = TributeCollection::config();
 
-    config.clean_tokens(deps.storage, batch_size)?;
+    config.clean_tokens(
+        deps.storage,
+        &HistoryContext::new(env, info).with_reason("burn_all"),
+        batch_size,
+    )?;
 
     Ok(Response::new()
         .add_attribute("action", "tribute::burn_all")
@@ -279,71 +545,395 @@ fn execute_burn_all(
         ))
 }
 
+/// Default number of tributes removed by a single `BurnForDay` call
+const DEFAULT_BURN_FOR_DAY_LIMIT: u32 = 500;
+
+/// Result of a `BurnForDay` call, set as response data
+#[cw_serde]
+pub struct BurnForDayResult {
+    pub date: WorldwideDay,
+    pub burned: u64,
+    /// False if tributes of the day are left and `BurnForDay` should be called again
+    pub finished: bool,
+}
+
 fn execute_burn_for_day(
     deps: DepsMut,
-    _env: &Env,
+    env: &Env,
     info: &MessageInfo,
     date: WorldwideDay,
+    limit: Option<u32>,
 ) -> Result<Response, ContractError> {
-    // Temporary disable ownership check because it's should be accepted when transferring
-    // outbe_nft::execute::assert_burner(deps.storage, &info.sender)?;
-    let config = Cw721Config::<TributeData, TributeConfig>::default();
-
-    // Collect token IDs that match the specified date
-    let tokens_to_burn: Vec<String> = config
+    assert_burner(deps.storage, &info.sender)?;
+    let config = TributeCollection::config();
+    let offered = config
         .nft_info
-        .range(deps.storage, None, None, Order::Ascending)
-        .filter_map(|item| {
-            if let Ok((token_id, token_info)) = item {
-                if token_info.extension.worldwide_day == date {
-                    Some(token_id)
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        })
-        .collect();
-
-    let burned_count = tokens_to_burn.len();
-
-    // Remove the tokens and update indexes
-    for token_id in tokens_to_burn {
-        if let Ok(token_info) = config.nft_info.load(deps.storage, &token_id) {
-            // Remove from indexes
-            config
-                .nft_info
-                .idx
-                .owner
-                .remove(deps.storage, token_id.as_bytes(), &token_info)?;
-        }
-        // Remove the token
-        config.nft_info.remove(deps.storage, &token_id)?;
-        // Decrement token count
-        config.decrement_tokens(deps.storage)?;
+        .idx
+        .extension
+        .state
+        .prefix((TributeStatus::Offered.to_string(), date))
+        .keys_raw(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+    if offered {
+        return Err(ContractError::NotConcluded { date });
     }
+    merkle::assert_not_running(deps.storage, date)?;
+
+    let limit = limit.unwrap_or(DEFAULT_BURN_FOR_DAY_LIMIT).max(1) as usize;
+    let progress = config.burn_by_index(
+        deps.storage,
+        &HistoryContext::new(env, info).with_reason("burn_for_day"),
+        &config.nft_info.idx.extension.worldwide_day,
+        date,
+        limit,
+    )?;
 
     Ok(Response::new()
+        .set_data(to_json_binary(&BurnForDayResult {
+            date,
+            burned: progress.burned,
+            finished: progress.finished,
+        })?)
         .add_attribute("action", "tribute::burn_for_day")
         .add_event(
             Event::new("tribute::burn_for_day")
                 .add_attribute("sender", info.sender.to_string())
                 .add_attribute("date", date.to_string())
-                .add_attribute("burned_count", burned_count.to_string()),
+                .add_attribute("burned_count", progress.burned.to_string())
+                .add_attribute("finished", progress.finished.to_string()),
+        ))
+}
+
+fn execute_select_vector(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    token_id: String,
+    vector_id: Option<u16>,
+) -> Result<Response, ContractError> {
+    let config = TributeCollection::config();
+    let mut token = config.nft_info.load(deps.storage, &token_id)?;
+    if token.owner != info.sender {
+        return Err(Cw721ContractError::Ownership(OwnershipError::NotOwner).into());
+    }
+    let status = token.extension.state.status();
+    if status != TributeStatus::Offered {
+        return Err(ContractError::NotOffered {
+            token_id,
+            state: status,
+        });
+    }
+
+    let collection_config = config.collection_config.load(deps.storage)?;
+    VectorChecker::new(deps.as_ref(), &collection_config).check(vector_id)?;
+    token.extension.vector_id = vector_id;
+    config.nft_info.save(deps.storage, &token_id, &token)?;
+
+    let vector = vector_id.map(|id| id.to_string()).unwrap_or_default();
+    Ok(Response::new()
+        .add_attribute("action", "tribute::select_vector")
+        .add_event(
+            Event::new("tribute::select_vector")
+                .add_attribute("token_id", token_id)
+                .add_attribute("vector_id", vector),
+        ))
+}
+
+fn execute_recognize(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    tributes: Vec<TributeRecognition>,
+) -> Result<Response, ContractError> {
+    assert_burner(deps.storage, &info.sender)?;
+    let config = TributeCollection::config();
+
+    let ctx = HistoryContext::new(env, info).with_reason("metadosis");
+    let mut token_ids: Vec<String> = Vec::with_capacity(tributes.len());
+    for recognition in tributes {
+        let mut token = config.nft_info.load(deps.storage, &recognition.token_id)?;
+        let status = token.extension.state.status();
+        if status != TributeStatus::Offered {
+            return Err(ContractError::NotOffered {
+                token_id: recognition.token_id,
+                state: status,
+            });
+        }
+        token.extension.state = TributeState::Recognized {
+            nod_id: recognition.nod_id,
+        };
+        change_state(deps.storage, &ctx, &recognition.token_id, &token, status)?;
+        token_ids.push(recognition.token_id);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "tribute::recognize")
+        .add_event(
+            Event::new("tribute::recognize")
+                .add_attribute("count", token_ids.len().to_string())
+                .add_attribute("token_ids", token_ids.join(",")),
+        ))
+}
+
+/// Default number of tributes changed by a single `ConcludeDay` or `ArchiveDay` call
+const DEFAULT_TRANSITION_LIMIT: u32 = 500;
+
+/// Result of a `ConcludeDay` or `ArchiveDay` call, set as response data
+#[cw_serde]
+pub struct TransitionResult {
+    pub date: WorldwideDay,
+    pub updated: u64,
+    /// False if tributes of the day are left and the call should be repeated
+    pub finished: bool,
+}
+
+fn execute_seal_day(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    date: WorldwideDay,
+) -> Result<Response, ContractError> {
+    assert_burner(deps.storage, &info.sender)?;
+    let seal = merkle::seal_day(deps.storage, date, env.block.time)?;
+
+    Ok(Response::new()
+        .set_data(to_json_binary(&seal)?)
+        .add_attribute("action", "tribute::seal_day")
+        .add_event(
+            Event::new("tribute::seal_day")
+                .add_attribute("date", date.to_string())
+                .add_attribute("root", seal.root.to_hex())
+                .add_attribute("leaves_count", seal.leaves_count.to_string()),
+        ))
+}
+
+fn execute_conclude_day(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    date: WorldwideDay,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    assert_burner(deps.storage, &info.sender)?;
+    let ctx = HistoryContext::new(env, info).with_reason("metadosis");
+    let limit = limit.unwrap_or(DEFAULT_TRANSITION_LIMIT).max(1) as usize;
+    let result = transition_day(
+        deps.storage,
+        &ctx,
+        date,
+        &[TributeStatus::Offered],
+        TributeState::Unrecognized,
+        limit,
+    )?;
+    if result.finished {
+        merkle::conclude_day(deps.storage, date)?;
+    }
+    transition_response("tribute::conclude_day", info, result)
+}
+
+fn execute_archive_day(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    date: WorldwideDay,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    assert_burner(deps.storage, &info.sender)?;
+    let ctx = HistoryContext::new(env, info).with_reason("archive");
+    let limit = limit.unwrap_or(DEFAULT_TRANSITION_LIMIT).max(1) as usize;
+    let result = transition_day(
+        deps.storage,
+        &ctx,
+        date,
+        &[TributeStatus::Recognized, TributeStatus::Unrecognized],
+        TributeState::Archived,
+        limit,
+    )?;
+    transition_response("tribute::archive_day", info, result)
+}
+
+/// Moves up to `limit` tributes of the day having one of the `from` statuses to `to`.
+/// Changed tributes leave the `from` prefixes of the state index, so no cursor is needed.
+fn transition_day(
+    storage: &mut dyn Storage,
+    ctx: &HistoryContext,
+    date: WorldwideDay,
+    from: &[TributeStatus],
+    to: TributeState,
+    limit: usize,
+) -> Result<TransitionResult, ContractError> {
+    let config = TributeCollection::config();
+
+    let mut tokens: Vec<(String, TributeNft)> = vec![];
+    for status in from {
+        let left = limit + 1 - tokens.len();
+        let found = config
+            .nft_info
+            .idx
+            .extension
+            .state
+            .prefix((status.to_string(), date))
+            .range(storage, None, None, Order::Ascending)
+            .take(left)
+            .collect::<StdResult<Vec<_>>>()?;
+        tokens.extend(found);
+        if tokens.len() > limit {
+            break;
+        }
+    }
+    let finished = tokens.len() <= limit;
+    tokens.truncate(limit);
+
+    for (token_id, mut token) in tokens.iter().cloned() {
+        let status = token.extension.state.status();
+        token.extension.state = to.clone();
+        change_state(storage, ctx, &token_id, &token, status)?;
+    }
+
+    Ok(TransitionResult {
+        date,
+        updated: tokens.len() as u64,
+        finished,
+    })
+}
+
+/// Saves a tribute with a new state and records the change in its history
+fn change_state(
+    storage: &mut dyn Storage,
+    ctx: &HistoryContext,
+    token_id: &str,
+    token: &TributeNft,
+    previous: TributeStatus,
+) -> StdResult<()> {
+    let config = TributeCollection::config();
+    config.nft_info.save(storage, token_id, token)?;
+    config.record_history(
+        storage,
+        ctx,
+        token_id,
+        TokenAction::StateChange {
+            from: previous.to_string(),
+            to: token.extension.state.status().to_string(),
+        },
+    )
+}
+
+fn transition_response(
+    action: &str,
+    info: &MessageInfo,
+    result: TransitionResult,
+) -> Result<Response, ContractError> {
+    Ok(Response::new()
+        .set_data(to_json_binary(&result)?)
+        .add_attribute("action", action)
+        .add_event(
+            Event::new(action)
+                .add_attribute("sender", info.sender.to_string())
+                .add_attribute("date", result.date.to_string())
+                .add_attribute("updated_count", result.updated.to_string())
+                .add_attribute("finished", result.finished.to_string()),
+        ))
+}
+
+/// Default number of tributes removed by a single `Prune` call
+const DEFAULT_PRUNE_LIMIT: u32 = 500;
+
+/// Result of a `Prune` call, set as response data
+#[cw_serde]
+pub struct PruneResult {
+    /// Archived tributes of days before this one are expired
+    pub expired_before: WorldwideDay,
+    pub pruned: u64,
+    /// False if expired tributes are left and `Prune` should be called again
+    pub finished: bool,
+}
+
+fn execute_prune(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    assert_burner(deps.storage, &info.sender)?;
+    let config = TributeCollection::config();
+    let retention_days = config
+        .collection_config
+        .load(deps.storage)?
+        .retention_days
+        .ok_or(ContractError::RetentionNotConfigured {})?;
+
+    let limit = limit.unwrap_or(DEFAULT_PRUNE_LIMIT).max(1) as usize;
+    let expired_before = subtract_days(normalize_to_date(&env.block.time), retention_days)?;
+    // archived tributes are ordered by day, the expired ones come first
+    let mut tokens = config
+        .nft_info
+        .idx
+        .extension
+        .state
+        .sub_prefix(TributeStatus::Archived.to_string())
+        .range(
+            deps.storage,
+            None,
+            Some(Bound::exclusive((expired_before, String::new()))),
+            Order::Ascending,
+        )
+        .take(limit + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+    let finished = tokens.len() <= limit;
+    tokens.truncate(limit);
+
+    let ctx = HistoryContext::new(env, info).with_reason("retention");
+    for (token_id, token) in tokens.iter() {
+        config
+            .nft_info
+            .replace(deps.storage, token_id, None, Some(token))?;
+        config.record_history(
+            deps.storage,
+            &ctx,
+            token_id,
+            TokenAction::Burn {
+                owner: token.owner.clone(),
+            },
+        )?;
+    }
+    let pruned = tokens.len() as u64;
+    config.decrease_tokens(deps.storage, pruned)?;
+
+    Ok(Response::new()
+        .set_data(to_json_binary(&PruneResult {
+            expired_before,
+            pruned,
+            finished,
+        })?)
+        .add_attribute("action", "tribute::prune")
+        .add_event(
+            Event::new("tribute::prune")
+                .add_attribute("sender", info.sender.to_string())
+                .add_attribute("expired_before", expired_before.to_string())
+                .add_attribute("pruned_count", pruned.to_string())
+                .add_attribute("finished", finished.to_string()),
         ))
 }
 
 #[cfg(test)]
 mod tests {
     use crate::contract::{
-        calc_nominal_amount, execute_burn_all, execute_burn_for_day, instantiate,
+        calc_nominal_amount, execute, execute_burn_all, execute_burn_for_day, instantiate,
+        BurnForDayResult,
     };
-    use crate::msg::{InstantiateMsg, TributeCollectionExtension};
-    use crate::types::{TributeConfig, TributeData, TributeNft};
+    use crate::error::ContractError;
+    use crate::msg::{
+        ExecuteMsg, InstantiateMsg, MintExtension, TributeCollectionExtension, TributeMintData,
+    };
+    use crate::types::{TributeConfig, TributeData, TributeIndexes, TributeNft, TributeState};
     use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env, MockApi};
-    use cosmwasm_std::{Addr, Decimal, Storage, Timestamp, Uint128};
-    use outbe_nft::state::Cw721Config;
+    use cosmwasm_std::{
+        from_json, Addr, Decimal, DepsMut, Env, MessageInfo, Storage, Timestamp, Uint128,
+    };
+    use outbe_nft::error::Cw721ContractError;
+    use outbe_nft::msg::MintBatchItem;
+    use outbe_nft::state::{Cw721Config, NftInfo};
     use outbe_utils::date::WorldwideDay;
     use outbe_utils::denom::{Currency, Denom};
     use std::str::FromStr;
@@ -377,10 +967,10 @@ mod tests {
                 minter: None,
                 creator: None,
                 burner: None,
-                collection_info_extension: TributeCollectionExtension {
-                    native_token: Denom::Native("coen".to_string()),
-                    price_oracle: oracle_addr,
-                },
+                collection_info_extension: TributeCollectionExtension::new(
+                    Denom::Native("coen".to_string()),
+                    oracle_addr,
+                ),
             },
         )
         .unwrap();
@@ -389,7 +979,7 @@ mod tests {
         create_test_token(deps.as_mut().storage, "token1");
         create_test_token(deps.as_mut().storage, "token2");
 
-        let config = Cw721Config::<TributeData, TributeConfig>::default();
+        let config = Cw721Config::<TributeData, TributeConfig, TributeIndexes>::default();
         assert_eq!(config.token_count(&deps.storage).unwrap(), 2);
 
         // Execute burn all
@@ -423,10 +1013,10 @@ mod tests {
                 minter: None,
                 creator: None,
                 burner: None,
-                collection_info_extension: TributeCollectionExtension {
-                    native_token: Denom::Native("coen".to_string()),
-                    price_oracle: oracle_addr,
-                },
+                collection_info_extension: TributeCollectionExtension::new(
+                    Denom::Native("coen".to_string()),
+                    oracle_addr,
+                ),
             },
         )
         .unwrap();
@@ -437,11 +1027,25 @@ mod tests {
         create_test_token_with_day(deps.as_mut().storage, "token3", 20250102);
         create_test_token_with_day(deps.as_mut().storage, "token4", 20250103);
 
-        let config = Cw721Config::<TributeData, TributeConfig>::default();
+        let config = Cw721Config::<TributeData, TributeConfig, TributeIndexes>::default();
         assert_eq!(config.token_count(&deps.storage).unwrap(), 4);
 
+        // Only the burner can burn a whole day
+        let stranger = message_info(&api.addr_make("stranger"), &[]);
+        let err = execute_burn_for_day(deps.as_mut(), &env, &stranger, 20250101, None).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Cw721ContractError(Cw721ContractError::NotBurner {})
+        );
+        assert_eq!(config.token_count(&deps.storage).unwrap(), 4);
+
+        // Offered tributes are burned only once their day is concluded
+        let err = execute_burn_for_day(deps.as_mut(), &env, &info, 20250101, None).unwrap_err();
+        assert_eq!(err, ContractError::NotConcluded { date: 20250101 });
+        conclude_day(deps.as_mut(), &env, &info, 20250101);
+
         // Execute burn for day 1
-        let res = execute_burn_for_day(deps.as_mut(), &env, &info, 20250101).unwrap();
+        let res = execute_burn_for_day(deps.as_mut(), &env, &info, 20250101, None).unwrap();
 
         // Verify only tokens from day 1 were burned (2 tokens)
         assert_eq!(config.token_count(&deps.storage).unwrap(), 2);
@@ -471,11 +1075,1083 @@ mod tests {
         assert!(config.nft_info.load(&deps.storage, "token4").is_ok());
     }
 
+    #[test]
+    fn test_tribute_lifecycle() {
+        use crate::contract::{PruneResult, TransitionResult};
+        use crate::msg::TributeRecognition;
+        use crate::query::{query, FullTributesResponse, QueryMsg};
+        use crate::types::TributeStatus;
+
+        let api = MockApi::default();
+        let owner_addr = api.addr_make("owner");
+        let mut deps = mock_dependencies();
+        let info = message_info(&owner_addr, &[]);
+        let mut env = mock_env();
+
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            InstantiateMsg {
+                name: "Test".to_string(),
+                symbol: "TEST".to_string(),
+                minter: None,
+                creator: None,
+                burner: None,
+                collection_info_extension: TributeCollectionExtension {
+                    retention_days: Some(30),
+                    ..TributeCollectionExtension::new(
+                        Denom::Native("coen".to_string()),
+                        api.addr_make("oracle"),
+                    )
+                },
+            },
+        )
+        .unwrap();
+
+        for token_id in ["token1", "token2", "token3"] {
+            create_test_token_with_day(deps.as_mut().storage, token_id, 20250101);
+        }
+        create_test_token_with_day(deps.as_mut().storage, "token4", 20250102);
+
+        let recognize = ExecuteMsg::Recognize {
+            tributes: vec![TributeRecognition {
+                token_id: "token2".to_string(),
+                nod_id: "nod2".to_string(),
+            }],
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), recognize.clone()).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), recognize).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NotOffered {
+                token_id: "token2".to_string(),
+                state: TributeStatus::Recognized,
+            }
+        );
+
+        let transition = |deps: DepsMut, msg: ExecuteMsg| -> TransitionResult {
+            let response = execute(deps, env.clone(), info.clone(), msg).unwrap();
+            from_json(response.data.unwrap()).unwrap()
+        };
+        let conclude = |limit| ExecuteMsg::ConcludeDay {
+            date: 20250101,
+            limit: Some(limit),
+        };
+        let result = transition(deps.as_mut(), conclude(1));
+        assert_eq!((result.updated, result.finished), (1, false));
+        let result = transition(deps.as_mut(), conclude(1));
+        assert_eq!((result.updated, result.finished), (1, true));
+
+        let by_state = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, state| -> Vec<String> {
+            let response: FullTributesResponse = from_json(
+                query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::TributesByState {
+                        state,
+                        date: None,
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            response.tributes.into_iter().map(|t| t.token_id).collect()
+        };
+        assert_eq!(by_state(&deps, TributeStatus::Recognized), vec!["token2"]);
+        assert_eq!(
+            by_state(&deps, TributeStatus::Unrecognized),
+            vec!["token1", "token3"]
+        );
+        // tributes of other days are not touched
+        assert_eq!(by_state(&deps, TributeStatus::Offered), vec!["token4"]);
+        let config = Cw721Config::<TributeData, TributeConfig, TributeIndexes>::default();
+        let token = config.nft_info.load(&deps.storage, "token2").unwrap();
+        assert_eq!(
+            token.extension.state,
+            TributeState::Recognized {
+                nod_id: "nod2".to_string()
+            }
+        );
+
+        let result = transition(
+            deps.as_mut(),
+            ExecuteMsg::ArchiveDay {
+                date: 20250101,
+                limit: None,
+            },
+        );
+        assert_eq!((result.updated, result.finished), (3, true));
+        assert_eq!(
+            by_state(&deps, TributeStatus::Archived),
+            vec!["token1", "token2", "token3"]
+        );
+
+        // archived tributes are kept for the retention period
+        env.block.time = Timestamp::from_seconds(1736467200); // 2025-01-10
+        let prune = |deps: DepsMut, env: &Env| -> PruneResult {
+            let msg = ExecuteMsg::Prune { limit: Some(2) };
+            let response = execute(deps, env.clone(), info.clone(), msg).unwrap();
+            from_json(response.data.unwrap()).unwrap()
+        };
+        let result = prune(deps.as_mut(), &env);
+        assert_eq!((result.expired_before, result.pruned), (20241211, 0));
+
+        env.block.time = Timestamp::from_seconds(1740787200); // 2025-03-01
+        let result = prune(deps.as_mut(), &env);
+        assert_eq!((result.pruned, result.finished), (2, false));
+        let result = prune(deps.as_mut(), &env);
+        assert_eq!((result.pruned, result.finished), (1, true));
+        assert!(by_state(&deps, TributeStatus::Archived).is_empty());
+        assert_eq!(config.token_count(&deps.storage).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_seal_day() {
+        use crate::merkle::{
+            leaf_hash, merkle_root, verify_proof, DaySeal, InclusionProofResponse,
+            SealedLeafIndexesResponse,
+        };
+        use crate::msg::TributeRecognition;
+        use crate::query::{query, QueryMsg};
+
+        let api = MockApi::default();
+        let owner_addr = api.addr_make("owner");
+        let mut deps = mock_dependencies();
+        let info = message_info(&owner_addr, &[]);
+        let env = mock_env();
+
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            InstantiateMsg {
+                name: "Test".to_string(),
+                symbol: "TEST".to_string(),
+                minter: None,
+                creator: None,
+                burner: None,
+                collection_info_extension: TributeCollectionExtension::new(
+                    Denom::Native("coen".to_string()),
+                    api.addr_make("oracle"),
+                ),
+            },
+        )
+        .unwrap();
+
+        for token_id in ["b", "c", "a"] {
+            create_test_token_with_day(deps.as_mut().storage, token_id, 20250101);
+        }
+        create_test_token_with_day(deps.as_mut().storage, "d", 20250102);
+
+        let seal_day = ExecuteMsg::SealDay { date: 20250101 };
+        let response = execute(deps.as_mut(), env.clone(), info.clone(), seal_day.clone()).unwrap();
+        let seal: DaySeal = from_json(response.data.unwrap()).unwrap();
+        let leaves: Vec<[u8; 32]> = ["a", "b", "c"]
+            .iter()
+            .map(|token_id| leaf_hash(token_id, "owner", Uint128::new(100)))
+            .collect();
+        assert_eq!(seal.leaves_count, 3);
+        assert_eq!(seal.root.as_slice(), merkle_root(&leaves).as_slice());
+
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), seal_day).unwrap_err();
+        assert_eq!(err, ContractError::DaySealed { date: 20250101 });
+        let mint = ExecuteMsg::Mint {
+            token_id: "e".to_string(),
+            owner: owner_addr.to_string(),
+            token_uri: None,
+            extension: Box::new(MintExtension {
+                data: TributeMintData {
+                    tribute_id: "e".to_string(),
+                    worldwide_day: 20250101,
+                    owner: owner_addr.to_string(),
+                    settlement_amount_minor: Uint128::new(100),
+                    settlement_currency: Denom::Fiat(Currency::Usd),
+                    nominal_amount_minor: Uint128::new(100),
+                    nominal_price: Decimal::one(),
+                    vector_id: None,
+                    amounts_commitment: None,
+                },
+            }),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), mint).unwrap_err();
+        assert_eq!(err, ContractError::DaySealed { date: 20250101 });
+
+        // proofs are kept when sealed tributes are removed
+        let config = Cw721Config::<TributeData, TributeConfig, TributeIndexes>::default();
+        config.nft_info.remove(deps.as_mut().storage, "b").unwrap();
+        let proof = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, date, token_id: &str| {
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::InclusionProof {
+                    date,
+                    token_id: token_id.to_string(),
+                },
+            )
+        };
+        let response: InclusionProofResponse =
+            from_json(proof(&deps, 20250101, "b").unwrap()).unwrap();
+        assert_eq!(response.index, 1);
+        assert_eq!(response.root, seal.root);
+        let siblings: Vec<[u8; 32]> = response
+            .proof
+            .iter()
+            .map(|hash| hash.to_array().unwrap())
+            .collect();
+        assert!(verify_proof(
+            &leaves[1],
+            response.index,
+            response.leaves_count,
+            &siblings,
+            &seal.root.to_array().unwrap(),
+        ));
+        assert!(proof(&deps, 20250102, "d").is_err());
+        let indexes: SealedLeafIndexesResponse = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::SealedLeafIndexes {
+                    date: 20250101,
+                    token_ids: vec!["c".to_string(), "d".to_string(), "a".to_string()],
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(indexes.indexes, vec![Some(2), None, Some(0)]);
+
+        let sealed: Option<DaySeal> = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::DaySeal { date: 20250101 },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(sealed, Some(seal));
+
+        // the tributes of a sealed day are kept until the day is concluded
+        let recognize = ExecuteMsg::Recognize {
+            tributes: vec![TributeRecognition {
+                token_id: "a".to_string(),
+                nod_id: "nod".to_string(),
+            }],
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), recognize).unwrap();
+        let burn = ExecuteMsg::Burn {
+            token_id: "a".to_string(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), burn.clone()).unwrap_err();
+        assert_eq!(err, ContractError::DaySealed { date: 20250101 });
+        let conclude = ExecuteMsg::ConcludeDay {
+            date: 20250101,
+            limit: Some(1),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), conclude).unwrap();
+        execute(deps.as_mut(), env.clone(), info.clone(), burn).unwrap();
+    }
+
+    #[test]
+    fn test_burn_for_day_in_steps() {
+        let api = MockApi::default();
+        let owner_addr = api.addr_make("owner");
+
+        let mut deps = mock_dependencies();
+        let info = message_info(&owner_addr, &[]);
+        let env = mock_env();
+
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            InstantiateMsg {
+                name: "Test".to_string(),
+                symbol: "TEST".to_string(),
+                minter: None,
+                creator: None,
+                burner: None,
+                collection_info_extension: TributeCollectionExtension::new(
+                    Denom::Native("coen".to_string()),
+                    api.addr_make("oracle"),
+                ),
+            },
+        )
+        .unwrap();
+
+        for token_id in ["token1", "token2", "token3"] {
+            create_test_token_with_day(deps.as_mut().storage, token_id, 20250101);
+        }
+        create_test_token_with_day(deps.as_mut().storage, "token4", 20250102);
+        conclude_day(deps.as_mut(), &env, &info, 20250101);
+
+        let burn_step = |deps: DepsMut| -> BurnForDayResult {
+            let res = execute_burn_for_day(deps, &env, &info, 20250101, Some(2)).unwrap();
+            from_json(res.data.unwrap()).unwrap()
+        };
+        assert_eq!(
+            burn_step(deps.as_mut()),
+            BurnForDayResult {
+                date: 20250101,
+                burned: 2,
+                finished: false,
+            }
+        );
+        assert_eq!(
+            burn_step(deps.as_mut()),
+            BurnForDayResult {
+                date: 20250101,
+                burned: 1,
+                finished: true,
+            }
+        );
+
+        let config = Cw721Config::<TributeData, TributeConfig, TributeIndexes>::default();
+        assert_eq!(config.token_count(&deps.storage).unwrap(), 1);
+        assert!(config.nft_info.has(&deps.storage, "token4"));
+    }
+
+    #[test]
+    fn test_mint_and_burn_batch() {
+        let api = MockApi::default();
+        let owner_addr = api.addr_make("owner");
+        let user_addr = api.addr_make("user");
+
+        let mut deps = mock_dependencies();
+        let info = message_info(&owner_addr, &[]);
+        let env = mock_env();
+
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            InstantiateMsg {
+                name: "Test".to_string(),
+                symbol: "TEST".to_string(),
+                minter: None,
+                creator: None,
+                burner: None,
+                collection_info_extension: TributeCollectionExtension::new(
+                    Denom::Native("coen".to_string()),
+                    api.addr_make("oracle"),
+                ),
+            },
+        )
+        .unwrap();
+
+        let item = |token_id: &str| MintBatchItem {
+            token_id: token_id.to_string(),
+            owner: user_addr.to_string(),
+            token_uri: None,
+            extension: MintExtension {
+                data: TributeMintData {
+                    tribute_id: token_id.to_string(),
+                    worldwide_day: 20250101,
+                    owner: user_addr.to_string(),
+                    settlement_amount_minor: Uint128::new(100),
+                    settlement_currency: Denom::Fiat(Currency::Usd),
+                    nominal_amount_minor: Uint128::new(100),
+                    nominal_price: Decimal::one(),
+                    vector_id: None,
+                    amounts_commitment: None,
+                },
+            },
+            reason: None,
+        };
+
+        // one invalid token rejects the whole batch
+        let mut invalid = item("3");
+        invalid.extension.data.tribute_id = "4".to_string();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::MintBatch {
+                tokens: vec![item("1"), item("2"), invalid],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::WrongInput {});
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::MintBatch {
+                tokens: vec![item("1"), item("2"), item("3")],
+            },
+        )
+        .unwrap();
+        let config = Cw721Config::<TributeData, TributeConfig, TributeIndexes>::default();
+        assert_eq!(config.token_count(&deps.storage).unwrap(), 3);
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "tribute::mint_batch");
+        assert!(res.events[0]
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "token_ids" && attr.value == "1,2,3"));
+
+        let burn_batch = ExecuteMsg::BurnBatch {
+            token_ids: vec!["1".to_string(), "3".to_string()],
+        };
+        let err =
+            execute(deps.as_mut(), env.clone(), info.clone(), burn_batch.clone()).unwrap_err();
+        assert_eq!(err, ContractError::NotConcluded { date: 20250101 });
+        conclude_day(deps.as_mut(), &env, &info, 20250101);
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), burn_batch).unwrap();
+        assert_eq!(config.token_count(&deps.storage).unwrap(), 1);
+        assert!(config.nft_info.has(&deps.storage, "2"));
+        assert!(res.events[0]
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "token_ids" && attr.value == "1,3"));
+    }
+
+    #[test]
+    fn test_mint_checks_oracle_price() {
+        use crate::types::PriceCheck;
+        use cosmwasm_std::{to_json_binary, ContractResult, SystemResult};
+        use price_oracle::types::PriceData;
+
+        let api = MockApi::default();
+        let owner_addr = api.addr_make("owner");
+        let mut deps = mock_dependencies();
+        let info = message_info(&owner_addr, &[]);
+        let env = mock_env();
+
+        deps.querier.update_wasm(|_| {
+            let latest = PriceData {
+                price: Decimal::from_str("2").unwrap(),
+                timestamp: Timestamp::from_seconds(0),
+                open: None,
+                high: None,
+                low: None,
+                close: None,
+                volume: None,
+            };
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&latest).unwrap()))
+        });
+
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            InstantiateMsg {
+                name: "Test".to_string(),
+                symbol: "TEST".to_string(),
+                minter: None,
+                creator: None,
+                burner: None,
+                collection_info_extension: TributeCollectionExtension {
+                    price_check: Some(PriceCheck::Latest {
+                        tolerance: Decimal::percent(10),
+                    }),
+                    ..TributeCollectionExtension::new(
+                        Denom::Native("coen".to_string()),
+                        api.addr_make("oracle"),
+                    )
+                },
+            },
+        )
+        .unwrap();
+
+        let mint = |token_id: &str, nominal_price: &str| ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner_addr.to_string(),
+            token_uri: None,
+            extension: Box::new(MintExtension {
+                data: TributeMintData {
+                    tribute_id: token_id.to_string(),
+                    worldwide_day: 20250101,
+                    owner: owner_addr.to_string(),
+                    settlement_amount_minor: Uint128::new(100),
+                    settlement_currency: Denom::Fiat(Currency::Usd),
+                    nominal_amount_minor: Uint128::new(100),
+                    nominal_price: Decimal::from_str(nominal_price).unwrap(),
+                    vector_id: None,
+                    amounts_commitment: None,
+                },
+            }),
+        };
+
+        execute(deps.as_mut(), env.clone(), info.clone(), mint("1", "2.1")).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), mint("2", "3")).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::PriceOutOfBounds {
+                nominal_price: Decimal::from_str("3").unwrap(),
+                min: Decimal::from_str("1.8").unwrap(),
+                max: Decimal::from_str("2.2").unwrap(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_tribute_vectors() {
+        use crate::query::{query, DailyVectorTotalsResponse, FullTributesResponse, QueryMsg};
+        use crate::stats::VectorTotal;
+        use cosmwasm_std::{to_json_binary, ContractResult, SystemResult};
+        use vector::query::AllVectorsResponse;
+        use vector::types::Vector;
+
+        let api = MockApi::default();
+        let owner_addr = api.addr_make("owner");
+        let stranger_addr = api.addr_make("stranger");
+        let mut deps = mock_dependencies();
+        let info = message_info(&owner_addr, &[]);
+        let env = mock_env();
+
+        deps.querier.update_wasm(|_| {
+            let vectors = AllVectorsResponse {
+                vectors: [1u16, 2]
+                    .into_iter()
+                    .map(|vector_id| Vector {
+                        vector_id,
+                        name: format!("Vector {}", vector_id),
+                        vector_rate: Uint128::new(vector_id as u128),
+                    })
+                    .collect(),
+            };
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&vectors).unwrap()))
+        });
+
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            InstantiateMsg {
+                name: "Test".to_string(),
+                symbol: "TEST".to_string(),
+                minter: None,
+                creator: None,
+                burner: None,
+                collection_info_extension: TributeCollectionExtension {
+                    vector: Some(api.addr_make("vector")),
+                    ..TributeCollectionExtension::new(
+                        Denom::Native("coen".to_string()),
+                        api.addr_make("oracle"),
+                    )
+                },
+            },
+        )
+        .unwrap();
+
+        let mint = |token_id: &str, vector_id: Option<u16>| ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner_addr.to_string(),
+            token_uri: None,
+            extension: Box::new(MintExtension {
+                data: TributeMintData {
+                    tribute_id: token_id.to_string(),
+                    worldwide_day: 20250101,
+                    owner: owner_addr.to_string(),
+                    settlement_amount_minor: Uint128::new(100),
+                    settlement_currency: Denom::Fiat(Currency::Usd),
+                    nominal_amount_minor: Uint128::new(100),
+                    nominal_price: Decimal::one(),
+                    vector_id,
+                    amounts_commitment: None,
+                },
+            }),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), mint("1", Some(1))).unwrap();
+        execute(deps.as_mut(), env.clone(), info.clone(), mint("2", None)).unwrap();
+        execute(deps.as_mut(), env.clone(), info.clone(), mint("3", Some(2))).unwrap();
+        let err =
+            execute(deps.as_mut(), env.clone(), info.clone(), mint("4", Some(7))).unwrap_err();
+        assert_eq!(err, ContractError::UnknownVector { vector_id: 7 });
+
+        let select = |token_id: &str, vector_id: Option<u16>| ExecuteMsg::SelectVector {
+            token_id: token_id.to_string(),
+            vector_id,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&stranger_addr, &[]),
+            select("2", Some(1)),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Cw721ContractError(_)));
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            select("2", Some(9)),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::UnknownVector { vector_id: 9 });
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            select("2", Some(1)),
+        )
+        .unwrap();
+
+        let by_vector = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, vector_id| -> Vec<String> {
+            let response: FullTributesResponse = from_json(
+                query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::TributesByVector {
+                        date: 20250101,
+                        vector_id,
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            response.tributes.into_iter().map(|t| t.token_id).collect()
+        };
+        assert_eq!(by_vector(&deps, Some(1)), vec!["1", "2"]);
+        assert_eq!(by_vector(&deps, Some(2)), vec!["3"]);
+        assert!(by_vector(&deps, None).is_empty());
+
+        let totals: DailyVectorTotalsResponse = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::DailyVectorTotals { date: 20250101 },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            totals.vectors,
+            vec![
+                VectorTotal {
+                    vector_id: Some(1),
+                    tributes_count: 2,
+                    nominal_amount_minor: Uint128::new(200),
+                },
+                VectorTotal {
+                    vector_id: Some(2),
+                    tributes_count: 1,
+                    nominal_amount_minor: Uint128::new(100),
+                },
+            ]
+        );
+
+        // the vector is fixed once the day is concluded
+        let conclude = ExecuteMsg::ConcludeDay {
+            date: 20250101,
+            limit: None,
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), conclude).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), select("3", None)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NotOffered {
+                token_id: "3".to_string(),
+                state: crate::types::TributeStatus::Unrecognized,
+            }
+        );
+    }
+
+    #[test]
+    fn test_migrate_tokens_to_v2() {
+        use crate::contract::migrate;
+        use crate::msg::MigrateMsg;
+        use crate::query::{query, QueryMsg};
+        use crate::stats::DailyStats;
+        use crate::types::{TributeDataV1, TributeIndexesV1, TributeState};
+        use cosmwasm_std::Order;
+        use outbe_nft::migration::{storage_version, MigrationProgress, STORAGE_VERSION};
+
+        let api = MockApi::default();
+        let owner_addr = api.addr_make("owner");
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner_addr, &[]),
+            InstantiateMsg {
+                name: "Test".to_string(),
+                symbol: "TEST".to_string(),
+                minter: None,
+                creator: None,
+                burner: None,
+                collection_info_extension: TributeCollectionExtension::new(
+                    Denom::Native("coen".to_string()),
+                    api.addr_make("oracle"),
+                ),
+            },
+        )
+        .unwrap();
+        assert_eq!(storage_version(&deps.storage).unwrap(), 2);
+
+        // a store written before tributes had a state
+        STORAGE_VERSION.save(deps.as_mut().storage, &1).unwrap();
+        let v1 = Cw721Config::<TributeDataV1, TributeConfig, TributeIndexesV1>::default();
+        for (token_id, day) in [
+            ("token1", 20250101),
+            ("token2", 20250101),
+            ("token3", 20250101),
+            ("token4", 20250102),
+            ("token5", 20250102),
+        ] {
+            let token = NftInfo {
+                owner: Addr::unchecked("owner"),
+                token_uri: None,
+                approvals: vec![],
+                extension: TributeDataV1 {
+                    settlement_amount_minor: Uint128::new(100),
+                    settlement_currency: Denom::Fiat(Currency::Usd),
+                    nominal_price: Decimal::one(),
+                    nominal_amount_minor: Uint128::new(100),
+                    worldwide_day: day,
+                    created_at: Timestamp::from_seconds(1000),
+                },
+            };
+            v1.nft_info
+                .save(deps.as_mut().storage, token_id, &token)
+                .unwrap();
+            v1.increment_tokens(deps.as_mut().storage).unwrap();
+        }
+
+        let mut step = |limit: u32| -> MigrationProgress {
+            let response = migrate(
+                deps.as_mut(),
+                env.clone(),
+                MigrateMsg::MigrateTokens { limit: Some(limit) },
+            )
+            .unwrap();
+            from_json(response.data.unwrap()).unwrap()
+        };
+        let progress: Vec<_> = (0..3)
+            .map(|_| {
+                let p = step(2);
+                (p.version, p.migrated, p.finished)
+            })
+            .collect();
+        assert_eq!(progress, vec![(1, 2, false), (1, 2, false), (2, 1, true)]);
+        // a finished migration is not repeated
+        assert_eq!(
+            step(2),
+            MigrationProgress {
+                version: 2,
+                migrated: 0,
+                finished: true,
+            }
+        );
+
+        let config = Cw721Config::<TributeData, TributeConfig, TributeIndexes>::default();
+        assert_eq!(config.token_count(&deps.storage).unwrap(), 5);
+        let token = config.nft_info.load(&deps.storage, "token4").unwrap();
+        assert_eq!(token.extension.worldwide_day, 20250102);
+        assert_eq!(token.extension.state, TributeState::Offered);
+
+        // the state index is built, the other indexes have no stale entries
+        let by_state = config
+            .nft_info
+            .idx
+            .extension
+            .state
+            .prefix(("offered".to_string(), 20250102))
+            .keys(&deps.storage, None, None, Order::Ascending)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(by_state, vec!["token4", "token5"]);
+        let by_day = config
+            .nft_info
+            .idx
+            .extension
+            .worldwide_day
+            .prefix(20250101)
+            .keys(&deps.storage, None, None, Order::Ascending)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(by_day, vec!["token1", "token2", "token3"]);
+        let by_owner = config
+            .nft_info
+            .idx
+            .owner
+            .prefix(Addr::unchecked("owner"))
+            .keys(&deps.storage, None, None, Order::Ascending)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(by_owner.len(), 5);
+        let stats: DailyStats = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::DailyStats { date: 20250101 },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(stats.tributes_count, 3);
+        assert_eq!(stats.total_nominal_amount_minor, Uint128::new(300));
+
+        // a store of a newer contract is not taken back
+        STORAGE_VERSION.save(deps.as_mut().storage, &3).unwrap();
+        let err = migrate(
+            deps.as_mut(),
+            env.clone(),
+            MigrateMsg::MigrateTokens { limit: None },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Cw721ContractError(Cw721ContractError::StorageDowngrade {
+                stored: 3,
+                target: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_mint_during_migration() {
+        use crate::contract::migrate;
+        use crate::msg::MigrateMsg;
+        use crate::types::{TributeDataV1, TributeIndexesV1};
+        use outbe_nft::migration::STORAGE_VERSION;
+
+        let api = MockApi::default();
+        let owner_addr = api.addr_make("owner");
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = message_info(&owner_addr, &[]);
+
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            InstantiateMsg {
+                name: "Test".to_string(),
+                symbol: "TEST".to_string(),
+                minter: None,
+                creator: None,
+                burner: None,
+                collection_info_extension: TributeCollectionExtension::new(
+                    Denom::Native("coen".to_string()),
+                    api.addr_make("oracle"),
+                ),
+            },
+        )
+        .unwrap();
+
+        STORAGE_VERSION.save(deps.as_mut().storage, &1).unwrap();
+        let v1 = Cw721Config::<TributeDataV1, TributeConfig, TributeIndexesV1>::default();
+        for token_id in ["token1", "token2"] {
+            let token = NftInfo {
+                owner: owner_addr.clone(),
+                token_uri: None,
+                approvals: vec![],
+                extension: TributeDataV1 {
+                    settlement_amount_minor: Uint128::new(100),
+                    settlement_currency: Denom::Fiat(Currency::Usd),
+                    nominal_price: Decimal::one(),
+                    nominal_amount_minor: Uint128::new(100),
+                    worldwide_day: 20250101,
+                    created_at: Timestamp::from_seconds(1000),
+                },
+            };
+            v1.nft_info
+                .save(deps.as_mut().storage, token_id, &token)
+                .unwrap();
+            v1.increment_tokens(deps.as_mut().storage).unwrap();
+        }
+
+        let mint = ExecuteMsg::Mint {
+            token_id: "token3".to_string(),
+            owner: owner_addr.to_string(),
+            token_uri: None,
+            extension: Box::new(MintExtension {
+                data: TributeMintData {
+                    tribute_id: "token3".to_string(),
+                    worldwide_day: 20250101,
+                    owner: owner_addr.to_string(),
+                    settlement_amount_minor: Uint128::new(100),
+                    settlement_currency: Denom::Fiat(Currency::Usd),
+                    nominal_amount_minor: Uint128::new(100),
+                    nominal_price: Decimal::one(),
+                    vector_id: None,
+                    amounts_commitment: None,
+                },
+            }),
+        };
+        let pending = ContractError::Cw721ContractError(Cw721ContractError::MigrationPending {
+            expected: 2,
+            stored: 1,
+        });
+
+        // half of the tributes are migrated
+        migrate(
+            deps.as_mut(),
+            env.clone(),
+            MigrateMsg::MigrateTokens { limit: Some(1) },
+        )
+        .unwrap();
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), mint.clone()).unwrap_err();
+        assert_eq!(err, pending);
+        let burn = ExecuteMsg::Burn {
+            token_id: "token2".to_string(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), burn.clone()).unwrap_err();
+        assert_eq!(err, pending);
+
+        let response = migrate(
+            deps.as_mut(),
+            env.clone(),
+            MigrateMsg::MigrateTokens { limit: Some(1) },
+        )
+        .unwrap();
+        let progress: outbe_nft::migration::MigrationProgress =
+            from_json(response.data.unwrap()).unwrap();
+        assert!(progress.finished);
+
+        execute(deps.as_mut(), env.clone(), info.clone(), mint).unwrap();
+        conclude_day(deps.as_mut(), &env, &info, 20250101);
+        execute(deps.as_mut(), env.clone(), info.clone(), burn).unwrap();
+        let config = Cw721Config::<TributeData, TributeConfig, TributeIndexes>::default();
+        assert_eq!(config.token_count(&deps.storage).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_rebuild_stats() {
+        use crate::contract::migrate;
+        use crate::msg::MigrateMsg;
+        use crate::query::{query, QueryMsg};
+        use crate::stats::{DailyStats, StatsRebuildProgress, STATS_COMPLETE};
+        use cw_storage_plus::Map;
+
+        let api = MockApi::default();
+        let owner_addr = api.addr_make("owner");
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = message_info(&owner_addr, &[]);
+
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            InstantiateMsg {
+                name: "Test".to_string(),
+                symbol: "TEST".to_string(),
+                minter: None,
+                creator: None,
+                burner: None,
+                collection_info_extension: TributeCollectionExtension::new(
+                    Denom::Native("coen".to_string()),
+                    api.addr_make("oracle"),
+                ),
+            },
+        )
+        .unwrap();
+        for (token_id, day) in [
+            ("token1", 20250101),
+            ("token2", 20250101),
+            ("token3", 20250102),
+        ] {
+            create_test_token_with_day(deps.as_mut().storage, token_id, day);
+        }
+        let daily_stats = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, date| -> DailyStats {
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::DailyStats { date }).unwrap())
+                .unwrap()
+        };
+        let rebuild = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>| -> StatsRebuildProgress {
+            let response = migrate(
+                deps.as_mut(),
+                mock_env(),
+                MigrateMsg::RebuildStats { limit: Some(2) },
+            )
+            .unwrap();
+            from_json(response.data.unwrap()).unwrap()
+        };
+
+        // stats of a new collection are complete
+        assert_eq!(
+            rebuild(&mut deps),
+            StatsRebuildProgress {
+                rebuilt: 0,
+                finished: true,
+            }
+        );
+
+        // a collection with tributes minted before the stats were kept
+        STATS_COMPLETE.remove(deps.as_mut().storage);
+        let stats = Map::<WorldwideDay, DailyStats>::new("tokens__daily_stats");
+        stats.remove(deps.as_mut().storage, 20250101);
+        let mut wrong = daily_stats(&deps, 20250102);
+        wrong.tributes_count = 7;
+        stats.save(deps.as_mut().storage, 20250102, &wrong).unwrap();
+
+        assert_eq!(
+            rebuild(&mut deps),
+            StatsRebuildProgress {
+                rebuilt: 2,
+                finished: false,
+            }
+        );
+        let burn = ExecuteMsg::Burn {
+            token_id: "token3".to_string(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), burn.clone()).unwrap_err();
+        assert_eq!(err, ContractError::StatsRebuildPending {});
+
+        assert_eq!(
+            rebuild(&mut deps),
+            StatsRebuildProgress {
+                rebuilt: 1,
+                finished: true,
+            }
+        );
+        assert_eq!(daily_stats(&deps, 20250101).tributes_count, 2);
+        assert_eq!(daily_stats(&deps, 20250102).tributes_count, 1);
+
+        // the rebuild only runs once, later changes are kept by the index
+        conclude_day(deps.as_mut(), &env, &info, 20250102);
+        execute(deps.as_mut(), env.clone(), info.clone(), burn).unwrap();
+        assert_eq!(
+            rebuild(&mut deps),
+            StatsRebuildProgress {
+                rebuilt: 0,
+                finished: true,
+            }
+        );
+        assert_eq!(daily_stats(&deps, 20250102), DailyStats::default());
+        assert_eq!(daily_stats(&deps, 20250101).tributes_count, 2);
+    }
+
+    #[test]
+    fn test_migrate_refuses_downgrade() {
+        use crate::contract::{migrate, CONTRACT_NAME};
+        use crate::msg::MigrateMsg;
+
+        let mut deps = mock_dependencies();
+        cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.0.1").unwrap();
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::Migrate {}).unwrap();
+
+        cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "99.0.0").unwrap();
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::Migrate {}).unwrap_err();
+    }
+
+    fn conclude_day(deps: DepsMut, env: &Env, info: &MessageInfo, date: WorldwideDay) {
+        execute(
+            deps,
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::ConcludeDay { date, limit: None },
+        )
+        .unwrap();
+    }
+
     fn create_test_token(storage: &mut dyn Storage, token_id: &str) {
-        let config = Cw721Config::<TributeData, TributeConfig>::default();
+        let config = Cw721Config::<TributeData, TributeConfig, TributeIndexes>::default();
         let token = TributeNft {
             owner: Addr::unchecked("owner"),
             token_uri: None,
+            approvals: vec![],
             extension: TributeData {
                 settlement_amount_minor: Uint128::new(100),
                 settlement_currency: Denom::Fiat(Currency::Usd),
@@ -483,6 +2159,10 @@ mod tests {
                 nominal_amount_minor: Uint128::new(100),
                 worldwide_day: 1,
                 created_at: Timestamp::from_seconds(1000),
+                state: TributeState::Offered,
+                vector_id: None,
+                normalized_settlement: None,
+                amounts_commitment: None,
             },
         };
         config.nft_info.save(storage, token_id, &token).unwrap();
@@ -490,10 +2170,11 @@ mod tests {
     }
 
     fn create_test_token_with_day(storage: &mut dyn Storage, token_id: &str, day: WorldwideDay) {
-        let config = Cw721Config::<TributeData, TributeConfig>::default();
+        let config = Cw721Config::<TributeData, TributeConfig, TributeIndexes>::default();
         let token = TributeNft {
             owner: Addr::unchecked("owner"),
             token_uri: None,
+            approvals: vec![],
             extension: TributeData {
                 settlement_amount_minor: Uint128::new(100),
                 settlement_currency: Denom::Fiat(Currency::Usd),
@@ -501,6 +2182,10 @@ mod tests {
                 nominal_amount_minor: Uint128::new(100),
                 worldwide_day: day,
                 created_at: Timestamp::from_seconds(1000),
+                state: TributeState::Offered,
+                vector_id: None,
+                normalized_settlement: None,
+                amounts_commitment: None,
             },
         };
         config.nft_info.save(storage, token_id, &token).unwrap();
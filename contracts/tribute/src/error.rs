@@ -1,5 +1,8 @@
-use cosmwasm_std::{StdError, VerificationError};
+use crate::types::TributeStatus;
+use cosmwasm_std::{CheckedMultiplyFractionError, Decimal, StdError, VerificationError};
 use outbe_nft::error::Cw721ContractError;
+use outbe_utils::date::{DateError, WorldwideDay};
+use outbe_utils::denom::Denom;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -14,8 +17,39 @@ pub enum ContractError {
     WrongDigest {},
     #[error("{0}")]
     VerificationError(#[from] VerificationError),
+    #[error("{0}")]
+    CheckedMultiplyFraction(#[from] CheckedMultiplyFractionError),
     #[error("HashAlreadyExists")]
     HashAlreadyExists {},
     #[error("Submission failed: tribute already exists on the network")]
     AlreadyExists {},
+    #[error("Tribute {token_id} is {state} and no longer offered")]
+    NotOffered {
+        token_id: String,
+        state: TributeStatus,
+    },
+    #[error("Unknown vector {vector_id}")]
+    UnknownVector { vector_id: u16 },
+    #[error("Vector contract is not configured")]
+    VectorNotConfigured {},
+    #[error("Tributes of day {date} are sealed")]
+    DaySealed { date: WorldwideDay },
+    #[error("Tributes of day {date} are offered until the day is concluded")]
+    NotConcluded { date: WorldwideDay },
+    #[error("Retention of archived tributes is not configured")]
+    RetentionNotConfigured {},
+    #[error(transparent)]
+    DateError(#[from] DateError),
+    #[error(
+        "Settlement currency {settlement_currency} can not be normalised, only fiat is supported"
+    )]
+    NotFiatSettlement { settlement_currency: Denom },
+    #[error("Daily stats are being rebuilt, call RebuildStats until it is finished")]
+    StatsRebuildPending {},
+    #[error("Nominal price {nominal_price} is out of the oracle bounds [{min}, {max}]")]
+    PriceOutOfBounds {
+        nominal_price: Decimal,
+        min: Decimal,
+        max: Decimal,
+    },
 }
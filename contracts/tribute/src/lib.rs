@@ -1,5 +1,9 @@
 pub mod contract;
 pub mod error;
+pub mod merkle;
 pub mod msg;
+pub mod price;
 pub mod query;
+pub mod stats;
 pub mod types;
+pub mod vector;
@@ -0,0 +1,297 @@
+use crate::error::ContractError;
+use crate::types::TributeCollection;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Empty, HexBinary, Order, StdError, StdResult, Storage, Timestamp, Uint128};
+use cw_storage_plus::Map;
+use outbe_nft::collection::Cw721Collection;
+use outbe_utils::date::WorldwideDay;
+use sha2::{Digest, Sha256};
+
+/// Merkle root of the Tributes of a worldwide day, fixed by `ExecuteMsg::SealDay`
+#[cw_serde]
+pub struct DaySeal {
+    pub root: HexBinary,
+    pub leaves_count: u64,
+    pub sealed_at: Timestamp,
+}
+
+pub const DAY_SEALS: Map<WorldwideDay, DaySeal> = Map::new("day_seals");
+
+/// Sealed days whose Tributes have all been concluded, they can be burned from then on
+const CONCLUDED_DAYS: Map<WorldwideDay, Empty> = Map::new("concluded_days");
+
+/// Leaf hashes of the sealed days by position, kept so proofs survive transfers and pruning
+const SEALED_LEAVES: Map<(WorldwideDay, u64), HexBinary> = Map::new("sealed_leaves");
+
+/// Position of a Tribute among the leaves of its sealed day
+const SEALED_LEAF_INDEXES: Map<(WorldwideDay, &str), u64> = Map::new("sealed_leaf_indexes");
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// `sha256(0x00 || len(token_id) || token_id || len(owner) || owner || nominal_amount_minor)`,
+/// lengths are big endian `u32` and the amount is a big endian `u128`
+pub fn leaf_hash(token_id: &str, owner: &str, nominal_amount_minor: Uint128) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update((token_id.len() as u32).to_be_bytes());
+    hasher.update(token_id.as_bytes());
+    hasher.update((owner.len() as u32).to_be_bytes());
+    hasher.update(owner.as_bytes());
+    hasher.update(nominal_amount_minor.u128().to_be_bytes());
+    hasher.finalize().into()
+}
+
+/// `sha256(0x01 || left || right)`
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(left, right),
+            // the last node of an odd level is moved up unchanged
+            [single] => *single,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Root of the tree over `leaves`, `sha256("")` for a day without Tributes
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return Sha256::digest([]).into();
+    }
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+/// Sibling hashes from the leaf at `index` up to the root, levels where the node has no
+/// sibling are skipped
+pub fn merkle_proof(leaves: &[[u8; 32]], mut index: usize) -> Vec<[u8; 32]> {
+    let mut proof = vec![];
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        if sibling < level.len() {
+            proof.push(level[sibling]);
+        }
+        level = next_level(&level);
+        index /= 2;
+    }
+    proof
+}
+
+/// Checks a proof of `merkle_proof` against the root of a tree of `leaves_count` leaves
+pub fn verify_proof(
+    leaf: &[u8; 32],
+    mut index: u64,
+    mut leaves_count: u64,
+    proof: &[[u8; 32]],
+    root: &[u8; 32],
+) -> bool {
+    if index >= leaves_count {
+        return false;
+    }
+    let mut hash = *leaf;
+    let mut siblings = proof.iter();
+    while leaves_count > 1 {
+        let sibling = index ^ 1;
+        if sibling < leaves_count {
+            let Some(sibling_hash) = siblings.next() else {
+                return false;
+            };
+            hash = if index.is_multiple_of(2) {
+                node_hash(&hash, sibling_hash)
+            } else {
+                node_hash(sibling_hash, &hash)
+            };
+        }
+        index /= 2;
+        leaves_count = leaves_count.div_ceil(2);
+    }
+    siblings.next().is_none() && &hash == root
+}
+
+/// Computes and stores the root over the Tributes of the day, ordered by token id
+pub fn seal_day(
+    storage: &mut dyn Storage,
+    date: WorldwideDay,
+    sealed_at: Timestamp,
+) -> Result<DaySeal, ContractError> {
+    if DAY_SEALS.has(storage, date) {
+        return Err(ContractError::DaySealed { date });
+    }
+
+    let leaves = TributeCollection::config()
+        .nft_info
+        .idx
+        .extension
+        .worldwide_day
+        .prefix(date)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            item.map(|(token_id, tribute)| {
+                let leaf = leaf_hash(
+                    &token_id,
+                    tribute.owner.as_str(),
+                    tribute.extension.nominal_amount_minor,
+                );
+                (token_id, leaf)
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (index, (token_id, leaf)) in leaves.iter().enumerate() {
+        let index = index as u64;
+        SEALED_LEAVES.save(storage, (date, index), &HexBinary::from(leaf))?;
+        SEALED_LEAF_INDEXES.save(storage, (date, token_id.as_str()), &index)?;
+    }
+    let leaves: Vec<[u8; 32]> = leaves.into_iter().map(|(_, leaf)| leaf).collect();
+    let seal = DaySeal {
+        root: HexBinary::from(merkle_root(&leaves)),
+        leaves_count: leaves.len() as u64,
+        sealed_at,
+    };
+    DAY_SEALS.save(storage, date, &seal)?;
+    Ok(seal)
+}
+
+pub fn assert_not_sealed(storage: &dyn Storage, date: WorldwideDay) -> Result<(), ContractError> {
+    if DAY_SEALS.has(storage, date) {
+        return Err(ContractError::DaySealed { date });
+    }
+    Ok(())
+}
+
+/// Tributes of a sealed day are its leaves until the day is concluded, burning one would leave
+/// the root and `leaves_count` of the seal behind
+pub fn assert_not_running(storage: &dyn Storage, date: WorldwideDay) -> Result<(), ContractError> {
+    if DAY_SEALS.has(storage, date) && !CONCLUDED_DAYS.has(storage, date) {
+        return Err(ContractError::DaySealed { date });
+    }
+    Ok(())
+}
+
+pub fn conclude_day(storage: &mut dyn Storage, date: WorldwideDay) -> StdResult<()> {
+    CONCLUDED_DAYS.save(storage, date, &Empty {})
+}
+
+#[cw_serde]
+pub struct InclusionProofResponse {
+    pub root: HexBinary,
+    pub leaves_count: u64,
+    /// Position of the Tribute among the leaves ordered by token id
+    pub index: u64,
+    pub leaf: HexBinary,
+    /// Sibling hashes from the leaf up to the root, see `verify_proof`
+    pub proof: Vec<HexBinary>,
+}
+
+pub fn query_inclusion_proof(
+    storage: &dyn Storage,
+    date: WorldwideDay,
+    token_id: &str,
+) -> StdResult<InclusionProofResponse> {
+    let seal = DAY_SEALS
+        .may_load(storage, date)?
+        .ok_or_else(|| StdError::generic_err(format!("Day {} is not sealed", date)))?;
+    let index = SEALED_LEAF_INDEXES
+        .may_load(storage, (date, token_id))?
+        .ok_or_else(|| {
+            StdError::generic_err(format!(
+                "Tribute {} is not sealed in day {}",
+                token_id, date
+            ))
+        })?;
+
+    let leaves = SEALED_LEAVES
+        .prefix(date)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.and_then(|(_, leaf)| to_hash(&leaf)))
+        .collect::<StdResult<Vec<_>>>()?;
+    let proof = merkle_proof(&leaves, index as usize)
+        .into_iter()
+        .map(HexBinary::from)
+        .collect();
+
+    Ok(InclusionProofResponse {
+        root: seal.root,
+        leaves_count: seal.leaves_count,
+        index,
+        leaf: HexBinary::from(leaves[index as usize]),
+        proof,
+    })
+}
+
+#[cw_serde]
+pub struct SealedLeafIndexesResponse {
+    /// Leaf position of each requested Tribute, `None` for Tributes not sealed in the day
+    pub indexes: Vec<Option<u64>>,
+}
+
+pub fn query_sealed_leaf_indexes(
+    storage: &dyn Storage,
+    date: WorldwideDay,
+    token_ids: &[String],
+) -> StdResult<SealedLeafIndexesResponse> {
+    let indexes = token_ids
+        .iter()
+        .map(|token_id| SEALED_LEAF_INDEXES.may_load(storage, (date, token_id.as_str())))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(SealedLeafIndexesResponse { indexes })
+}
+
+fn to_hash(value: &HexBinary) -> StdResult<[u8; 32]> {
+    value
+        .to_array()
+        .map_err(|_| StdError::generic_err("Invalid leaf hash"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(count: usize) -> Vec<[u8; 32]> {
+        (0..count)
+            .map(|i| leaf_hash(&i.to_string(), "owner", Uint128::new(i as u128)))
+            .collect()
+    }
+
+    #[test]
+    fn test_proofs_verify_against_root() {
+        for count in 1..=9 {
+            let leaves = leaves(count);
+            let root = merkle_root(&leaves);
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = merkle_proof(&leaves, index);
+                assert!(verify_proof(
+                    leaf,
+                    index as u64,
+                    count as u64,
+                    &proof,
+                    &root
+                ));
+            }
+            let other = leaf_hash("other", "owner", Uint128::one());
+            let proof = merkle_proof(&leaves, 0);
+            assert!(!verify_proof(&other, 0, count as u64, &proof, &root));
+        }
+    }
+
+    #[test]
+    fn test_root_of_small_trees() {
+        let leaves = leaves(2);
+        assert_eq!(merkle_root(&leaves[..1]), leaves[0]);
+        assert_eq!(merkle_root(&leaves[..2]), node_hash(&leaves[0], &leaves[1]));
+    }
+}
@@ -1,13 +1,47 @@
+use crate::types::PriceCheck;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal, Uint128};
-use outbe_nft::msg::{CollectionInfoMsg, Cw721InstantiateMsg};
+use cosmwasm_std::{Addr, Binary, Decimal, HexBinary, Uint128};
+use cw_utils::Expiration;
+use outbe_nft::msg::{CollectionInfoMsg, Cw721InstantiateMsg, MintBatchItem};
+use outbe_nft::state::TransferPolicy;
 use outbe_utils::date::WorldwideDay;
-use outbe_utils::denom::Denom;
+use outbe_utils::denom::{Currency, Denom};
 
 #[cw_serde]
 pub struct TributeCollectionExtension {
     pub native_token: Denom,
     pub price_oracle: Addr,
+    /// Defines who can transfer Tributes, soulbound if not set
+    pub transfer_policy: Option<TransferPolicy>,
+    /// Keeps mint, transfer and burn history of Tributes, disabled if not set
+    pub track_history: Option<bool>,
+    /// Renders the metadata of Tributes as a `data:` token_uri, disabled if not set
+    pub render_token_uri: Option<bool>,
+    /// Bounds for the `nominal_price` of minted Tributes, not checked if not set
+    pub price_check: Option<PriceCheck>,
+    /// Days archived Tributes are kept after their worldwide day, see `ExecuteMsg::Prune`
+    pub retention_days: Option<u32>,
+    /// Vector contract to check the `vector_id` of Tributes against
+    pub vector: Option<Addr>,
+    /// Currency settlement amounts are normalised to, not normalised if not set
+    pub reference_currency: Option<Currency>,
+}
+
+impl TributeCollectionExtension {
+    /// Collection of the given native token and price oracle, every option unset
+    pub fn new(native_token: Denom, price_oracle: Addr) -> Self {
+        TributeCollectionExtension {
+            native_token,
+            price_oracle,
+            transfer_policy: None,
+            track_history: None,
+            render_token_uri: None,
+            price_check: None,
+            retention_days: None,
+            vector: None,
+            reference_currency: None,
+        }
+    }
 }
 
 pub type InstantiateMsg = Cw721InstantiateMsg<TributeCollectionExtension>;
@@ -37,21 +71,104 @@ pub enum ExecuteMsg {
         extension: Box<MintExtension>,
     },
 
+    /// Mint several NFTs at once, can only be called by the contract minter.
+    /// Either all tokens of the batch are minted or none.
+    MintBatch {
+        tokens: Vec<MintBatchItem<MintExtension>>,
+    },
+
     /// Burn an NFT the sender has access to
     Burn {
         token_id: String,
     },
+    /// Burn several NFTs at once, the sender must have access to all of them
+    BurnBatch {
+        token_ids: Vec<String>,
+    },
+    /// Transfer is a base message to move a token to another account without triggering actions
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    /// Send is a base message to transfer a token to a contract and trigger an action
+    /// on the receiving contract.
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    /// Allows operator to transfer / send the token from the owner's account.
+    /// If expiration is set, then this allowance has a time/height limit
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted Approval
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    /// Allows operator to transfer / send any token from the owner's account.
+    /// If expiration is set, then this allowance has a time/height limit
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted ApproveAll permission
+    RevokeAll {
+        operator: String,
+    },
     #[cfg(feature = "demo")]
     /// Removes all tributes previously submitted
     BurnAll {
         batch_size: Option<usize>,
     },
-    /// Removes all tributes previously submitted
+    /// Removes tributes of the given day, at most `limit` per call.
+    /// The response data tells whether tributes of the day are left.
     BurnForDay {
         date: WorldwideDay,
+        limit: Option<u32>,
+    },
+    /// Changes the vector of an offered tribute, can only be called by the tribute owner
+    SelectVector {
+        token_id: String,
+        vector_id: Option<u16>,
+    },
+    /// Marks offered tributes as recognized by the given Nods, can only be called by the burner
+    Recognize {
+        tributes: Vec<TributeRecognition>,
+    },
+    /// Fixes the Merkle root over the tributes of the given day, no tributes can be minted for
+    /// the day afterwards. Can only be called by the burner, the response data is the `DaySeal`.
+    SealDay {
+        date: WorldwideDay,
+    },
+    /// Marks tributes of the given day that are still offered as unrecognized, at most `limit`
+    /// per call. The response data tells whether offered tributes of the day are left.
+    ConcludeDay {
+        date: WorldwideDay,
+        limit: Option<u32>,
+    },
+    /// Archives recognized and unrecognized tributes of the given day, at most `limit` per call.
+    /// The response data tells whether tributes of the day are left to archive.
+    ArchiveDay {
+        date: WorldwideDay,
+        limit: Option<u32>,
+    },
+    /// Removes at most `limit` archived tributes older than the configured retention.
+    /// The response data tells whether expired tributes are left.
+    Prune {
+        limit: Option<u32>,
     },
 }
 
+#[cw_serde]
+pub struct TributeRecognition {
+    pub token_id: String,
+    pub nod_id: String,
+}
+
 #[cw_serde]
 pub struct MintExtension {
     pub data: TributeMintData,
@@ -71,9 +188,26 @@ pub struct TributeMintData {
     pub nominal_amount_minor: Uint128,
     /// Price in Native coins with a rate on the moment of the transaction
     pub nominal_price: Decimal,
+    /// Vector joined by the owner, one of the vectors of the configured vector contract
+    #[serde(default)]
+    pub vector_id: Option<u16>,
+    /// Commitment to the exact amounts when the published ones are rounded by the factory
+    #[serde(default)]
+    pub amounts_commitment: Option<HexBinary>,
 }
 
 #[cw_serde]
 pub enum MigrateMsg {
     Migrate {},
+    /// Rewrites at most `limit` stored tributes to the current layout of `TributeData`.
+    /// The response data tells whether tributes of an older layout are left.
+    MigrateTokens {
+        limit: Option<u32>,
+    },
+    /// Recomputes the daily stats of collections with tributes minted before the stats were
+    /// kept, counting at most `limit` tributes per call. Runs after `MigrateTokens` and only
+    /// once, the response data tells whether tributes are left.
+    RebuildStats {
+        limit: Option<u32>,
+    },
 }
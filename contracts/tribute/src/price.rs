@@ -0,0 +1,364 @@
+use crate::error::ContractError;
+use crate::types::{NormalizedSettlement, PriceCheck, TributeConfig};
+use cosmwasm_std::{Decimal, Deps, Uint128};
+use outbe_utils::consts::DECIMAL_PLACES;
+use outbe_utils::denom::{Currency, CurrencyCode, Denom};
+use price_oracle::query::QueryMsg as PriceOracleQueryMsg;
+use price_oracle::types::{PriceData, VwapData};
+use std::collections::BTreeMap;
+
+/// Accepted range of `nominal_price` for one settlement currency
+#[derive(Clone, Debug, PartialEq)]
+pub struct PriceBounds {
+    pub min: Decimal,
+    pub max: Decimal,
+}
+
+impl PriceBounds {
+    fn around(low: Decimal, high: Decimal, tolerance: Decimal) -> Self {
+        Self {
+            min: low * (Decimal::one() - tolerance.min(Decimal::one())),
+            max: high * (Decimal::one() + tolerance),
+        }
+    }
+
+    pub fn check(&self, nominal_price: Decimal) -> Result<(), ContractError> {
+        if nominal_price < self.min || nominal_price > self.max {
+            return Err(ContractError::PriceOutOfBounds {
+                nominal_price,
+                min: self.min,
+                max: self.max,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Queries the bounds for Tributes settled in `settlement_currency`, `None` if prices are not checked
+pub fn query_price_bounds(
+    deps: Deps,
+    config: &TributeConfig,
+    settlement_currency: &Denom,
+) -> Result<Option<PriceBounds>, ContractError> {
+    let pair = || (config.native_token.clone(), settlement_currency.clone());
+    let bounds = match &config.price_check {
+        PriceCheck::Disabled => return Ok(None),
+        PriceCheck::Latest { tolerance } => {
+            let (token1, token2) = pair();
+            let latest: PriceData = deps.querier.query_wasm_smart(
+                &config.price_oracle,
+                &PriceOracleQueryMsg::GetLatestPrice { token1, token2 },
+            )?;
+            PriceBounds::around(latest.price, latest.price, *tolerance)
+        }
+        PriceCheck::Vwap { tolerance } => {
+            let (token1, token2) = pair();
+            let vwap: VwapData = deps.querier.query_wasm_smart(
+                &config.price_oracle,
+                &PriceOracleQueryMsg::GetVwap { token1, token2 },
+            )?;
+            PriceBounds::around(vwap.vwap, vwap.vwap, *tolerance)
+        }
+        PriceCheck::DayRange { tolerance } => {
+            let (token1, token2) = pair();
+            let latest: PriceData = deps.querier.query_wasm_smart(
+                &config.price_oracle,
+                &PriceOracleQueryMsg::GetLatestPrice { token1, token2 },
+            )?;
+            let low = latest.low.unwrap_or(latest.price).min(latest.price);
+            let high = latest.high.unwrap_or(latest.price).max(latest.price);
+            PriceBounds::around(low, high, *tolerance)
+        }
+    };
+    Ok(Some(bounds))
+}
+
+/// Checks nominal prices of several Tributes, querying the oracle once per settlement currency
+pub struct PriceChecker<'a> {
+    deps: Deps<'a>,
+    config: &'a TributeConfig,
+    bounds: BTreeMap<String, Option<PriceBounds>>,
+}
+
+impl<'a> PriceChecker<'a> {
+    pub fn new(deps: Deps<'a>, config: &'a TributeConfig) -> Self {
+        Self {
+            deps,
+            config,
+            bounds: BTreeMap::new(),
+        }
+    }
+
+    pub fn check(
+        &mut self,
+        settlement_currency: &Denom,
+        nominal_price: Decimal,
+    ) -> Result<(), ContractError> {
+        let key = settlement_currency.to_string();
+        if !self.bounds.contains_key(&key) {
+            let bounds = query_price_bounds(self.deps, self.config, settlement_currency)?;
+            self.bounds.insert(key.clone(), bounds);
+        }
+        match &self.bounds[&key] {
+            Some(bounds) => bounds.check(nominal_price),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Converts `amount` from a currency to `reference` with `rate`, the price of the currency in
+/// `reference`. Both amounts have `DECIMAL_PLACES`, the result is rounded down to the minor units
+/// of `reference`, e.g. to whole yen for JPY.
+pub fn convert_to_reference(
+    amount: Uint128,
+    rate: Decimal,
+    reference: Currency,
+) -> Result<Uint128, ContractError> {
+    let converted = amount.checked_mul_floor(rate)?;
+    let minor_unit = Uint128::new(10).pow(DECIMAL_PLACES - reference.minor_units() as u32);
+    Ok(converted - converted % minor_unit)
+}
+
+/// Normalises settlement amounts of several Tributes to the reference currency, querying the
+/// fiat cross rate of the oracle once per settlement currency
+pub struct SettlementNormalizer<'a> {
+    deps: Deps<'a>,
+    config: &'a TributeConfig,
+    rates: BTreeMap<CurrencyCode, Decimal>,
+}
+
+impl<'a> SettlementNormalizer<'a> {
+    pub fn new(deps: Deps<'a>, config: &'a TributeConfig) -> Self {
+        Self {
+            deps,
+            config,
+            rates: BTreeMap::new(),
+        }
+    }
+
+    /// `None` if the collection has no reference currency
+    pub fn normalize(
+        &mut self,
+        settlement_currency: &Denom,
+        settlement_amount_minor: Uint128,
+    ) -> Result<Option<NormalizedSettlement>, ContractError> {
+        let Some(reference) = self.config.reference_currency else {
+            return Ok(None);
+        };
+        let Denom::Fiat(currency) = settlement_currency else {
+            return Err(ContractError::NotFiatSettlement {
+                settlement_currency: settlement_currency.clone(),
+            });
+        };
+        let rate = match self.rates.get(&currency.numeric_code()) {
+            Some(rate) => *rate,
+            None => {
+                let rate = self.query_rate(*currency, reference)?;
+                self.rates.insert(currency.numeric_code(), rate);
+                rate
+            }
+        };
+        Ok(Some(NormalizedSettlement {
+            currency: reference,
+            amount_minor: convert_to_reference(settlement_amount_minor, rate, reference)?,
+            rate,
+        }))
+    }
+
+    fn query_rate(
+        &self,
+        currency: Currency,
+        reference: Currency,
+    ) -> Result<Decimal, ContractError> {
+        if currency == reference {
+            return Ok(Decimal::one());
+        }
+        let latest: PriceData = self.deps.querier.query_wasm_smart(
+            &self.config.price_oracle,
+            &PriceOracleQueryMsg::GetLatestPrice {
+                token1: Denom::Fiat(currency),
+                token2: Denom::Fiat(reference),
+            },
+        )?;
+        Ok(latest.price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+    use cosmwasm_std::{
+        from_json, to_json_binary, Addr, ContractResult, SystemResult, Timestamp, Uint128,
+        WasmQuery,
+    };
+    use outbe_nft::state::TransferPolicy;
+    use outbe_utils::denom::Currency;
+    use std::str::FromStr;
+
+    fn config(price_check: PriceCheck) -> TributeConfig {
+        TributeConfig {
+            native_token: Denom::Native("coen".to_string()),
+            price_oracle: Addr::unchecked("oracle"),
+            transfer_policy: TransferPolicy::default(),
+            track_history: false,
+            render_token_uri: false,
+            price_check,
+            retention_days: None,
+            vector: None,
+            reference_currency: None,
+        }
+    }
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_price_bounds() {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|query| {
+            let WasmQuery::Smart { msg, .. } = query else {
+                panic!("unexpected query");
+            };
+            let response = match from_json(msg).unwrap() {
+                PriceOracleQueryMsg::GetLatestPrice { .. } => to_json_binary(&PriceData {
+                    price: dec("2"),
+                    timestamp: Timestamp::from_seconds(0),
+                    open: Some(dec("1.8")),
+                    high: Some(dec("2.5")),
+                    low: Some(dec("1.5")),
+                    close: Some(dec("2")),
+                    volume: None,
+                }),
+                PriceOracleQueryMsg::GetVwap { .. } => to_json_binary(&VwapData {
+                    vwap: dec("3"),
+                    total_volume: Uint128::new(10),
+                    window_seconds: 300,
+                    timestamp: Timestamp::from_seconds(0),
+                }),
+                _ => panic!("unexpected query"),
+            };
+            SystemResult::Ok(ContractResult::Ok(response.unwrap()))
+        });
+        let usd = Denom::Fiat(Currency::Usd);
+        let bounds = |price_check: PriceCheck| {
+            query_price_bounds(deps.as_ref(), &config(price_check), &usd).unwrap()
+        };
+
+        assert_eq!(bounds(PriceCheck::Disabled), None);
+        let tolerance = dec("0.1");
+        assert_eq!(
+            bounds(PriceCheck::Latest { tolerance }),
+            Some(PriceBounds {
+                min: dec("1.8"),
+                max: dec("2.2"),
+            })
+        );
+        assert_eq!(
+            bounds(PriceCheck::Vwap { tolerance }),
+            Some(PriceBounds {
+                min: dec("2.7"),
+                max: dec("3.3"),
+            })
+        );
+        let day_range = bounds(PriceCheck::DayRange { tolerance }).unwrap();
+        assert_eq!(
+            day_range,
+            PriceBounds {
+                min: dec("1.35"),
+                max: dec("2.75"),
+            }
+        );
+
+        day_range.check(dec("2.75")).unwrap();
+        assert_eq!(
+            day_range.check(dec("2.76")).unwrap_err(),
+            ContractError::PriceOutOfBounds {
+                nominal_price: dec("2.76"),
+                min: dec("1.35"),
+                max: dec("2.75"),
+            }
+        );
+    }
+
+    #[test]
+    fn test_settlement_normalizer() {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|query| {
+            let WasmQuery::Smart { msg, .. } = query else {
+                panic!("unexpected query");
+            };
+            let PriceOracleQueryMsg::GetLatestPrice { token1, token2 } = from_json(msg).unwrap()
+            else {
+                panic!("unexpected query");
+            };
+            let price = match (token1, token2) {
+                (Denom::Fiat(Currency::Usd), Denom::Fiat(Currency::Jpy)) => dec("150.5"),
+                (Denom::Fiat(Currency::Eur), Denom::Fiat(Currency::Jpy)) => dec("162.25"),
+                _ => panic!("unexpected pair"),
+            };
+            SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&PriceData {
+                    price,
+                    timestamp: Timestamp::from_seconds(0),
+                    open: None,
+                    high: None,
+                    low: None,
+                    close: None,
+                    volume: None,
+                })
+                .unwrap(),
+            ))
+        });
+        let mut config = config(PriceCheck::Disabled);
+        let amount = |value: &str| dec(value).atomics();
+
+        let mut normalizer = SettlementNormalizer::new(deps.as_ref(), &config);
+        assert_eq!(
+            normalizer
+                .normalize(&Denom::Fiat(Currency::Usd), amount("10"))
+                .unwrap(),
+            None
+        );
+
+        config.reference_currency = Some(Currency::Jpy);
+        let mut normalizer = SettlementNormalizer::new(deps.as_ref(), &config);
+        let normalize = |normalizer: &mut SettlementNormalizer, currency, value: &str| {
+            normalizer
+                .normalize(&Denom::Fiat(currency), amount(value))
+                .unwrap()
+                .unwrap()
+        };
+        // yen has no minor units
+        assert_eq!(
+            normalize(&mut normalizer, Currency::Usd, "1.234567"),
+            NormalizedSettlement {
+                currency: Currency::Jpy,
+                amount_minor: amount("185"),
+                rate: dec("150.5"),
+            }
+        );
+        assert_eq!(
+            normalize(&mut normalizer, Currency::Eur, "2").amount_minor,
+            amount("324")
+        );
+        assert_eq!(
+            normalize(&mut normalizer, Currency::Jpy, "1000").rate,
+            Decimal::one()
+        );
+        assert_eq!(
+            normalizer
+                .normalize(&Denom::Native("coen".to_string()), amount("1"))
+                .unwrap_err(),
+            ContractError::NotFiatSettlement {
+                settlement_currency: Denom::Native("coen".to_string()),
+            }
+        );
+
+        assert_eq!(
+            convert_to_reference(amount("1.23456"), dec("1.1"), Currency::Usd).unwrap(),
+            amount("1.35")
+        );
+    }
+}
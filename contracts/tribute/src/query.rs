@@ -1,40 +1,79 @@
-use crate::types::{TributeConfig, TributeData};
+use crate::contract::MintChecker;
+use crate::merkle::{
+    query_inclusion_proof, query_sealed_leaf_indexes, DaySeal, InclusionProofResponse,
+    SealedLeafIndexesResponse, DAY_SEALS,
+};
+use crate::msg::TributeMintData;
+use crate::stats::{DailyStats, TributesSummary, VectorTotal};
+use crate::types::{
+    TributeCollection, TributeConfig, TributeData, TributeNft, TributeStatus, NO_VECTOR,
+};
 use cosmwasm_schema::{cw_serde, QueryResponses};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_json_binary, Binary, Deps, Empty, Env, Order, StdResult, Uint128};
+use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order, StdResult, Uint128};
 use cw_storage_plus::Bound;
-use outbe_nft::query::MAX_LIMIT;
-use outbe_nft::state::Cw721Config;
+use outbe_nft::collection::Cw721Collection;
+use outbe_nft::query::{Cw721QueryMsg, DEFAULT_LIMIT, MAX_LIMIT};
 use outbe_utils::date::WorldwideDay;
 
 pub type TributeInfoResponse = outbe_nft::msg::NftInfoResponse<TributeData>;
-pub type TributeContractInfoResponse = outbe_nft::msg::ContractInfoResponse<TributeConfig>;
+pub type TributeAllInfoResponse = outbe_nft::msg::AllNftInfoResponse<TributeData>;
+pub type TributeCollectionInfoResponse = outbe_nft::msg::CollectionInfoResponse<TributeConfig>;
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
-    #[returns(TributeContractInfoResponse)]
-    ContractInfo {},
-
-    // TODO add Cw721 config as well
+    // ---- CW721 spec queries ----
     #[returns(outbe_nft::msg::OwnerOfResponse)]
-    OwnerOf { token_id: String },
+    OwnerOf {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+
+    #[returns(outbe_nft::msg::ApprovalResponse)]
+    Approval {
+        token_id: String,
+        spender: String,
+        include_expired: Option<bool>,
+    },
+
+    #[returns(outbe_nft::msg::ApprovalsResponse)]
+    Approvals {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+
+    #[returns(outbe_nft::msg::OperatorResponse)]
+    Operator {
+        owner: String,
+        operator: String,
+        include_expired: Option<bool>,
+    },
+
+    #[returns(outbe_nft::msg::OperatorsResponse)]
+    AllOperators {
+        owner: String,
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 
     #[returns(outbe_nft::msg::NumTokensResponse)]
     NumTokens {},
 
-    #[returns(cw_ownable::Ownership<String>)]
-    GetMinterOwnership {},
-
-    #[returns(cw_ownable::Ownership<String>)]
-    GetCreatorOwnership {},
-    #[returns(cw_ownable::Ownership<String>)]
-    GetBurnerOwnership {},
+    #[returns(outbe_nft::msg::ContractInfoResponse)]
+    ContractInfo {},
 
     #[returns(TributeInfoResponse)]
     NftInfo { token_id: String },
 
+    #[returns(TributeAllInfoResponse)]
+    AllNftInfo {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+
     /// Returns all tokens owned by the given address.
     /// Same as `AllTokens` but with owner filter.
     #[returns(outbe_nft::msg::TokensResponse)]
@@ -53,7 +92,34 @@ pub enum QueryMsg {
         query_order: Option<Order>,
     },
 
-    /// Returns all tokens created in the given date with an optional filter by status.
+    // ---- Tribute queries ----
+    /// Returns the collection info together with the Tribute config
+    #[returns(TributeCollectionInfoResponse)]
+    GetCollectionInfo {},
+
+    #[returns(cw_ownable::Ownership<String>)]
+    GetMinterOwnership {},
+
+    #[returns(cw_ownable::Ownership<String>)]
+    GetCreatorOwnership {},
+    #[returns(cw_ownable::Ownership<String>)]
+    GetBurnerOwnership {},
+
+    /// Returns the ERC721 metadata built from the token data
+    #[returns(outbe_nft::metadata::NftMetadata)]
+    NftMetadata { token_id: String },
+
+    /// Returns the recorded history of the token, also after it was burned.
+    /// Empty unless the collection keeps history.
+    #[returns(outbe_nft::msg::TokenHistoryResponse)]
+    TokenHistory {
+        token_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Returns tokens created in the given date, or all tokens if not set.
+    /// Prefer `TributesByDay`, which tells whether more tokens are left.
     #[returns(FullTributesResponse)]
     DailyTributes {
         date: Option<WorldwideDay>,
@@ -61,10 +127,95 @@ pub enum QueryMsg {
         limit: Option<u32>,
         query_order: Option<Order>,
     },
+    /// Returns tributes of the given date ordered by token id
+    #[returns(TributesPageResponse)]
+    TributesByDay {
+        date: WorldwideDay,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns tributes of the given owner and date ordered by token id
+    #[returns(TributesPageResponse)]
+    TributesByOwnerAndDay {
+        owner: String,
+        date: WorldwideDay,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns tributes of the dates from `from_day` to `to_day` inclusive, ordered by date
+    /// and token id
+    #[returns(TributesRangeResponse)]
+    TributesInRange {
+        from_day: WorldwideDay,
+        to_day: WorldwideDay,
+        start_after: Option<TributeCursor>,
+        limit: Option<u32>,
+    },
+    /// Returns the totals of the tributes of the given date
+    #[returns(TributesSummary)]
+    TributesByDaySummary { date: WorldwideDay },
+    /// Returns the totals of the tributes of the given owner and date
+    #[returns(TributesSummary)]
+    TributesByOwnerAndDaySummary { owner: String, date: WorldwideDay },
+    /// Returns the totals of the tributes of the dates from `from_day` to `to_day` inclusive
+    #[returns(TributesSummary)]
+    TributesInRangeSummary {
+        from_day: WorldwideDay,
+        to_day: WorldwideDay,
+    },
+    /// Returns tributes in the given state, optionally only the ones of the given date.
+    /// Without a date they are ordered by date.
+    #[returns(FullTributesResponse)]
+    TributesByState {
+        state: TributeStatus,
+        date: Option<WorldwideDay>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns tributes of the given date that joined the given vector, or no vector if not set
+    #[returns(FullTributesResponse)]
+    TributesByVector {
+        date: WorldwideDay,
+        vector_id: Option<u16>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the count and nominal amount of the tributes of the given date per vector
+    #[returns(DailyVectorTotalsResponse)]
+    DailyVectorTotals { date: WorldwideDay },
+    /// Runs the checks of `Mint` on the data without minting: nominal price bounds, vector and
+    /// sealed day. Minters use it to skip Tributes that would fail a whole `MintBatch`.
+    #[returns(CheckMintResponse)]
+    CheckMint { data: TributeMintData },
+    /// Returns the Merkle root of the given date if the day is sealed
+    #[returns(Option<DaySeal>)]
+    DaySeal { date: WorldwideDay },
+    /// Returns the Merkle inclusion proof of a tribute in its sealed day
+    #[returns(InclusionProofResponse)]
+    InclusionProof {
+        date: WorldwideDay,
+        token_id: String,
+    },
+    /// Returns the leaf positions of the given tributes in their sealed day, in the same order
+    #[returns(SealedLeafIndexesResponse)]
+    SealedLeafIndexes {
+        date: WorldwideDay,
+        token_ids: Vec<String>,
+    },
     /// Total Tribute Interest is calculated as the sum of nominal amount recorded within each
     /// Tribute for the given date.
     #[returns(TotalInterestResponse)]
     TotalInterest { date: WorldwideDay },
+    /// Returns the aggregates of the Tributes of the given date, zero if there are none.
+    /// The min and max nominal prices are the ones ever minted on the date, burns keep them.
+    #[returns(DailyStats)]
+    DailyStats { date: WorldwideDay },
+    /// Returns the aggregates of every date from `from` to `to` inclusive having Tributes
+    #[returns(DailyStatsRangeResponse)]
+    DailyStatsRange {
+        from: WorldwideDay,
+        to: WorldwideDay,
+    },
 }
 
 #[cw_serde]
@@ -73,84 +224,268 @@ pub struct FullTributeData {
     pub owner: String,
     pub data: TributeData,
 }
+#[cw_serde]
+pub struct CheckMintResponse {
+    /// Why the Tribute would be rejected, `None` if it can be minted
+    pub error: Option<String>,
+}
+
 #[cw_serde]
 pub struct TotalInterestResponse {
     pub total_nominal_amount: Uint128,
 }
 
+#[cw_serde]
+pub struct DailyVectorTotalsResponse {
+    pub vectors: Vec<VectorTotal>,
+}
+
+#[cw_serde]
+pub struct DailyStatsEntry {
+    pub date: WorldwideDay,
+    pub stats: DailyStats,
+}
+
+#[cw_serde]
+pub struct DailyStatsRangeResponse {
+    pub days: Vec<DailyStatsEntry>,
+}
+
 #[cw_serde]
 pub struct FullTributesResponse {
     pub tributes: Vec<FullTributeData>,
 }
 
+#[cw_serde]
+pub struct TributesPageResponse {
+    pub tributes: Vec<FullTributeData>,
+    /// Cursor of the next page, `None` when there are no tributes left
+    pub next_start_after: Option<String>,
+}
+
+/// Position of a tribute in `QueryMsg::TributesInRange`
+#[cw_serde]
+pub struct TributeCursor {
+    pub worldwide_day: WorldwideDay,
+    pub token_id: String,
+}
+
+#[cw_serde]
+pub struct TributesRangeResponse {
+    pub tributes: Vec<FullTributeData>,
+    /// Cursor of the next page, `None` when there are no tributes left
+    pub next_start_after: Option<TributeCursor>,
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::ContractInfo {} => to_json_binary(&outbe_nft::query::query_contract_info::<
-            TributeConfig,
-        >(deps.storage)?),
-        QueryMsg::OwnerOf { token_id } => to_json_binary(&outbe_nft::query::query_owner_of(
-            deps.storage,
-            &env,
-            token_id,
-        )?),
-        QueryMsg::NumTokens {} => {
-            to_json_binary(&outbe_nft::query::query_num_tokens(deps.storage)?)
+    let cw721_msg = match msg {
+        QueryMsg::DailyTributes {
+            date,
+            start_after,
+            limit,
+            query_order,
+        } => {
+            return to_json_binary(&query_daily_tributes(
+                deps,
+                &env,
+                date,
+                start_after,
+                limit,
+                query_order,
+            )?)
+        }
+        QueryMsg::TotalInterest { date } => {
+            return to_json_binary(&query_total_nominal_amount(deps, &env, date)?)
+        }
+        QueryMsg::TributesByDay {
+            date,
+            start_after,
+            limit,
+        } => return to_json_binary(&query_tributes_by_day(deps, date, start_after, limit)?),
+        QueryMsg::TributesByOwnerAndDay {
+            owner,
+            date,
+            start_after,
+            limit,
+        } => {
+            return to_json_binary(&query_tributes_by_owner_and_day(
+                deps,
+                owner,
+                date,
+                start_after,
+                limit,
+            )?)
         }
-        QueryMsg::GetMinterOwnership {} => {
-            to_json_binary(&outbe_nft::query::query_minter_ownership(deps.storage)?)
+        QueryMsg::TributesInRange {
+            from_day,
+            to_day,
+            start_after,
+            limit,
+        } => {
+            return to_json_binary(&query_tributes_in_range(
+                deps,
+                from_day,
+                to_day,
+                start_after,
+                limit,
+            )?)
+        }
+        QueryMsg::TributesByDaySummary { date } => {
+            return to_json_binary(&query_summary_in_range(deps, date, date)?)
         }
-        QueryMsg::GetCreatorOwnership {} => {
-            to_json_binary(&outbe_nft::query::query_creator_ownership(deps.storage)?)
+        QueryMsg::TributesByOwnerAndDaySummary { owner, date } => {
+            return to_json_binary(&query_owner_and_day_summary(deps, owner, date)?)
         }
-        QueryMsg::GetBurnerOwnership {} => {
-            to_json_binary(&outbe_nft::query::query_burner_ownership(deps.storage)?)
+        QueryMsg::TributesInRangeSummary { from_day, to_day } => {
+            return to_json_binary(&query_summary_in_range(deps, from_day, to_day)?)
         }
-        QueryMsg::NftInfo { token_id } => to_json_binary(&outbe_nft::query::query_nft_info::<
-            TributeData,
-        >(deps.storage, token_id)?),
+        QueryMsg::TributesByState {
+            state,
+            date,
+            start_after,
+            limit,
+        } => {
+            return to_json_binary(&query_tributes_by_state(
+                deps,
+                state,
+                date,
+                start_after,
+                limit,
+            )?)
+        }
+        QueryMsg::TributesByVector {
+            date,
+            vector_id,
+            start_after,
+            limit,
+        } => {
+            return to_json_binary(&query_tributes_by_vector(
+                deps,
+                date,
+                vector_id,
+                start_after,
+                limit,
+            )?)
+        }
+        QueryMsg::DailyVectorTotals { date } => {
+            return to_json_binary(&DailyVectorTotalsResponse {
+                vectors: query_daily_stats(deps, date)?.vector_totals,
+            })
+        }
+        QueryMsg::CheckMint { data } => {
+            let config = TributeCollection::config()
+                .collection_config
+                .load(deps.storage)?;
+            let checked = MintChecker::new(deps, &config).check(deps.storage, &data);
+            return to_json_binary(&CheckMintResponse {
+                error: checked.err().map(|err| err.to_string()),
+            });
+        }
+        QueryMsg::DaySeal { date } => {
+            return to_json_binary(&DAY_SEALS.may_load(deps.storage, date)?)
+        }
+        QueryMsg::InclusionProof { date, token_id } => {
+            return to_json_binary(&query_inclusion_proof(deps.storage, date, &token_id)?)
+        }
+        QueryMsg::SealedLeafIndexes { date, token_ids } => {
+            return to_json_binary(&query_sealed_leaf_indexes(deps.storage, date, &token_ids)?)
+        }
+        QueryMsg::DailyStats { date } => return to_json_binary(&query_daily_stats(deps, date)?),
+        QueryMsg::DailyStatsRange { from, to } => {
+            return to_json_binary(&query_daily_stats_range(deps, from, to)?)
+        }
+        QueryMsg::OwnerOf {
+            token_id,
+            include_expired,
+        } => Cw721QueryMsg::OwnerOf {
+            token_id,
+            include_expired,
+        },
+        QueryMsg::Approval {
+            token_id,
+            spender,
+            include_expired,
+        } => Cw721QueryMsg::Approval {
+            token_id,
+            spender,
+            include_expired,
+        },
+        QueryMsg::Approvals {
+            token_id,
+            include_expired,
+        } => Cw721QueryMsg::Approvals {
+            token_id,
+            include_expired,
+        },
+        QueryMsg::Operator {
+            owner,
+            operator,
+            include_expired,
+        } => Cw721QueryMsg::Operator {
+            owner,
+            operator,
+            include_expired,
+        },
+        QueryMsg::AllOperators {
+            owner,
+            include_expired,
+            start_after,
+            limit,
+        } => Cw721QueryMsg::AllOperators {
+            owner,
+            include_expired,
+            start_after,
+            limit,
+        },
+        QueryMsg::NumTokens {} => Cw721QueryMsg::NumTokens {},
+        QueryMsg::ContractInfo {} => Cw721QueryMsg::ContractInfo {},
+        QueryMsg::NftInfo { token_id } => Cw721QueryMsg::NftInfo { token_id },
+        QueryMsg::AllNftInfo {
+            token_id,
+            include_expired,
+        } => Cw721QueryMsg::AllNftInfo {
+            token_id,
+            include_expired,
+        },
         QueryMsg::Tokens {
             owner,
             start_after,
             limit,
             query_order,
-        } => to_json_binary(&outbe_nft::query::query_tokens(
-            deps,
-            &env,
+        } => Cw721QueryMsg::Tokens {
             owner,
             start_after,
             limit,
             query_order,
-        )?),
+        },
         QueryMsg::AllTokens {
             start_after,
             limit,
             query_order,
-        } => to_json_binary(&outbe_nft::query::query_all_tokens(
-            deps,
-            &env,
+        } => Cw721QueryMsg::AllTokens {
             start_after,
             limit,
             query_order,
-        )?),
-        QueryMsg::DailyTributes {
-            date,
+        },
+        QueryMsg::GetCollectionInfo {} => Cw721QueryMsg::GetCollectionInfo {},
+        QueryMsg::GetMinterOwnership {} => Cw721QueryMsg::GetMinterOwnership {},
+        QueryMsg::GetCreatorOwnership {} => Cw721QueryMsg::GetCreatorOwnership {},
+        QueryMsg::GetBurnerOwnership {} => Cw721QueryMsg::GetBurnerOwnership {},
+        QueryMsg::NftMetadata { token_id } => Cw721QueryMsg::NftMetadata { token_id },
+        QueryMsg::TokenHistory {
+            token_id,
             start_after,
             limit,
-            query_order,
-        } => to_json_binary(&query_daily_tributes(
-            deps,
-            &env,
-            date,
+        } => Cw721QueryMsg::TokenHistory {
+            token_id,
             start_after,
             limit,
-            query_order,
-        )?),
-        QueryMsg::TotalInterest { date } => {
-            to_json_binary(&query_total_nominal_amount(deps, &env, date)?)
-        }
-    }
+        },
+    };
+    TributeCollection::query_cw721(deps, &env, cw721_msg)
 }
+
 fn query_daily_tributes(
     deps: Deps,
     _env: &Env,
@@ -159,57 +494,314 @@ fn query_daily_tributes(
     limit: Option<u32>,
     query_order: Option<Order>,
 ) -> StdResult<FullTributesResponse> {
-    // todo deal with limit maybe to propagate here amount?
-    let limit = limit.unwrap_or(MAX_LIMIT) as usize;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let order = query_order.unwrap_or(Order::Ascending);
 
-    let (start, end) = match order {
-        Order::Ascending => (start_after.as_deref().map(Bound::exclusive), None),
-        Order::Descending => (None, start_after.as_deref().map(Bound::exclusive)),
+    let config = TributeCollection::config();
+    let tributes = match date {
+        Some(date) => {
+            let (start, end) = match order {
+                Order::Ascending => (start_after.map(Bound::exclusive), None),
+                Order::Descending => (None, start_after.map(Bound::exclusive)),
+            };
+            config
+                .nft_info
+                .idx
+                .extension
+                .worldwide_day
+                .prefix(date)
+                .range(deps.storage, start, end, order)
+        }
+        None => {
+            let (start, end) = match order {
+                Order::Ascending => (start_after.as_deref().map(Bound::exclusive), None),
+                Order::Descending => (None, start_after.as_deref().map(Bound::exclusive)),
+            };
+            config.nft_info.range(deps.storage, start, end, order)
+        }
     };
 
-    let tokens: StdResult<Vec<FullTributeData>> =
-        Cw721Config::<TributeData, Option<Empty>>::default()
-            .nft_info
-            .range(deps.storage, start, end, order)
-            .take(limit)
-            .filter_map(|item| match item {
-                Ok((id, tribute))
-                    if date.is_none() || tribute.extension.worldwide_day == date.unwrap() =>
-                {
-                    Some(Ok(FullTributeData {
-                        token_id: id,
-                        owner: tribute.owner.to_string(),
-                        data: tribute.extension,
-                    }))
-                }
-                _ => None,
+    let tokens: StdResult<Vec<FullTributeData>> = tributes
+        .take(limit)
+        .map(|item| {
+            item.map(|(id, tribute)| FullTributeData {
+                token_id: id,
+                owner: tribute.owner.to_string(),
+                data: tribute.extension,
             })
-            .collect();
+        })
+        .collect();
 
     Ok(FullTributesResponse { tributes: tokens? })
 }
 
-fn query_total_nominal_amount(
+/// Takes up to `limit` tributes, the cursor of the last one is returned if more are left
+fn take_page<C>(
+    tributes: impl Iterator<Item = StdResult<(String, TributeNft)>>,
+    limit: Option<u32>,
+    cursor: impl Fn(&FullTributeData) -> C,
+) -> StdResult<(Vec<FullTributeData>, Option<C>)> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let mut page = tributes
+        .take(limit + 1)
+        .map(|item| {
+            item.map(|(token_id, tribute)| FullTributeData {
+                token_id,
+                owner: tribute.owner.to_string(),
+                data: tribute.extension,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let next = if page.len() > limit {
+        page.truncate(limit);
+        page.last().map(cursor)
+    } else {
+        None
+    };
+    Ok((page, next))
+}
+
+fn query_tributes_by_day(
     deps: Deps,
-    _env: &Env,
     date: WorldwideDay,
-) -> StdResult<TotalInterestResponse> {
-    let total_nominal_amount: Uint128 = Cw721Config::<TributeData, Option<Empty>>::default()
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TributesPageResponse> {
+    let config = TributeCollection::config();
+    let tributes = config
         .nft_info
+        .idx
+        .extension
+        .worldwide_day
+        .prefix(date)
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        );
+    let (tributes, next_start_after) = take_page(tributes, limit, |t| t.token_id.clone())?;
+    Ok(TributesPageResponse {
+        tributes,
+        next_start_after,
+    })
+}
+
+fn query_tributes_by_owner_and_day(
+    deps: Deps,
+    owner: String,
+    date: WorldwideDay,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TributesPageResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let config = TributeCollection::config();
+    let tributes = config
+        .nft_info
+        .idx
+        .extension
+        .owner_day
+        .prefix((owner, date))
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        );
+    let (tributes, next_start_after) = take_page(tributes, limit, |t| t.token_id.clone())?;
+    Ok(TributesPageResponse {
+        tributes,
+        next_start_after,
+    })
+}
+
+fn query_tributes_in_range(
+    deps: Deps,
+    from_day: WorldwideDay,
+    to_day: WorldwideDay,
+    start_after: Option<TributeCursor>,
+    limit: Option<u32>,
+) -> StdResult<TributesRangeResponse> {
+    let start = match start_after {
+        Some(cursor) => Bound::exclusive((cursor.worldwide_day, cursor.token_id)),
+        None => Bound::inclusive((from_day, String::new())),
+    };
+    // days are numbers, so the day after `to_day` bounds the range even if it is not a date
+    let end = Bound::exclusive((to_day.saturating_add(1), String::new()));
+
+    let config = TributeCollection::config();
+    let tributes = config.nft_info.idx.extension.worldwide_day.range(
+        deps.storage,
+        Some(start),
+        Some(end),
+        Order::Ascending,
+    );
+    let (tributes, next_start_after) = take_page(tributes, limit, |t| TributeCursor {
+        worldwide_day: t.data.worldwide_day,
+        token_id: t.token_id.clone(),
+    })?;
+    Ok(TributesRangeResponse {
+        tributes,
+        next_start_after,
+    })
+}
+
+fn query_summary_in_range(
+    deps: Deps,
+    from_day: WorldwideDay,
+    to_day: WorldwideDay,
+) -> StdResult<TributesSummary> {
+    let mut summary = TributesSummary::default();
+    for (_, stats) in TributeCollection::config()
+        .nft_info
+        .idx
+        .extension
+        .daily_stats
+        .range(deps.storage, from_day, to_day)?
+    {
+        summary.add_stats(&stats)?;
+    }
+    Ok(summary)
+}
+
+fn query_owner_and_day_summary(
+    deps: Deps,
+    owner: String,
+    date: WorldwideDay,
+) -> StdResult<TributesSummary> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let mut summary = TributesSummary::default();
+    for item in TributeCollection::config()
+        .nft_info
+        .idx
+        .extension
+        .owner_day
+        .prefix((owner, date))
         .range(deps.storage, None, None, Order::Ascending)
-        .filter(
-            |item| matches!(item, Ok((_id, tribute)) if tribute.extension.worldwide_day == date),
+    {
+        let (_, tribute) = item?;
+        summary.add_tribute(&tribute.extension)?;
+    }
+    Ok(summary)
+}
+
+fn query_tributes_by_state(
+    deps: Deps,
+    state: TributeStatus,
+    date: Option<WorldwideDay>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<FullTributesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let config = TributeCollection::config();
+    let index = &config.nft_info.idx.extension.state;
+    let tributes = match date {
+        Some(date) => index.prefix((state.to_string(), date)).range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        ),
+        None => {
+            // the index is ordered by date first, so the cursor needs the date of its tribute
+            let start = match start_after {
+                Some(token_id) => {
+                    let token = config.nft_info.load(deps.storage, &token_id)?;
+                    Some(Bound::exclusive((token.extension.worldwide_day, token_id)))
+                }
+                None => None,
+            };
+            index
+                .sub_prefix(state.to_string())
+                .range(deps.storage, start, None, Order::Ascending)
+        }
+    };
+
+    let tributes = tributes
+        .take(limit)
+        .map(|item| {
+            item.map(|(token_id, tribute)| FullTributeData {
+                token_id,
+                owner: tribute.owner.to_string(),
+                data: tribute.extension,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(FullTributesResponse { tributes })
+}
+
+fn query_tributes_by_vector(
+    deps: Deps,
+    date: WorldwideDay,
+    vector_id: Option<u16>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<FullTributesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let tributes = TributeCollection::config()
+        .nft_info
+        .idx
+        .extension
+        .vector
+        .prefix((date, vector_id.unwrap_or(NO_VECTOR)))
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
         )
-        .map(|it| it.unwrap())
-        .map(|(_, tribute)| tribute.extension.nominal_amount_minor)
-        .fold(Uint128::zero(), |acc, t| acc + t);
+        .take(limit)
+        .map(|item| {
+            item.map(|(token_id, tribute)| FullTributeData {
+                token_id,
+                owner: tribute.owner.to_string(),
+                data: tribute.extension,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(FullTributesResponse { tributes })
+}
 
+fn query_total_nominal_amount(
+    deps: Deps,
+    _env: &Env,
+    date: WorldwideDay,
+) -> StdResult<TotalInterestResponse> {
+    let total_nominal_amount = query_daily_stats(deps, date)?.total_nominal_amount_minor;
     Ok(TotalInterestResponse {
         total_nominal_amount,
     })
 }
 
+fn query_daily_stats(deps: Deps, date: WorldwideDay) -> StdResult<DailyStats> {
+    Ok(TributeCollection::config()
+        .nft_info
+        .idx
+        .extension
+        .daily_stats
+        .may_load(deps.storage, date)?
+        .unwrap_or_default())
+}
+
+fn query_daily_stats_range(
+    deps: Deps,
+    from: WorldwideDay,
+    to: WorldwideDay,
+) -> StdResult<DailyStatsRangeResponse> {
+    let days = TributeCollection::config()
+        .nft_info
+        .idx
+        .extension
+        .daily_stats
+        .range(deps.storage, from, to)?
+        .into_iter()
+        .map(|(date, stats)| DailyStatsEntry { date, stats })
+        .collect();
+    Ok(DailyStatsRangeResponse { days })
+}
+
 #[cfg(test)]
 mod tests {
     use crate::contract::{execute, instantiate};
@@ -231,10 +823,10 @@ mod tests {
         let init_msg = InstantiateMsg {
             name: "tribute".to_string(),
             symbol: "t".to_string(),
-            collection_info_extension: TributeCollectionExtension {
-                native_token: Denom::Native("native".to_string()),
-                price_oracle: Addr::unchecked("price_oracle"),
-            },
+            collection_info_extension: TributeCollectionExtension::new(
+                Denom::Native("native".to_string()),
+                Addr::unchecked("price_oracle"),
+            ),
             minter: None,
             burner: None,
             creator: None,
@@ -278,10 +870,10 @@ mod tests {
         let init_msg = InstantiateMsg {
             name: "tribute".to_string(),
             symbol: "t".to_string(),
-            collection_info_extension: TributeCollectionExtension {
-                native_token: Denom::Native("native".to_string()),
-                price_oracle: Addr::unchecked("price_oracle"),
-            },
+            collection_info_extension: TributeCollectionExtension::new(
+                Denom::Native("native".to_string()),
+                Addr::unchecked("price_oracle"),
+            ),
             minter: None,
             burner: None,
             creator: None,
@@ -301,6 +893,8 @@ mod tests {
             nominal_price: Decimal::one(),
             worldwide_day: date,
             owner: owner.to_string(),
+            vector_id: None,
+            amounts_commitment: None,
         };
 
         // Mint first tribute
@@ -328,6 +922,8 @@ mod tests {
             nominal_price: Decimal::one(),
             worldwide_day: date,
             owner: owner.to_string(),
+            vector_id: None,
+            amounts_commitment: None,
         };
         app.execute_contract(
             owner.clone(),
@@ -369,4 +965,498 @@ mod tests {
 
         assert_eq!(response.total_nominal_amount, Uint128::zero());
     }
+
+    #[test]
+    fn test_query_daily_stats() {
+        use crate::msg::ExecuteMsg;
+        use crate::query::DailyStatsRangeResponse;
+        use crate::stats::{DailyStats, SettlementTotal, VectorTotal};
+
+        let mut app = App::default();
+        let owner = app.api().addr_make("owner");
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let init_msg = InstantiateMsg {
+            name: "tribute".to_string(),
+            symbol: "t".to_string(),
+            collection_info_extension: TributeCollectionExtension::new(
+                Denom::Native("native".to_string()),
+                Addr::unchecked("price_oracle"),
+            ),
+            minter: None,
+            burner: None,
+            creator: None,
+        };
+
+        let contract_addr = app
+            .instantiate_contract(code_id, owner.clone(), &init_msg, &[], "t1", None)
+            .unwrap();
+
+        for (token_id, date, currency, amount, price) in [
+            ("a", 1, Currency::Usd, 100, 2),
+            ("b", 1, Currency::Eur, 30, 1),
+            ("c", 2, Currency::Usd, 10, 1),
+        ] {
+            app.execute_contract(
+                owner.clone(),
+                contract_addr.clone(),
+                &ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: owner.to_string(),
+                    token_uri: None,
+                    extension: Box::new(MintExtension {
+                        data: TributeMintData {
+                            tribute_id: token_id.to_string(),
+                            settlement_amount_minor: Uint128::new(amount),
+                            settlement_currency: Denom::Fiat(currency),
+                            nominal_amount_minor: Uint128::new(amount),
+                            nominal_price: Decimal::from_atomics(price as u128, 0).unwrap(),
+                            worldwide_day: date,
+                            owner: owner.to_string(),
+                            vector_id: None,
+                            amounts_commitment: None,
+                        },
+                    }),
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        let daily_stats = |app: &App, date| -> DailyStats {
+            app.wrap()
+                .query_wasm_smart(contract_addr.clone(), &QueryMsg::DailyStats { date })
+                .unwrap()
+        };
+        assert_eq!(
+            daily_stats(&app, 1),
+            DailyStats {
+                tributes_count: 2,
+                total_nominal_amount_minor: Uint128::new(80),
+                settlement_totals: vec![
+                    SettlementTotal {
+                        currency: Denom::Fiat(Currency::Usd),
+                        amount_minor: Uint128::new(100),
+                    },
+                    SettlementTotal {
+                        currency: Denom::Fiat(Currency::Eur),
+                        amount_minor: Uint128::new(30),
+                    },
+                ],
+                min_nominal_price: Some(Decimal::one()),
+                max_nominal_price: Some(Decimal::from_atomics(2u128, 0).unwrap()),
+                vector_totals: vec![VectorTotal {
+                    vector_id: None,
+                    tributes_count: 2,
+                    nominal_amount_minor: Uint128::new(80),
+                }],
+                normalized_settlement_totals: vec![],
+            }
+        );
+
+        // offered tributes are burned once their day is concluded
+        app.execute_contract(
+            owner.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::ConcludeDay {
+                date: 1,
+                limit: None,
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            owner.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::Burn {
+                token_id: "a".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let stats = daily_stats(&app, 1);
+        assert_eq!(stats.tributes_count, 1);
+        assert_eq!(stats.total_nominal_amount_minor, Uint128::new(30));
+        assert_eq!(
+            stats.settlement_totals,
+            vec![SettlementTotal {
+                currency: Denom::Fiat(Currency::Eur),
+                amount_minor: Uint128::new(30),
+            }]
+        );
+        let response: TotalInterestResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::TotalInterest { date: 1 })
+            .unwrap();
+        assert_eq!(response.total_nominal_amount, Uint128::new(30));
+
+        app.execute_contract(
+            owner.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::BurnForDay {
+                date: 1,
+                limit: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        // emptied days are left out of the range
+        assert_eq!(daily_stats(&app, 1), DailyStats::default());
+        let response: DailyStatsRangeResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::DailyStatsRange { from: 1, to: 3 },
+            )
+            .unwrap();
+        assert_eq!(response.days.len(), 1);
+        assert_eq!(response.days[0].date, 2);
+        assert_eq!(response.days[0].stats.tributes_count, 1);
+    }
+
+    #[test]
+    fn test_query_daily_tributes_by_date() {
+        use crate::msg::ExecuteMsg;
+        use crate::query::FullTributesResponse;
+
+        let mut app = App::default();
+        let owner = app.api().addr_make("owner");
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let init_msg = InstantiateMsg {
+            name: "tribute".to_string(),
+            symbol: "t".to_string(),
+            collection_info_extension: TributeCollectionExtension::new(
+                Denom::Native("native".to_string()),
+                Addr::unchecked("price_oracle"),
+            ),
+            minter: None,
+            burner: None,
+            creator: None,
+        };
+
+        let contract_addr = app
+            .instantiate_contract(code_id, owner.clone(), &init_msg, &[], "t1", None)
+            .unwrap();
+
+        for (token_id, date) in [("a", 1), ("b", 2), ("c", 1)] {
+            app.execute_contract(
+                owner.clone(),
+                contract_addr.clone(),
+                &ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: owner.to_string(),
+                    token_uri: None,
+                    extension: Box::new(MintExtension {
+                        data: TributeMintData {
+                            tribute_id: token_id.to_string(),
+                            settlement_amount_minor: Uint128::new(1),
+                            settlement_currency: Denom::Fiat(Currency::Usd),
+                            nominal_amount_minor: Uint128::new(1),
+                            nominal_price: Decimal::one(),
+                            worldwide_day: date,
+                            owner: owner.to_string(),
+                            vector_id: None,
+                            amounts_commitment: None,
+                        },
+                    }),
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        let query_ids = |date, start_after: Option<&str>, limit| -> Vec<String> {
+            let response: FullTributesResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::DailyTributes {
+                        date,
+                        start_after: start_after.map(|s| s.to_string()),
+                        limit,
+                        query_order: None,
+                    },
+                )
+                .unwrap();
+            response.tributes.into_iter().map(|t| t.token_id).collect()
+        };
+
+        // the limit is applied after the date filter
+        assert_eq!(query_ids(Some(1), None, Some(1)), vec!["a"]);
+        assert_eq!(query_ids(Some(1), Some("a"), Some(1)), vec!["c"]);
+        assert_eq!(query_ids(Some(1), None, None), vec!["a", "c"]);
+        assert_eq!(query_ids(Some(2), None, None), vec!["b"]);
+        assert_eq!(query_ids(None, None, None), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_query_tributes_pages() {
+        use crate::msg::ExecuteMsg;
+        use crate::query::{TributeCursor, TributesPageResponse, TributesRangeResponse};
+        use crate::stats::{SettlementTotal, TributesSummary};
+
+        let mut app = App::default();
+        let owner = app.api().addr_make("owner");
+        let other = app.api().addr_make("other");
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let init_msg = InstantiateMsg {
+            name: "tribute".to_string(),
+            symbol: "t".to_string(),
+            collection_info_extension: TributeCollectionExtension::new(
+                Denom::Native("native".to_string()),
+                Addr::unchecked("price_oracle"),
+            ),
+            minter: None,
+            burner: None,
+            creator: None,
+        };
+        let contract_addr = app
+            .instantiate_contract(code_id, owner.clone(), &init_msg, &[], "t1", None)
+            .unwrap();
+
+        for (token_id, date, tribute_owner) in [
+            ("a", 1, &owner),
+            ("b", 2, &owner),
+            ("c", 1, &other),
+            ("d", 1, &owner),
+            ("e", 3, &other),
+            ("f", 4, &owner),
+        ] {
+            app.execute_contract(
+                owner.clone(),
+                contract_addr.clone(),
+                &ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: tribute_owner.to_string(),
+                    token_uri: None,
+                    extension: Box::new(MintExtension {
+                        data: TributeMintData {
+                            tribute_id: token_id.to_string(),
+                            settlement_amount_minor: Uint128::new(10),
+                            settlement_currency: Denom::Fiat(Currency::Usd),
+                            nominal_amount_minor: Uint128::new(1),
+                            nominal_price: Decimal::one(),
+                            worldwide_day: date,
+                            owner: tribute_owner.to_string(),
+                            vector_id: None,
+                            amounts_commitment: None,
+                        },
+                    }),
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        let ids = |tributes: Vec<crate::query::FullTributeData>| -> Vec<String> {
+            tributes.into_iter().map(|t| t.token_id).collect()
+        };
+        let by_day = |start_after: Option<&str>| -> TributesPageResponse {
+            app.wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::TributesByDay {
+                        date: 1,
+                        start_after: start_after.map(|s| s.to_string()),
+                        limit: Some(2),
+                    },
+                )
+                .unwrap()
+        };
+        let page = by_day(None);
+        assert_eq!(page.next_start_after, Some("c".to_string()));
+        assert_eq!(ids(page.tributes), vec!["a", "c"]);
+        let page = by_day(Some("c"));
+        assert_eq!(page.next_start_after, None);
+        assert_eq!(ids(page.tributes), vec!["d"]);
+
+        let page: TributesPageResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::TributesByOwnerAndDay {
+                    owner: owner.to_string(),
+                    date: 1,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(page.next_start_after, None);
+        assert_eq!(ids(page.tributes), vec!["a", "d"]);
+
+        let in_range = |start_after: Option<TributeCursor>| -> TributesRangeResponse {
+            app.wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::TributesInRange {
+                        from_day: 1,
+                        to_day: 3,
+                        start_after,
+                        limit: Some(3),
+                    },
+                )
+                .unwrap()
+        };
+        let page = in_range(None);
+        assert_eq!(ids(page.tributes), vec!["a", "c", "d"]);
+        let next = page.next_start_after.unwrap();
+        assert_eq!(
+            next,
+            TributeCursor {
+                worldwide_day: 1,
+                token_id: "d".to_string(),
+            }
+        );
+        let page = in_range(Some(next));
+        assert_eq!(page.next_start_after, None);
+        assert_eq!(ids(page.tributes), vec!["b", "e"]);
+
+        let summary = |msg: &QueryMsg| -> TributesSummary {
+            app.wrap()
+                .query_wasm_smart(contract_addr.clone(), msg)
+                .unwrap()
+        };
+        let totals = |count: u64| TributesSummary {
+            tributes_count: count,
+            // the nominal amount is recomputed from the settlement amount and price
+            total_nominal_amount_minor: Uint128::new(10 * count as u128),
+            settlement_totals: vec![SettlementTotal {
+                currency: Denom::Fiat(Currency::Usd),
+                amount_minor: Uint128::new(10 * count as u128),
+            }],
+            normalized_settlement_totals: vec![],
+        };
+        assert_eq!(
+            summary(&QueryMsg::TributesByDaySummary { date: 1 }),
+            totals(3)
+        );
+        assert_eq!(
+            summary(&QueryMsg::TributesByOwnerAndDaySummary {
+                owner: owner.to_string(),
+                date: 1,
+            }),
+            totals(2)
+        );
+        assert_eq!(
+            summary(&QueryMsg::TributesInRangeSummary {
+                from_day: 1,
+                to_day: 3,
+            }),
+            totals(5)
+        );
+    }
+
+    #[test]
+    fn test_query_cw721_spec() {
+        use crate::msg::ExecuteMsg;
+        use cosmwasm_std::{Binary, QueryRequest, WasmQuery};
+        use outbe_nft::msg::{
+            AllNftInfoResponse, ApprovalsResponse, ContractInfoResponse, OwnerOfResponse,
+        };
+
+        let mut app = App::default();
+        let owner = app.api().addr_make("owner");
+        let spender = app.api().addr_make("spender");
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let init_msg = InstantiateMsg {
+            name: "tribute".to_string(),
+            symbol: "t".to_string(),
+            collection_info_extension: TributeCollectionExtension::new(
+                Denom::Native("native".to_string()),
+                Addr::unchecked("price_oracle"),
+            ),
+            minter: None,
+            burner: None,
+            creator: None,
+        };
+        let contract_addr = app
+            .instantiate_contract(code_id, owner.clone(), &init_msg, &[], "t1", None)
+            .unwrap();
+
+        app.execute_contract(
+            owner.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {
+                token_id: "1".to_string(),
+                owner: owner.to_string(),
+                token_uri: Some("ipfs://tribute".to_string()),
+                extension: Box::new(MintExtension {
+                    data: TributeMintData {
+                        tribute_id: "1".to_string(),
+                        settlement_amount_minor: Uint128::new(1),
+                        settlement_currency: Denom::Fiat(Currency::Usd),
+                        nominal_amount_minor: Uint128::new(1),
+                        nominal_price: Decimal::one(),
+                        worldwide_day: 1,
+                        owner: owner.to_string(),
+                        vector_id: None,
+                        amounts_commitment: None,
+                    },
+                }),
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            owner.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::Approve {
+                spender: spender.to_string(),
+                token_id: "1".to_string(),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        // queries are sent as plain CW721 json, the way standard tooling does it
+        let raw_query = |msg: String| -> Binary {
+            let request: QueryRequest = QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: contract_addr.to_string(),
+                msg: Binary::from(msg.into_bytes()),
+            });
+            app.wrap()
+                .raw_query(&cosmwasm_std::to_json_vec(&request).unwrap())
+                .unwrap()
+                .unwrap()
+        };
+
+        let response: ContractInfoResponse =
+            cosmwasm_std::from_json(raw_query(r#"{"contract_info":{}}"#.to_string())).unwrap();
+        assert_eq!(response.name, "tribute");
+        assert_eq!(response.symbol, "t");
+
+        let response: OwnerOfResponse =
+            cosmwasm_std::from_json(raw_query(r#"{"owner_of":{"token_id":"1"}}"#.to_string()))
+                .unwrap();
+        assert_eq!(response.owner, owner.to_string());
+        assert_eq!(response.approvals[0].spender, spender);
+
+        let response: ApprovalsResponse =
+            cosmwasm_std::from_json(raw_query(r#"{"approvals":{"token_id":"1"}}"#.to_string()))
+                .unwrap();
+        assert_eq!(response.approvals.len(), 1);
+
+        let response: AllNftInfoResponse<crate::types::TributeData> = cosmwasm_std::from_json(
+            raw_query(r#"{"all_nft_info":{"token_id":"1"}}"#.to_string()),
+        )
+        .unwrap();
+        assert_eq!(response.access.owner, owner.to_string());
+        assert_eq!(response.info.token_uri, Some("ipfs://tribute".to_string()));
+        assert_eq!(response.info.extension.worldwide_day, 1);
+    }
 }
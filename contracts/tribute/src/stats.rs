@@ -0,0 +1,391 @@
+use crate::types::{TributeData, TributeDataV1};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Decimal, Empty, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Bound, Index, Item, Map};
+use outbe_nft::state::{Cw721Config, NftInfo};
+use outbe_nft::traits::{Cw721ExtensionIndexes, Cw721State};
+use outbe_utils::date::WorldwideDay;
+use outbe_utils::denom::Denom;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Set once `DailyStats` account for every stored Tribute, by instantiate or a finished rebuild
+pub const STATS_COMPLETE: Item<bool> = Item::new("daily_stats_complete");
+/// Last Tribute counted by an unfinished `DailyStatsIndex::rebuild`
+const REBUILD_CURSOR: Item<String> = Item::new("daily_stats_rebuild_cursor");
+
+/// Result of a `DailyStatsIndex::rebuild` call
+#[cw_serde]
+pub struct StatsRebuildProgress {
+    pub rebuilt: u64,
+    /// False if Tributes are left and the rebuild must be called again
+    pub finished: bool,
+}
+
+/// Running aggregates of the Tributes of one worldwide day
+#[cw_serde]
+#[derive(Default)]
+pub struct DailyStats {
+    pub tributes_count: u64,
+    pub total_nominal_amount_minor: Uint128,
+    /// Sum of `settlement_amount_minor` per settlement currency
+    pub settlement_totals: Vec<SettlementTotal>,
+    /// Sum of the settlement amounts normalised to the reference currency, comparable across
+    /// settlement currencies. Tributes minted without a reference currency are not included.
+    #[serde(default)]
+    pub normalized_settlement_totals: Vec<SettlementTotal>,
+    /// Lowest `nominal_price` ever minted on the day. Burns do not narrow it, so no remaining
+    /// Tribute may have it; it is reset once all Tributes of the day are burned.
+    pub min_nominal_price: Option<Decimal>,
+    /// Highest `nominal_price` ever minted on the day, see `min_nominal_price`
+    pub max_nominal_price: Option<Decimal>,
+    /// Tributes per vector, ordered by vector id with the Tributes without a vector first
+    pub vector_totals: Vec<VectorTotal>,
+}
+
+#[cw_serde]
+pub struct SettlementTotal {
+    pub currency: Denom,
+    pub amount_minor: Uint128,
+}
+
+#[cw_serde]
+pub struct VectorTotal {
+    pub vector_id: Option<u16>,
+    pub tributes_count: u64,
+    pub nominal_amount_minor: Uint128,
+}
+
+/// Values of a Tribute summed up in `DailyStats`
+pub struct TributeAmounts<'a> {
+    pub worldwide_day: WorldwideDay,
+    pub settlement_currency: &'a Denom,
+    pub settlement_amount_minor: Uint128,
+    pub nominal_amount_minor: Uint128,
+    pub nominal_price: Decimal,
+    pub vector_id: Option<u16>,
+    pub normalized_settlement: Option<(Denom, Uint128)>,
+}
+
+/// Token data counted in `DailyStats`, implemented by every stored layout of Tributes
+pub trait CountedInStats {
+    fn amounts(&self) -> TributeAmounts<'_>;
+}
+
+impl CountedInStats for TributeData {
+    fn amounts(&self) -> TributeAmounts<'_> {
+        TributeAmounts {
+            worldwide_day: self.worldwide_day,
+            settlement_currency: &self.settlement_currency,
+            settlement_amount_minor: self.settlement_amount_minor,
+            nominal_amount_minor: self.nominal_amount_minor,
+            nominal_price: self.nominal_price,
+            vector_id: self.vector_id,
+            normalized_settlement: self
+                .normalized_settlement
+                .as_ref()
+                .map(|normalized| (Denom::Fiat(normalized.currency), normalized.amount_minor)),
+        }
+    }
+}
+
+impl CountedInStats for TributeDataV1 {
+    fn amounts(&self) -> TributeAmounts<'_> {
+        TributeAmounts {
+            worldwide_day: self.worldwide_day,
+            settlement_currency: &self.settlement_currency,
+            settlement_amount_minor: self.settlement_amount_minor,
+            nominal_amount_minor: self.nominal_amount_minor,
+            nominal_price: self.nominal_price,
+            vector_id: None,
+            normalized_settlement: None,
+        }
+    }
+}
+
+impl DailyStats {
+    fn add(&mut self, data: &TributeAmounts) -> StdResult<()> {
+        self.tributes_count += 1;
+        self.total_nominal_amount_minor = self
+            .total_nominal_amount_minor
+            .checked_add(data.nominal_amount_minor)?;
+        add_to_totals(
+            &mut self.settlement_totals,
+            data.settlement_currency,
+            data.settlement_amount_minor,
+        )?;
+        if let Some((currency, amount_minor)) = &data.normalized_settlement {
+            add_to_totals(
+                &mut self.normalized_settlement_totals,
+                currency,
+                *amount_minor,
+            )?;
+        }
+        self.min_nominal_price = Some(
+            self.min_nominal_price
+                .map_or(data.nominal_price, |min| min.min(data.nominal_price)),
+        );
+        self.max_nominal_price = Some(
+            self.max_nominal_price
+                .map_or(data.nominal_price, |max| max.max(data.nominal_price)),
+        );
+        match self
+            .vector_totals
+            .binary_search_by_key(&data.vector_id, |total| total.vector_id)
+        {
+            Ok(position) => {
+                let total = &mut self.vector_totals[position];
+                total.tributes_count += 1;
+                total.nominal_amount_minor = total
+                    .nominal_amount_minor
+                    .checked_add(data.nominal_amount_minor)?;
+            }
+            Err(position) => self.vector_totals.insert(
+                position,
+                VectorTotal {
+                    vector_id: data.vector_id,
+                    tributes_count: 1,
+                    nominal_amount_minor: data.nominal_amount_minor,
+                },
+            ),
+        }
+        Ok(())
+    }
+
+    fn subtract(&mut self, data: &TributeAmounts) {
+        self.tributes_count = self.tributes_count.saturating_sub(1);
+        self.total_nominal_amount_minor = self
+            .total_nominal_amount_minor
+            .saturating_sub(data.nominal_amount_minor);
+        subtract_from_totals(
+            &mut self.settlement_totals,
+            data.settlement_currency,
+            data.settlement_amount_minor,
+        );
+        if let Some((currency, amount_minor)) = &data.normalized_settlement {
+            subtract_from_totals(
+                &mut self.normalized_settlement_totals,
+                currency,
+                *amount_minor,
+            );
+        }
+        if let Ok(position) = self
+            .vector_totals
+            .binary_search_by_key(&data.vector_id, |total| total.vector_id)
+        {
+            let total = &mut self.vector_totals[position];
+            total.tributes_count = total.tributes_count.saturating_sub(1);
+            total.nominal_amount_minor = total
+                .nominal_amount_minor
+                .saturating_sub(data.nominal_amount_minor);
+            if total.tributes_count == 0 {
+                self.vector_totals.remove(position);
+            }
+        }
+    }
+}
+
+/// Totals of a set of Tributes, see the summary queries
+#[cw_serde]
+#[derive(Default)]
+pub struct TributesSummary {
+    pub tributes_count: u64,
+    pub total_nominal_amount_minor: Uint128,
+    pub settlement_totals: Vec<SettlementTotal>,
+    pub normalized_settlement_totals: Vec<SettlementTotal>,
+}
+
+impl TributesSummary {
+    pub fn add_stats(&mut self, stats: &DailyStats) -> StdResult<()> {
+        self.tributes_count += stats.tributes_count;
+        self.total_nominal_amount_minor = self
+            .total_nominal_amount_minor
+            .checked_add(stats.total_nominal_amount_minor)?;
+        for total in stats.settlement_totals.iter() {
+            add_to_totals(
+                &mut self.settlement_totals,
+                &total.currency,
+                total.amount_minor,
+            )?;
+        }
+        for total in stats.normalized_settlement_totals.iter() {
+            add_to_totals(
+                &mut self.normalized_settlement_totals,
+                &total.currency,
+                total.amount_minor,
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn add_tribute(&mut self, tribute: &impl CountedInStats) -> StdResult<()> {
+        let data = tribute.amounts();
+        self.tributes_count += 1;
+        self.total_nominal_amount_minor = self
+            .total_nominal_amount_minor
+            .checked_add(data.nominal_amount_minor)?;
+        add_to_totals(
+            &mut self.settlement_totals,
+            data.settlement_currency,
+            data.settlement_amount_minor,
+        )?;
+        if let Some((currency, amount_minor)) = &data.normalized_settlement {
+            add_to_totals(
+                &mut self.normalized_settlement_totals,
+                currency,
+                *amount_minor,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn add_to_totals(
+    totals: &mut Vec<SettlementTotal>,
+    currency: &Denom,
+    amount_minor: Uint128,
+) -> StdResult<()> {
+    match totals.iter_mut().find(|total| &total.currency == currency) {
+        Some(total) => total.amount_minor = total.amount_minor.checked_add(amount_minor)?,
+        None => totals.push(SettlementTotal {
+            currency: currency.clone(),
+            amount_minor,
+        }),
+    }
+    Ok(())
+}
+
+fn subtract_from_totals(
+    totals: &mut Vec<SettlementTotal>,
+    currency: &Denom,
+    amount_minor: Uint128,
+) {
+    if let Some(total) = totals.iter_mut().find(|total| &total.currency == currency) {
+        total.amount_minor = total.amount_minor.saturating_sub(amount_minor);
+    }
+    totals.retain(|total| !total.amount_minor.is_zero());
+}
+
+/// Keeps `DailyStats` in line with the `nft_info` map. Being an index, it is updated on every
+/// write of a Tribute, so mint, burn and the bulk deletions are all accounted for.
+pub struct DailyStatsIndex {
+    stats: Map<WorldwideDay, DailyStats>,
+}
+
+impl DailyStatsIndex {
+    pub const fn new(namespace: &'static str) -> Self {
+        Self {
+            stats: Map::new(namespace),
+        }
+    }
+
+    pub fn may_load(
+        &self,
+        storage: &dyn Storage,
+        date: WorldwideDay,
+    ) -> StdResult<Option<DailyStats>> {
+        self.stats.may_load(storage, date)
+    }
+
+    /// Stats of the days from `from` to `to` inclusive that have Tributes, in ascending order
+    pub fn range(
+        &self,
+        storage: &dyn Storage,
+        from: WorldwideDay,
+        to: WorldwideDay,
+    ) -> StdResult<Vec<(WorldwideDay, DailyStats)>> {
+        self.stats
+            .range(
+                storage,
+                Some(Bound::inclusive(from)),
+                Some(Bound::inclusive(to)),
+                Order::Ascending,
+            )
+            .collect()
+    }
+
+    /// Marks the stats of a new collection as complete, no rebuild is needed
+    pub fn initialize(&self, storage: &mut dyn Storage) -> StdResult<()> {
+        STATS_COMPLETE.save(storage, &true)
+    }
+
+    /// True between the first and the last call of an unfinished `rebuild`. Tributes written
+    /// meanwhile would be counted twice or not at all, so they must not change.
+    pub fn is_rebuilding(&self, storage: &dyn Storage) -> bool {
+        REBUILD_CURSOR.exists(storage)
+    }
+
+    /// Recomputes the stats of all days from the Tributes stored in the `T` layout, counting at
+    /// most `limit` Tributes per call and continuing after the last one counted by the previous
+    /// call. Stats already complete are left untouched, so the rebuild only runs once.
+    pub fn rebuild<T, I>(
+        &self,
+        storage: &mut dyn Storage,
+        limit: usize,
+    ) -> StdResult<StatsRebuildProgress>
+    where
+        T: CountedInStats + Cw721State,
+        I: Cw721ExtensionIndexes<T>,
+    {
+        if STATS_COMPLETE.may_load(storage)?.unwrap_or_default() {
+            return Ok(StatsRebuildProgress {
+                rebuilt: 0,
+                finished: true,
+            });
+        }
+        let cursor = REBUILD_CURSOR.may_load(storage)?;
+        if cursor.is_none() {
+            self.stats.clear(storage);
+        }
+        let tributes = Cw721Config::<T, Option<Empty>, I>::default()
+            .nft_info
+            .range(
+                storage,
+                cursor.as_deref().map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit + 1)
+            .collect::<StdResult<Vec<(String, NftInfo<T>)>>>()?;
+        let finished = tributes.len() <= limit;
+
+        let mut rebuilt = 0u64;
+        for (token_id, tribute) in tributes.iter().take(limit) {
+            self.save(storage, &[], tribute)?;
+            REBUILD_CURSOR.save(storage, token_id)?;
+            rebuilt += 1;
+        }
+        if finished {
+            REBUILD_CURSOR.remove(storage);
+            STATS_COMPLETE.save(storage, &true)?;
+        }
+        Ok(StatsRebuildProgress { rebuilt, finished })
+    }
+}
+
+impl<T> Index<NftInfo<T>> for DailyStatsIndex
+where
+    T: CountedInStats + Serialize + DeserializeOwned + Clone,
+{
+    fn save(&self, store: &mut dyn Storage, _pk: &[u8], data: &NftInfo<T>) -> StdResult<()> {
+        let amounts = data.extension.amounts();
+        let date = amounts.worldwide_day;
+        let mut stats = self.stats.may_load(store, date)?.unwrap_or_default();
+        stats.add(&amounts)?;
+        self.stats.save(store, date, &stats)
+    }
+
+    fn remove(&self, store: &mut dyn Storage, _pk: &[u8], old_data: &NftInfo<T>) -> StdResult<()> {
+        let amounts = old_data.extension.amounts();
+        let date = amounts.worldwide_day;
+        let Some(mut stats) = self.stats.may_load(store, date)? else {
+            return Ok(());
+        };
+        stats.subtract(&amounts);
+        if stats.tributes_count == 0 {
+            self.stats.remove(store, date);
+            return Ok(());
+        }
+        self.stats.save(store, date, &stats)
+    }
+}
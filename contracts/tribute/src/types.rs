@@ -1,17 +1,74 @@
+use crate::stats::DailyStatsIndex;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
-use outbe_nft::state::NftInfo;
-use outbe_nft::traits::Cw721CollectionConfig;
+use cosmwasm_std::{Addr, Decimal, HexBinary, Timestamp, Uint128};
+use cw_storage_plus::{Index, IndexList, MultiIndex};
+use outbe_nft::collection::Cw721Collection;
+use outbe_nft::metadata::{NftAttribute, NftMetadata};
+use outbe_nft::migration::{MigrateFrom, VersionedExtension};
+use outbe_nft::state::{CollectionInfo, NftInfo, TransferPolicy};
+use outbe_nft::traits::{Cw721CollectionConfig, Cw721ExtensionIndexes};
 use outbe_utils::date::WorldwideDay;
-use outbe_utils::denom::Denom;
+use outbe_utils::denom::{Currency, Denom};
+use std::fmt::{Display, Formatter};
 
 #[cw_serde]
 pub struct TributeConfig {
     pub native_token: Denom,
     pub price_oracle: Addr,
+    /// Tributes are soulbound unless configured otherwise
+    #[serde(default)]
+    pub transfer_policy: TransferPolicy,
+    /// Keeps the history of every Tribute, see `QueryMsg::TokenHistory`
+    #[serde(default)]
+    pub track_history: bool,
+    /// Renders the metadata as `token_uri` of Tributes minted without one
+    #[serde(default)]
+    pub render_token_uri: bool,
+    /// Bounds for the `nominal_price` of minted Tributes, see `PriceCheck`
+    #[serde(default)]
+    pub price_check: PriceCheck,
+    /// Days archived Tributes are kept after their worldwide day, kept forever if not set
+    #[serde(default)]
+    pub retention_days: Option<u32>,
+    /// Vector contract the `vector_id` of Tributes is checked against
+    #[serde(default)]
+    pub vector: Option<Addr>,
+    /// Currency settlement amounts are normalised to with the fiat cross rates of `price_oracle`,
+    /// not normalised if not set
+    #[serde(default)]
+    pub reference_currency: Option<Currency>,
 }
 
-impl Cw721CollectionConfig for TributeConfig {}
+/// Reference price a minted Tribute's `nominal_price` is checked against.
+/// The price of the native coin in the settlement currency is queried from `price_oracle`
+/// and `tolerance` widens the accepted band on both sides, e.g. `0.05` accepts 5% off.
+#[cw_serde]
+#[derive(Default)]
+pub enum PriceCheck {
+    /// Any `nominal_price` is accepted
+    #[default]
+    Disabled,
+    /// Around the latest price
+    Latest { tolerance: Decimal },
+    /// Around the VWAP over the oracle window
+    Vwap { tolerance: Decimal },
+    /// Within the low and high of the latest OHLC record of the day
+    DayRange { tolerance: Decimal },
+}
+
+impl Cw721CollectionConfig for TributeConfig {
+    fn transfer_policy(&self) -> TransferPolicy {
+        self.transfer_policy.clone()
+    }
+
+    fn track_history(&self) -> bool {
+        self.track_history
+    }
+
+    fn render_token_uri(&self) -> bool {
+        self.render_token_uri
+    }
+}
 
 /// ConsumptionUnit public data
 #[cw_serde]
@@ -20,6 +77,9 @@ pub struct TributeData {
     pub settlement_amount_minor: Uint128,
     /// Tribute settlement token
     pub settlement_currency: Denom,
+    /// Settlement amount in the reference currency of the collection at the time of the mint
+    #[serde(default)]
+    pub normalized_settlement: Option<NormalizedSettlement>,
     /// Value of the Tribute in Native Coins
     pub nominal_amount_minor: Uint128,
     /// Price in Native coins with a rate on the moment of the transaction
@@ -28,9 +88,245 @@ pub struct TributeData {
     pub worldwide_day: WorldwideDay,
     /// Time when the Tribute NFT was created on the network
     pub created_at: Timestamp,
+    /// Vector joined by the owner, it sets the Tribute Price Floor (ADR-0301)
+    #[serde(default)]
+    pub vector_id: Option<u16>,
+    /// Commitment to the exact amounts when the published ones are rounded by the factory
+    #[serde(default)]
+    pub amounts_commitment: Option<HexBinary>,
+    /// Outcome of the Tribute in Metadosis
+    pub state: TributeState,
 }
 
 pub type TributeNft = NftInfo<TributeData>;
 
-impl outbe_nft::traits::Cw721State for TributeData {}
+#[cw_serde]
+pub struct NormalizedSettlement {
+    pub currency: Currency,
+    /// Settlement amount converted to `currency`, rounded down to its minor units
+    pub amount_minor: Uint128,
+    /// Price of the settlement currency in `currency` used for the conversion
+    pub rate: Decimal,
+}
+
+/// Lifecycle of a Tribute: offered until the Metadosis run of its day, then recognized or not,
+/// and finally archived until removed by the retention job
+#[cw_serde]
+#[derive(Default)]
+pub enum TributeState {
+    #[default]
+    Offered,
+    Recognized {
+        nod_id: String,
+    },
+    Unrecognized,
+    Archived,
+}
+
+impl TributeState {
+    pub fn status(&self) -> TributeStatus {
+        match self {
+            TributeState::Offered => TributeStatus::Offered,
+            TributeState::Recognized { .. } => TributeStatus::Recognized,
+            TributeState::Unrecognized => TributeStatus::Unrecognized,
+            TributeState::Archived => TributeStatus::Archived,
+        }
+    }
+}
+
+/// `TributeState` without its data, used to look Tributes up by state
+#[cw_serde]
+#[derive(Copy, Eq)]
+pub enum TributeStatus {
+    Offered,
+    Recognized,
+    Unrecognized,
+    Archived,
+}
+
+impl Display for TributeStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TributeStatus::Offered => write!(f, "offered"),
+            TributeStatus::Recognized => write!(f, "recognized"),
+            TributeStatus::Unrecognized => write!(f, "unrecognized"),
+            TributeStatus::Archived => write!(f, "archived"),
+        }
+    }
+}
+
+impl outbe_nft::traits::Cw721State for TributeData {
+    fn metadata(&self, collection: &CollectionInfo, token_id: &str) -> NftMetadata {
+        let mut attributes = vec![
+            NftAttribute::number("worldwide_day", self.worldwide_day),
+            NftAttribute::new("settlement_currency", &self.settlement_currency),
+            NftAttribute::number("settlement_amount_minor", self.settlement_amount_minor),
+            NftAttribute::number("nominal_amount_minor", self.nominal_amount_minor),
+            NftAttribute::number("nominal_price", self.nominal_price),
+            NftAttribute::new("state", self.state.status()),
+        ];
+        if let Some(normalized) = &self.normalized_settlement {
+            attributes.push(NftAttribute::new("reference_currency", normalized.currency));
+            attributes.push(NftAttribute::number(
+                "reference_amount_minor",
+                normalized.amount_minor,
+            ));
+        }
+        if let Some(vector_id) = self.vector_id {
+            attributes.push(NftAttribute::number("vector_id", vector_id));
+        }
+        NftMetadata {
+            description: format!(
+                "Tribute of {} {} on day {}",
+                self.settlement_amount_minor, self.settlement_currency, self.worldwide_day
+            ),
+            attributes,
+            ..NftMetadata::base(collection, token_id)
+        }
+    }
+}
 impl outbe_nft::traits::Cw721CustomMsg for TributeData {}
+
+impl VersionedExtension for TributeData {
+    const VERSION: u16 = 2;
+}
+
+impl MigrateFrom<TributeDataV1> for TributeData {
+    fn migrate_from(previous: TributeDataV1) -> Self {
+        Self {
+            settlement_amount_minor: previous.settlement_amount_minor,
+            settlement_currency: previous.settlement_currency,
+            normalized_settlement: None,
+            nominal_amount_minor: previous.nominal_amount_minor,
+            nominal_price: previous.nominal_price,
+            worldwide_day: previous.worldwide_day,
+            created_at: previous.created_at,
+            vector_id: None,
+            amounts_commitment: None,
+            state: TributeState::Offered,
+        }
+    }
+}
+
+/// Tribute specific indexes over the `nft_info` map
+pub struct TributeIndexes<'a> {
+    pub worldwide_day: MultiIndex<'a, WorldwideDay, TributeNft, String>,
+    /// Tributes by status and worldwide day
+    pub state: MultiIndex<'a, (String, WorldwideDay), TributeNft, String>,
+    /// Tributes by worldwide day and vector, `NO_VECTOR` for the ones without a vector
+    pub vector: MultiIndex<'a, (WorldwideDay, u16), TributeNft, String>,
+    /// Tributes by owner and worldwide day
+    pub owner_day: MultiIndex<'a, (Addr, WorldwideDay), TributeNft, String>,
+    pub daily_stats: DailyStatsIndex,
+}
+
+/// Key of Tributes without a vector in `TributeIndexes::vector`, vector ids start from 1
+pub const NO_VECTOR: u16 = 0;
+
+impl Cw721ExtensionIndexes<TributeData> for TributeIndexes<'_> {
+    fn new(pk_namespace: &'static str) -> Self {
+        Self {
+            worldwide_day: MultiIndex::new(
+                |_pk, d: &TributeNft| d.extension.worldwide_day,
+                pk_namespace,
+                "tokens__worldwide_day",
+            ),
+            state: MultiIndex::new(
+                |_pk, d: &TributeNft| {
+                    (
+                        d.extension.state.status().to_string(),
+                        d.extension.worldwide_day,
+                    )
+                },
+                pk_namespace,
+                "tokens__state",
+            ),
+            vector: MultiIndex::new(
+                |_pk, d: &TributeNft| {
+                    (
+                        d.extension.worldwide_day,
+                        d.extension.vector_id.unwrap_or(NO_VECTOR),
+                    )
+                },
+                pk_namespace,
+                "tokens__vector",
+            ),
+            owner_day: MultiIndex::new(
+                |_pk, d: &TributeNft| (d.owner.clone(), d.extension.worldwide_day),
+                pk_namespace,
+                "tokens__owner_day",
+            ),
+            daily_stats: DailyStatsIndex::new("tokens__daily_stats"),
+        }
+    }
+}
+
+impl IndexList<TributeNft> for TributeIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<TributeNft>> + '_> {
+        let v: Vec<&dyn Index<TributeNft>> = vec![
+            &self.worldwide_day,
+            &self.state,
+            &self.vector,
+            &self.owner_day,
+            &self.daily_stats,
+        ];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Tribute collection, the standard CW721 messages are implemented by `Cw721Collection`
+pub struct TributeCollection;
+
+impl Cw721Collection for TributeCollection {
+    type NftExtension = TributeData;
+    type CollectionConfig = TributeConfig;
+    type ExtensionIndexes = TributeIndexes<'static>;
+
+    const NAME: &'static str = "tribute";
+}
+
+/// Layout of `TributeData` before Tributes had a state
+#[cw_serde]
+pub struct TributeDataV1 {
+    pub settlement_amount_minor: Uint128,
+    pub settlement_currency: Denom,
+    pub nominal_amount_minor: Uint128,
+    pub nominal_price: Decimal,
+    pub worldwide_day: WorldwideDay,
+    pub created_at: Timestamp,
+}
+
+impl outbe_nft::traits::Cw721State for TributeDataV1 {}
+
+impl VersionedExtension for TributeDataV1 {
+    const VERSION: u16 = 1;
+}
+
+/// Indexes of the `TributeDataV1` layout
+pub struct TributeIndexesV1<'a> {
+    pub worldwide_day: MultiIndex<'a, WorldwideDay, NftInfo<TributeDataV1>, String>,
+    pub daily_stats: DailyStatsIndex,
+}
+
+impl Cw721ExtensionIndexes<TributeDataV1> for TributeIndexesV1<'_> {
+    fn new(pk_namespace: &'static str) -> Self {
+        Self {
+            worldwide_day: MultiIndex::new(
+                |_pk, d: &NftInfo<TributeDataV1>| d.extension.worldwide_day,
+                pk_namespace,
+                "tokens__worldwide_day",
+            ),
+            daily_stats: DailyStatsIndex::new("tokens__daily_stats"),
+        }
+    }
+}
+
+impl IndexList<NftInfo<TributeDataV1>> for TributeIndexesV1<'_> {
+    fn get_indexes(
+        &'_ self,
+    ) -> Box<dyn Iterator<Item = &'_ dyn Index<NftInfo<TributeDataV1>>> + '_> {
+        let v: Vec<&dyn Index<NftInfo<TributeDataV1>>> =
+            vec![&self.worldwide_day, &self.daily_stats];
+        Box::new(v.into_iter())
+    }
+}
@@ -0,0 +1,44 @@
+use crate::error::ContractError;
+use crate::types::TributeConfig;
+use cosmwasm_std::Deps;
+use vector::query::{AllVectorsResponse, QueryMsg as VectorQueryMsg};
+
+/// Checks the vectors chosen for Tributes, querying the vector contract once
+pub struct VectorChecker<'a> {
+    deps: Deps<'a>,
+    config: &'a TributeConfig,
+    vector_ids: Option<Vec<u16>>,
+}
+
+impl<'a> VectorChecker<'a> {
+    pub fn new(deps: Deps<'a>, config: &'a TributeConfig) -> Self {
+        Self {
+            deps,
+            config,
+            vector_ids: None,
+        }
+    }
+
+    /// Accepts no vector or one of the vectors of the configured vector contract
+    pub fn check(&mut self, vector_id: Option<u16>) -> Result<(), ContractError> {
+        let Some(vector_id) = vector_id else {
+            return Ok(());
+        };
+        if self.vector_ids.is_none() {
+            let vector = self
+                .config
+                .vector
+                .as_ref()
+                .ok_or(ContractError::VectorNotConfigured {})?;
+            let response: AllVectorsResponse = self
+                .deps
+                .querier
+                .query_wasm_smart(vector, &VectorQueryMsg::Vectors {})?;
+            self.vector_ids = Some(response.vectors.iter().map(|v| v.vector_id).collect());
+        }
+        match self.vector_ids.as_ref() {
+            Some(ids) if ids.contains(&vector_id) => Ok(()),
+            _ => Err(ContractError::UnknownVector { vector_id }),
+        }
+    }
+}
@@ -36,6 +36,8 @@ fn test_tribute() {
                     settlement_amount_minor: Uint128::from(100000000u32),
                     worldwide_day: normalize_to_date(&app.block_info().time),
                     nominal_price: Decimal::one(),
+                    vector_id: None,
+                    amounts_commitment: None,
                 },
             }),
         },
@@ -97,6 +99,9 @@ fn test_metadosis() {
     println!("📦 Deploy Token Allocator");
     let token_allocator = deploy_token_allocator(&mut app, config.owner_addr.clone());
 
+    println!("📦 Deploy Tribute Factory");
+    let tribute_factory = deploy_tribute_factory(&mut app, config.owner_addr.clone());
+
     println!("📦 Deploy Metadosis");
     let metadosis = deploy_metadosis(
         &mut app,
@@ -106,93 +111,31 @@ fn test_metadosis() {
         token_allocator.address.clone(),
         price_oracle.address.clone(),
         random_oracle.address.clone(),
+        tribute_factory.address.clone(),
     );
-    println!("🧪 Perform tests");
-
-    println!("☑️ Add token pair");
-
-    app.execute_contract(
-        config.owner_addr.clone(),
-        price_oracle.address.clone(),
-        &price_oracle::msg::ExecuteMsg::AddTokenPair {
-            token1: Denom::Native("coen".to_string()),
-            token2: Denom::Native("usdc".to_string()),
-        },
-        &[],
-    )
-    .unwrap();
-
-    app.execute_contract(
-        config.owner_addr.clone(),
-        price_oracle.address.clone(),
-        &price_oracle::msg::ExecuteMsg::AddTokenPair {
-            token1: Denom::Native("xau".to_string()),
-            token2: Denom::Fiat(Currency::Usd),
-        },
-        &[],
-    )
-    .unwrap();
-
-    println!("☑️ Set Green day");
 
+    println!("☑️ Make Metadosis the Tribute burner");
     app.execute_contract(
         config.owner_addr.clone(),
-        price_oracle.address.clone(),
-        &price_oracle::msg::ExecuteMsg::SetDayType {
-            token1: Denom::Native("coen".to_string()),
-            token2: Denom::Native("usdc".to_string()),
-            day_type: price_oracle::types::DayType::Green,
-        },
+        tribute.address.clone(),
+        &tribute::msg::ExecuteMsg::UpdateBurnerOwnership(cw_ownable::Action::TransferOwnership {
+            new_owner: metadosis.address.to_string(),
+            expiry: None,
+        }),
         &[],
     )
     .unwrap();
-
     app.execute_contract(
-        config.owner_addr.clone(),
-        price_oracle.address.clone(),
-        &price_oracle::msg::ExecuteMsg::SetDayType {
-            token1: Denom::Native("xau".to_string()),
-            token2: Denom::Fiat(Currency::Usd),
-            day_type: price_oracle::types::DayType::Green,
-        },
+        metadosis.address.clone(),
+        tribute.address.clone(),
+        &tribute::msg::ExecuteMsg::UpdateBurnerOwnership(cw_ownable::Action::AcceptOwnership),
         &[],
     )
     .unwrap();
 
-    println!("☑️ Add price");
-    app.execute_contract(
-        config.owner_addr.clone(),
-        price_oracle.address.clone(),
-        &price_oracle::msg::ExecuteMsg::UpdatePrice {
-            token1: Denom::Native("coen".to_string()),
-            token2: Denom::Native("usdc".to_string()),
-            price: Decimal::from_str("1.25").unwrap(),
-            open: None,
-            close: None,
-            high: None,
-            low: None,
-            volume: None,
-        },
-        &[],
-    )
-    .unwrap();
+    println!("🧪 Perform tests");
 
-    app.execute_contract(
-        config.owner_addr.clone(),
-        price_oracle.address.clone(),
-        &price_oracle::msg::ExecuteMsg::UpdatePrice {
-            token1: Denom::Native("xau".to_string()),
-            token2: Denom::Fiat(Currency::Usd),
-            price: Decimal::from_str("3305.90").unwrap(),
-            open: None,
-            close: None,
-            high: None,
-            low: None,
-            volume: None,
-        },
-        &[],
-    )
-    .unwrap();
+    add_prices(&mut app, &config.owner_addr, &price_oracle.address);
 
     println!("☑️ Add tributes");
     app.execute_contract(
@@ -211,6 +154,8 @@ fn test_metadosis() {
                     nominal_amount_minor: Uint128::from(10_000000000000000000u128),
                     worldwide_day: normalize_to_date(&app.block_info().time),
                     nominal_price: Decimal::from_str("0.5").unwrap(),
+                    vector_id: None,
+                    amounts_commitment: None,
                 },
             }),
         },
@@ -234,6 +179,8 @@ fn test_metadosis() {
                     nominal_amount_minor: Uint128::from(5_000000000000000000u128),
                     worldwide_day: normalize_to_date(&app.block_info().time),
                     nominal_price: Decimal::from_str("3").unwrap(),
+                    vector_id: None,
+                    amounts_commitment: None,
                 },
             }),
         },
@@ -295,11 +242,64 @@ fn test_metadosis() {
     app.execute_contract(
         config.owner_addr.clone(),
         metadosis.address.clone(),
-        &metadosis::msg::ExecuteMsg::Execute { run_date: None },
+        &metadosis::msg::ExecuteMsg::Execute {
+            run_date: None,
+            limit: None,
+        },
         &[],
     )
     .unwrap();
 
+    // the run concluded all tributes of the day, so there is nothing left to finish
+    app.execute_contract(
+        config.owner_addr.clone(),
+        metadosis.address.clone(),
+        &metadosis::msg::ExecuteMsg::FinishConclusion {
+            date: metadosis_info.date,
+            limit: None,
+        },
+        &[],
+    )
+    .unwrap_err();
+
+    // the run recorded the root of the tributes sealed by prepare
+    let seal: Option<tribute::merkle::DaySeal> = app
+        .wrap()
+        .query_wasm_smart(
+            tribute.address.clone(),
+            &QueryMsg::DaySeal {
+                date: metadosis_info.date,
+            },
+        )
+        .unwrap();
+    let history: metadosis::query::HistoryResponse = app
+        .wrap()
+        .query_wasm_smart(
+            metadosis.address.clone(),
+            &metadosis::query::QueryMsg::History {},
+        )
+        .unwrap();
+    let tributes_root = match history.data.first().unwrap() {
+        metadosis::state::Entry::Lysis(entity) => entity.tributes_root.clone(),
+        metadosis::state::Entry::Touch(entity) => entity.tributes_root.clone(),
+    };
+    assert_eq!(tributes_root, Some(seal.unwrap().root));
+
+    // the run was for an earlier day, the tributes of today are left offered
+    let response: tribute::query::FullTributesResponse = app
+        .wrap()
+        .query_wasm_smart(
+            tribute.address.clone(),
+            &QueryMsg::TributesByState {
+                state: tribute::types::TributeStatus::Offered,
+                date: Some(normalize_to_date(&app.block_info().time)),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(response.tributes.len(), 2);
+
     let _response: outbe_nft::msg::TokensResponse = app
         .wrap()
         .query_wasm_smart(
@@ -313,6 +313,433 @@ fn test_metadosis() {
         .unwrap();
 }
 
+#[test]
+fn test_offer_window_ends_before_metadosis_run() {
+    use outbe_utils::date::subtract_days;
+    use outbe_utils::Base58Binary;
+    use tribute_factory::contract::generate_tribute_draft_id_hash;
+    use tribute_factory::msg::{ZkProof, ZkProofPublicData};
+    use tribute_factory::query::OfferWindowResponse;
+    use tribute_factory::types::TributeInputPayload;
+
+    let (mut app, config) = setup_test_env();
+
+    let price_oracle = deploy_price_oracle(&mut app, config.owner_addr.clone());
+    let tribute = deploy_tribute(
+        &mut app,
+        config.owner_addr.clone(),
+        price_oracle.address.clone(),
+    );
+    let nod = deploy_nod(&mut app, config.owner_addr.clone());
+    let random_oracle = deploy_random_oracle(&mut app, config.owner_addr.clone());
+    let token_allocator = deploy_token_allocator(&mut app, config.owner_addr.clone());
+    let tribute_factory = deploy_tribute_factory(&mut app, config.owner_addr.clone());
+    let metadosis = deploy_metadosis(
+        &mut app,
+        config.owner_addr.clone(),
+        tribute.address.clone(),
+        nod.address.clone(),
+        token_allocator.address.clone(),
+        price_oracle.address.clone(),
+        random_oracle.address.clone(),
+        tribute_factory.address.clone(),
+    );
+
+    // the factory mints tributes, metadosis seals their days
+    app.execute_contract(
+        config.owner_addr.clone(),
+        tribute.address.clone(),
+        &tribute::msg::ExecuteMsg::UpdateMinterOwnership(cw_ownable::Action::TransferOwnership {
+            new_owner: tribute_factory.address.to_string(),
+            expiry: None,
+        }),
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        tribute_factory.address.clone(),
+        tribute.address.clone(),
+        &tribute::msg::ExecuteMsg::UpdateMinterOwnership(cw_ownable::Action::AcceptOwnership),
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        config.owner_addr.clone(),
+        tribute.address.clone(),
+        &tribute::msg::ExecuteMsg::UpdateBurnerOwnership(cw_ownable::Action::TransferOwnership {
+            new_owner: metadosis.address.to_string(),
+            expiry: None,
+        }),
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        metadosis.address.clone(),
+        tribute.address.clone(),
+        &tribute::msg::ExecuteMsg::UpdateBurnerOwnership(cw_ownable::Action::AcceptOwnership),
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        config.owner_addr.clone(),
+        tribute_factory.address.clone(),
+        &tribute_factory::msg::ExecuteMsg::UpdateConfig {
+            new_tribute_address: Some(tribute.address.clone()),
+            new_owner: None,
+            new_tee_config: None,
+            new_zk_config: None,
+            new_agent_attestation: None,
+            new_offer_window_days: None,
+            new_obfuscation: None,
+            new_check_draft_id: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let offer_window = |app: &App, day| -> OfferWindowResponse {
+        app.wrap()
+            .query_wasm_smart(
+                tribute_factory.address.clone(),
+                &tribute_factory::query::QueryMsg::OfferWindow { day },
+            )
+            .unwrap()
+    };
+    let offer = |app: &mut App, owner: &str, worldwide_day| {
+        let owner = Base58Binary::from(owner.as_bytes());
+        app.execute_contract(
+            config.user_addr.clone(),
+            tribute_factory.address.clone(),
+            &tribute_factory::msg::ExecuteMsg::OfferInsecure {
+                tribute_input: TributeInputPayload {
+                    tribute_draft_id: generate_tribute_draft_id_hash(&owner, worldwide_day)
+                        .unwrap(),
+                    cu_hashes: vec![Base58Binary::from(owner.as_slice())],
+                    worldwide_day,
+                    settlement_currency: Currency::Usd.into(),
+                    settlement_base_amount: cosmwasm_std::Uint64::new(500),
+                    settlement_atto_amount: Uint128::zero(),
+                    nominal_base_amount: cosmwasm_std::Uint64::new(1000),
+                    nominal_atto_amount: Uint128::zero(),
+                    owner,
+                    vector_id: None,
+                    amounts_blinding: None,
+                },
+                zk_proof: ZkProof {
+                    proof: Default::default(),
+                    public_data: ZkProofPublicData {
+                        public_key: Default::default(),
+                        merkle_root: Default::default(),
+                    },
+                    verification_key: Default::default(),
+                },
+                tribute_owner_l1: None,
+            },
+            &[],
+        )
+    };
+    let day_seal = |app: &App, date| -> Option<tribute::merkle::DaySeal> {
+        app.wrap()
+            .query_wasm_smart(tribute.address.clone(), &QueryMsg::DaySeal { date })
+            .unwrap()
+    };
+
+    add_prices(&mut app, &config.owner_addr, &price_oracle.address);
+
+    // today is the last day of the offer window of `last_day`
+    let today = normalize_to_date(&app.block_info().time);
+    let window = offer_window(&app, today).offer_window_days;
+    let last_day = subtract_days(today, window).unwrap();
+    let run_day = subtract_days(last_day, 1).unwrap();
+    assert_eq!(offer_window(&app, last_day).closes_on, today);
+    assert!(offer_window(&app, last_day).open);
+    assert!(!offer_window(&app, run_day).open);
+    offer(&mut app, "owner1", last_day).unwrap();
+
+    // metadosis runs the day whose window closed, the open day can still be offered
+    app.execute_contract(
+        config.owner_addr.clone(),
+        metadosis.address.clone(),
+        &metadosis::msg::ExecuteMsg::Prepare { run_date: None },
+        &[],
+    )
+    .unwrap();
+    assert!(day_seal(&app, run_day).is_some());
+    assert!(day_seal(&app, last_day).is_none());
+    offer(&mut app, "owner2", last_day).unwrap();
+
+    // the next day it is closed in the factory before metadosis seals it
+    app.update_block(|block| block.time = block.time.plus_days(1));
+    assert!(!offer_window(&app, last_day).open);
+    let err = offer(&mut app, "owner3", last_day).unwrap_err();
+    assert_eq!(
+        err.downcast::<tribute_factory::error::ContractError>()
+            .unwrap(),
+        tribute_factory::error::ContractError::ClosedOfferWindow {}
+    );
+}
+
+#[test]
+fn test_metadosis_run_in_pages() {
+    use outbe_utils::date::subtract_days;
+    use tribute::types::TributeStatus;
+
+    let (mut app, config) = setup_test_env();
+
+    let price_oracle = deploy_price_oracle(&mut app, config.owner_addr.clone());
+    let tribute = deploy_tribute(
+        &mut app,
+        config.owner_addr.clone(),
+        price_oracle.address.clone(),
+    );
+    let nod = deploy_nod(&mut app, config.owner_addr.clone());
+    let random_oracle = deploy_random_oracle(&mut app, config.owner_addr.clone());
+    let token_allocator = deploy_token_allocator(&mut app, config.owner_addr.clone());
+    let tribute_factory = deploy_tribute_factory(&mut app, config.owner_addr.clone());
+    let metadosis = deploy_metadosis(
+        &mut app,
+        config.owner_addr.clone(),
+        tribute.address.clone(),
+        nod.address.clone(),
+        token_allocator.address.clone(),
+        price_oracle.address.clone(),
+        random_oracle.address.clone(),
+        tribute_factory.address.clone(),
+    );
+
+    // metadosis recognizes tributes and mints their nods
+    app.execute_contract(
+        config.owner_addr.clone(),
+        tribute.address.clone(),
+        &tribute::msg::ExecuteMsg::UpdateBurnerOwnership(cw_ownable::Action::TransferOwnership {
+            new_owner: metadosis.address.to_string(),
+            expiry: None,
+        }),
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        metadosis.address.clone(),
+        tribute.address.clone(),
+        &tribute::msg::ExecuteMsg::UpdateBurnerOwnership(cw_ownable::Action::AcceptOwnership),
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        config.owner_addr.clone(),
+        nod.address.clone(),
+        &nod::msg::ExecuteMsg::UpdateMinterOwnership(cw_ownable::Action::TransferOwnership {
+            new_owner: metadosis.address.to_string(),
+            expiry: None,
+        }),
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        metadosis.address.clone(),
+        nod.address.clone(),
+        &nod::msg::ExecuteMsg::UpdateMinterOwnership(cw_ownable::Action::AcceptOwnership),
+        &[],
+    )
+    .unwrap();
+
+    add_prices(&mut app, &config.owner_addr, &price_oracle.address);
+
+    // tributes of the day whose offer window is over
+    let today = normalize_to_date(&app.block_info().time);
+    let window: tribute_factory::query::OfferWindowResponse = app
+        .wrap()
+        .query_wasm_smart(
+            tribute_factory.address.clone(),
+            &tribute_factory::query::QueryMsg::OfferWindow { day: today },
+        )
+        .unwrap();
+    let run_day = subtract_days(today, window.offer_window_days + 1).unwrap();
+    for token_id in ["1", "2", "3"] {
+        app.execute_contract(
+            config.owner_addr.clone(),
+            tribute.address.clone(),
+            &Mint {
+                token_id: token_id.to_string(),
+                token_uri: None,
+                owner: config.user_addr.to_string(),
+                extension: Box::new(MintExtension {
+                    data: TributeMintData {
+                        tribute_id: token_id.to_string(),
+                        owner: config.user_addr.to_string(),
+                        settlement_currency: Denom::Fiat(Currency::Usd),
+                        settlement_amount_minor: Uint128::from(5_000000000000000000u128),
+                        nominal_amount_minor: Uint128::from(10_000000000000000000u128),
+                        worldwide_day: run_day,
+                        nominal_price: Decimal::from_str("0.5").unwrap(),
+                        vector_id: None,
+                        amounts_commitment: None,
+                    },
+                }),
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    app.execute_contract(
+        config.owner_addr.clone(),
+        metadosis.address.clone(),
+        &metadosis::msg::ExecuteMsg::Prepare { run_date: None },
+        &[],
+    )
+    .unwrap();
+
+    let tributes_count = |app: &App, state| -> usize {
+        let response: tribute::query::FullTributesResponse = app
+            .wrap()
+            .query_wasm_smart(
+                tribute.address.clone(),
+                &QueryMsg::TributesByState {
+                    state,
+                    date: Some(run_day),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        response.tributes.len()
+    };
+    let execute = |app: &mut App| {
+        app.execute_contract(
+            config.owner_addr.clone(),
+            metadosis.address.clone(),
+            &metadosis::msg::ExecuteMsg::Execute {
+                run_date: None,
+                limit: Some(2),
+            },
+            &[],
+        )
+    };
+    let finish_conclusion = |app: &mut App| {
+        app.execute_contract(
+            config.owner_addr.clone(),
+            metadosis.address.clone(),
+            &metadosis::msg::ExecuteMsg::FinishConclusion {
+                date: run_day,
+                limit: None,
+            },
+            &[],
+        )
+    };
+
+    // the first page is recognized, the day is not concluded before its last page
+    execute(&mut app).unwrap();
+    assert_eq!(tributes_count(&app, TributeStatus::Recognized), 2);
+    assert_eq!(tributes_count(&app, TributeStatus::Offered), 1);
+    finish_conclusion(&mut app).unwrap_err();
+
+    execute(&mut app).unwrap();
+    assert_eq!(tributes_count(&app, TributeStatus::Recognized), 3);
+    assert_eq!(tributes_count(&app, TributeStatus::Offered), 0);
+    let history: metadosis::query::HistoryResponse = app
+        .wrap()
+        .query_wasm_smart(
+            metadosis.address.clone(),
+            &metadosis::query::QueryMsg::History {},
+        )
+        .unwrap();
+    match history.data.first().unwrap() {
+        metadosis::state::Entry::Lysis(entity) => assert_eq!(entity.assigned_tributes, 3),
+        metadosis::state::Entry::Touch(_) => panic!("expected a lysis run"),
+    }
+
+    // the run is finished
+    execute(&mut app).unwrap_err();
+}
+
+fn add_prices(app: &mut App, owner: &Addr, price_oracle: &Addr) {
+    println!("☑️ Add token pair");
+
+    app.execute_contract(
+        owner.clone(),
+        price_oracle.clone(),
+        &price_oracle::msg::ExecuteMsg::AddTokenPair {
+            token1: Denom::Native("coen".to_string()),
+            token2: Denom::Native("usdc".to_string()),
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        owner.clone(),
+        price_oracle.clone(),
+        &price_oracle::msg::ExecuteMsg::AddTokenPair {
+            token1: Denom::Native("xau".to_string()),
+            token2: Denom::Fiat(Currency::Usd),
+        },
+        &[],
+    )
+    .unwrap();
+
+    println!("☑️ Set Green day");
+
+    app.execute_contract(
+        owner.clone(),
+        price_oracle.clone(),
+        &price_oracle::msg::ExecuteMsg::SetDayType {
+            token1: Denom::Native("coen".to_string()),
+            token2: Denom::Native("usdc".to_string()),
+            day_type: price_oracle::types::DayType::Green,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        owner.clone(),
+        price_oracle.clone(),
+        &price_oracle::msg::ExecuteMsg::SetDayType {
+            token1: Denom::Native("xau".to_string()),
+            token2: Denom::Fiat(Currency::Usd),
+            day_type: price_oracle::types::DayType::Green,
+        },
+        &[],
+    )
+    .unwrap();
+
+    println!("☑️ Add price");
+    app.execute_contract(
+        owner.clone(),
+        price_oracle.clone(),
+        &price_oracle::msg::ExecuteMsg::UpdatePrice {
+            token1: Denom::Native("coen".to_string()),
+            token2: Denom::Native("usdc".to_string()),
+            price: Decimal::from_str("1.25").unwrap(),
+            open: None,
+            close: None,
+            high: None,
+            low: None,
+            volume: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        owner.clone(),
+        price_oracle.clone(),
+        &price_oracle::msg::ExecuteMsg::UpdatePrice {
+            token1: Denom::Native("xau".to_string()),
+            token2: Denom::Fiat(Currency::Usd),
+            price: Decimal::from_str("3305.90").unwrap(),
+            open: None,
+            close: None,
+            high: None,
+            low: None,
+            volume: None,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
 pub fn deploy_tribute(app: &mut App, owner: Addr, price_oracle: Addr) -> DeployedContract {
     use tribute::contract::{execute, instantiate};
     use tribute::msg::InstantiateMsg;
@@ -324,10 +751,10 @@ pub fn deploy_tribute(app: &mut App, owner: Addr, price_oracle: Addr) -> Deploye
     let instantiate_msg = InstantiateMsg {
         name: "consumption unit".to_string(),
         symbol: "cu".to_string(),
-        collection_info_extension: TributeCollectionExtension {
-            native_token: Denom::Native(NATIVE_DENOM.to_string()),
+        collection_info_extension: TributeCollectionExtension::new(
+            Denom::Native(NATIVE_DENOM.to_string()),
             price_oracle,
-        },
+        ),
         minter: None,
         creator: None,
         burner: None,
@@ -355,7 +782,11 @@ pub fn deploy_nod(app: &mut App, owner: Addr) -> DeployedContract {
     let instantiate_msg = InstantiateMsg {
         name: "nod".to_string(),
         symbol: "nod".to_string(),
-        collection_info_extension: nod::msg::NodCollectionExtension {},
+        collection_info_extension: nod::msg::NodCollectionExtension {
+            transfer_policy: None,
+            track_history: None,
+            render_token_uri: None,
+        },
         minter: None,
         creator: None,
         burner: None,
@@ -396,6 +827,33 @@ pub fn deploy_random_oracle(app: &mut App, owner: Addr) -> DeployedContract {
     DeployedContract { address, code_id }
 }
 
+pub fn deploy_tribute_factory(app: &mut App, owner: Addr) -> DeployedContract {
+    use tribute_factory::contract::{execute, instantiate};
+    use tribute_factory::msg::InstantiateMsg;
+    use tribute_factory::query::query;
+
+    let code = ContractWrapper::new(execute, instantiate, query);
+    let code_id = app.store_code(Box::new(code));
+
+    let instantiate_msg = InstantiateMsg {
+        tribute_address: None,
+        owner: Some(owner.clone()),
+        tee_config: None,
+        zk_config: None,
+    };
+    let address = app
+        .instantiate_contract(
+            code_id,
+            owner,
+            &instantiate_msg,
+            &[],
+            "tribute-factory".to_string(),
+            None,
+        )
+        .unwrap();
+    DeployedContract { address, code_id }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn deploy_metadosis(
     app: &mut App,
@@ -405,6 +863,7 @@ pub fn deploy_metadosis(
     token_allocator: Addr,
     price_oracle: Addr,
     random_oracle: Addr,
+    tribute_factory: Addr,
 ) -> DeployedContract {
     use metadosis::contract::{execute, instantiate, reply};
     use metadosis::msg::InstantiateMsg;
@@ -420,6 +879,7 @@ pub fn deploy_metadosis(
         token_allocator: Some(token_allocator),
         price_oracle: Some(price_oracle),
         random_oracle: Some(random_oracle),
+        tribute_factory: Some(tribute_factory),
         lysis_limit_percent: Decimal::from_str("0.08").unwrap(),
     };
     let address = app
@@ -0,0 +1,258 @@
+use crate::error::Cw721ContractError;
+use crate::execute::{
+    approve, approve_all, burn_batch, burn_nft, initialize_burner, initialize_creator,
+    initialize_minter, mint_batch, mint_nft, revoke, revoke_all, send_nft, transfer_nft,
+    update_burner_ownership, update_creator_ownership, update_minter_ownership, Cw721ExecuteMsg,
+};
+use crate::msg::Cw721InstantiateMsg;
+use crate::query::{query_cw721, Cw721QueryMsg};
+use crate::state::{CollectionInfo, Cw721Config, HistoryContext, NftInfo};
+use crate::traits::{Cw721CollectionConfig, Cw721ExtensionIndexes, Cw721State};
+use cosmwasm_std::{Binary, Deps, DepsMut, Env, Event, MessageInfo, Response, StdResult, Storage};
+
+/// Roles set up when the collection is instantiated
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollectionRoles {
+    pub minter: String,
+    pub creator: String,
+    pub burner: String,
+}
+
+/// Base of an NFT collection.
+///
+/// The default methods implement instantiation, the execute messages of `Cw721ExecuteMsg` and
+/// the queries of `Cw721QueryMsg`, so a collection only defines its token data and its own
+/// messages. Collections implement it on a unit struct and route the standard variants of their
+/// `ExecuteMsg` and `QueryMsg` to `execute_cw721` and `query_cw721`.
+pub trait Cw721Collection {
+    type NftExtension: Cw721State;
+    type CollectionConfig: Cw721CollectionConfig;
+    type ExtensionIndexes: Cw721ExtensionIndexes<Self::NftExtension>;
+
+    /// Prefix of the actions and events emitted by the collection, e.g. `tribute`
+    const NAME: &'static str;
+
+    fn config(
+    ) -> Cw721Config<'static, Self::NftExtension, Self::CollectionConfig, Self::ExtensionIndexes>
+    {
+        Cw721Config::default()
+    }
+
+    /// Stores the collection info and config and sets up the minter, creator and burner roles.
+    /// Roles that are not set in `msg` are given to the sender.
+    fn instantiate_collection<TCollectionExtensionMsg>(
+        deps: DepsMut,
+        env: &Env,
+        info: &MessageInfo,
+        msg: &Cw721InstantiateMsg<TCollectionExtensionMsg>,
+        collection_config: &Self::CollectionConfig,
+    ) -> Result<CollectionRoles, Cw721ContractError> {
+        let config = Self::config();
+        config
+            .collection_config
+            .save(deps.storage, collection_config)?;
+        config.collection_info.save(
+            deps.storage,
+            &CollectionInfo {
+                name: msg.name.clone(),
+                symbol: msg.symbol.clone(),
+                updated_at: env.block.time,
+            },
+        )?;
+
+        let role = |role: &Option<String>| role.clone().unwrap_or_else(|| info.sender.to_string());
+        let roles = CollectionRoles {
+            minter: role(&msg.minter),
+            creator: role(&msg.creator),
+            burner: role(&msg.burner),
+        };
+        initialize_minter(deps.storage, deps.api, Some(&roles.minter))?;
+        initialize_creator(deps.storage, deps.api, Some(&roles.creator))?;
+        initialize_burner(deps.storage, deps.api, Some(&roles.burner))?;
+        Ok(roles)
+    }
+
+    /// Stores a new token, see `mint_nft`
+    fn mint(
+        storage: &mut dyn Storage,
+        ctx: &HistoryContext,
+        token_id: &str,
+        token: NftInfo<Self::NftExtension>,
+    ) -> Result<(), Cw721ContractError> {
+        mint_nft::<Self::NftExtension, Self::CollectionConfig, Self::ExtensionIndexes>(
+            storage, ctx, token_id, token,
+        )
+    }
+
+    /// Stores a batch of new tokens, see `mint_batch`
+    fn mint_batch(
+        storage: &mut dyn Storage,
+        ctx: &HistoryContext,
+        tokens: Vec<(String, NftInfo<Self::NftExtension>, Option<String>)>,
+    ) -> Result<(), Cw721ContractError> {
+        mint_batch::<Self::NftExtension, Self::CollectionConfig, Self::ExtensionIndexes>(
+            storage, ctx, tokens,
+        )
+    }
+
+    fn execute_cw721(
+        deps: DepsMut,
+        env: &Env,
+        info: &MessageInfo,
+        msg: Cw721ExecuteMsg,
+    ) -> Result<Response, Cw721ContractError> {
+        match msg {
+            Cw721ExecuteMsg::UpdateMinterOwnership(action) => {
+                update_minter_ownership(deps, env, info, action)
+            }
+            Cw721ExecuteMsg::UpdateCreatorOwnership(action) => {
+                update_creator_ownership(deps, env, info, action)
+            }
+            Cw721ExecuteMsg::UpdateBurnerOwnership(action) => {
+                update_burner_ownership(deps, env, info, action)
+            }
+            Cw721ExecuteMsg::TransferNft {
+                recipient,
+                token_id,
+            } => {
+                transfer_nft::<Self::NftExtension, Self::CollectionConfig, Self::ExtensionIndexes>(
+                    deps, env, info, recipient, token_id,
+                )
+            }
+            Cw721ExecuteMsg::SendNft {
+                contract,
+                token_id,
+                msg,
+            } => send_nft::<Self::NftExtension, Self::CollectionConfig, Self::ExtensionIndexes>(
+                deps, env, info, contract, token_id, msg,
+            ),
+            Cw721ExecuteMsg::Approve {
+                spender,
+                token_id,
+                expires,
+            } => approve::<Self::NftExtension, Self::CollectionConfig, Self::ExtensionIndexes>(
+                deps, env, info, spender, token_id, expires,
+            ),
+            Cw721ExecuteMsg::Revoke { spender, token_id } => {
+                revoke::<Self::NftExtension, Self::CollectionConfig, Self::ExtensionIndexes>(
+                    deps, env, info, spender, token_id,
+                )
+            }
+            Cw721ExecuteMsg::ApproveAll { operator, expires } => {
+                approve_all(deps, env, info, operator, expires)
+            }
+            Cw721ExecuteMsg::RevokeAll { operator } => revoke_all(deps, env, info, operator),
+            Cw721ExecuteMsg::Burn { token_id } => {
+                burn_nft::<Self::NftExtension, Self::CollectionConfig, Self::ExtensionIndexes>(
+                    deps, env, info, &token_id,
+                )?;
+                let action = format!("{}::burn", Self::NAME);
+                Ok(Response::new().add_attribute("action", &action).add_event(
+                    Event::new(action)
+                        .add_attribute("sender", info.sender.to_string())
+                        .add_attribute("token_id", token_id),
+                ))
+            }
+            Cw721ExecuteMsg::BurnBatch { token_ids } => {
+                burn_batch::<Self::NftExtension, Self::CollectionConfig, Self::ExtensionIndexes>(
+                    deps, env, info, &token_ids,
+                )?;
+                let action = format!("{}::burn_batch", Self::NAME);
+                Ok(Response::new().add_attribute("action", &action).add_event(
+                    Event::new(action)
+                        .add_attribute("sender", info.sender.to_string())
+                        .add_attribute("count", token_ids.len().to_string())
+                        .add_attribute("token_ids", token_ids.join(",")),
+                ))
+            }
+        }
+    }
+
+    fn query_cw721(deps: Deps, env: &Env, msg: Cw721QueryMsg) -> StdResult<Binary> {
+        query_cw721::<Self::NftExtension, Self::CollectionConfig>(deps, env, msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Cw721ContractError;
+    use crate::query::query_minter_ownership;
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::{from_json, Empty};
+
+    struct TestCollection;
+
+    impl Cw721Collection for TestCollection {
+        type NftExtension = Option<Empty>;
+        type CollectionConfig = Option<Empty>;
+        type ExtensionIndexes = crate::state::NoExtensionIndexes;
+
+        const NAME: &'static str = "test";
+    }
+
+    #[test]
+    fn test_collection_base() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = deps.api.addr_make("creator");
+        let minter = deps.api.addr_make("minter");
+        let owner = deps.api.addr_make("owner");
+
+        let msg = Cw721InstantiateMsg {
+            name: "test".to_string(),
+            symbol: "t".to_string(),
+            collection_info_extension: (),
+            minter: Some(minter.to_string()),
+            burner: None,
+            creator: None,
+        };
+        let roles = TestCollection::instantiate_collection(
+            deps.as_mut(),
+            &env,
+            &message_info(&creator, &[]),
+            &msg,
+            &None,
+        )
+        .unwrap();
+        assert_eq!(roles.minter, minter.to_string());
+        assert_eq!(roles.creator, creator.to_string());
+        assert_eq!(roles.burner, creator.to_string());
+        assert_eq!(
+            query_minter_ownership(&deps.storage).unwrap().owner,
+            Some(minter)
+        );
+
+        let ctx = HistoryContext::new(&env, &message_info(&creator, &[]));
+        let token = NftInfo {
+            owner: owner.clone(),
+            token_uri: None,
+            approvals: vec![],
+            extension: None,
+        };
+        TestCollection::mint(&mut deps.storage, &ctx, "1", token.clone()).unwrap();
+        let err = TestCollection::mint(&mut deps.storage, &ctx, "1", token).unwrap_err();
+        assert_eq!(err, Cw721ContractError::Claimed {});
+
+        let num_tokens: crate::msg::NumTokensResponse = from_json(
+            TestCollection::query_cw721(deps.as_ref(), &env, Cw721QueryMsg::NumTokens {}).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(num_tokens.count, 1);
+
+        let res = TestCollection::execute_cw721(
+            deps.as_mut(),
+            &env,
+            &message_info(&owner, &[]),
+            Cw721ExecuteMsg::Burn {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.events[0].ty, "test::burn");
+        assert_eq!(
+            TestCollection::config().token_count(&deps.storage).unwrap(),
+            0
+        );
+    }
+}
@@ -27,9 +27,24 @@ pub enum Cw721ContractError {
     #[error(transparent)]
     Version(#[from] cw2::VersionError),
 
+    #[error("Token storage version {stored} cannot be migrated down to {target}")]
+    StorageDowngrade { stored: u16, target: u16 },
+
+    #[error("Token storage version is {stored}, expected {expected}")]
+    UnexpectedStorageVersion { expected: u16, stored: u16 },
+
+    #[error("Tokens are being migrated from storage version {stored} to {expected}")]
+    MigrationPending { expected: u16, stored: u16 },
+
     #[error("token_id already claimed")]
     Claimed {},
 
+    #[error("token_id {token_id} occurs more than once in the batch")]
+    DuplicateInBatch { token_id: String },
+
+    #[error("Batch must contain at least one token")]
+    EmptyBatch {},
+
     #[error("Caller is not collection creator")]
     NotCreator {},
 
@@ -39,9 +54,15 @@ pub enum Cw721ContractError {
     #[error("Caller is not burner")]
     NotBurner {},
 
+    #[error("Caller is neither token owner, approved operator nor burner")]
+    NotOwnerOperatorOrBurner {},
+
     #[error("Caller is neither minter nor collection creator")]
     NotMinterOrCreator {},
 
+    #[error("Tokens of this collection are not transferable")]
+    Soulbound {},
+
     #[error("Cannot set approval that is already expired")]
     Expired {},
 
@@ -1,7 +1,68 @@
 use crate::error::Cw721ContractError;
-use crate::state::{BURNER, CREATOR, MINTER};
-use cosmwasm_std::{Addr, Api, DepsMut, Env, MessageInfo, Response, StdResult, Storage};
-use cw_ownable::{Action, Ownership};
+use crate::msg::Cw721ReceiveMsg;
+use crate::state::{
+    Approval, Cw721Config, HistoryContext, NftInfo, TokenAction, TransferPolicy, BURNER, CREATOR,
+    MINTER,
+};
+use crate::traits::{Cw721CollectionConfig, Cw721ExtensionIndexes, Cw721State};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    Addr, Api, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult, Storage,
+};
+use cw_ownable::{Action, Ownership, OwnershipError};
+use cw_utils::Expiration;
+use std::collections::BTreeSet;
+
+/// Execute messages every collection supports. Collections expose these variants next to their
+/// custom messages and dispatch them with `Cw721Collection::execute_cw721`.
+#[cw_serde]
+pub enum Cw721ExecuteMsg {
+    UpdateMinterOwnership(Action),
+    UpdateCreatorOwnership(Action),
+    UpdateBurnerOwnership(Action),
+    /// Transfer is a base message to move a token to another account without triggering actions
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    /// Send is a base message to transfer a token to a contract and trigger an action
+    /// on the receiving contract.
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    /// Allows operator to transfer / send the token from the owner's account.
+    /// If expiration is set, then this allowance has a time/height limit
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted Approval
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    /// Allows operator to transfer / send any token from the owner's account.
+    /// If expiration is set, then this allowance has a time/height limit
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted ApproveAll permission
+    RevokeAll {
+        operator: String,
+    },
+    /// Burn a token the sender has access to
+    Burn {
+        token_id: String,
+    },
+    /// Burn several tokens at once, the sender must have access to all of them
+    BurnBatch {
+        token_ids: Vec<String>,
+    },
+}
 
 pub fn assert_minter(storage: &dyn Storage, sender: &Addr) -> Result<(), Cw721ContractError> {
     if MINTER.assert_owner(storage, sender).is_err() {
@@ -84,3 +145,975 @@ pub fn update_creator_ownership(
         .add_attribute("update_creator_ownership", info.sender.to_string())
         .add_attributes(ownership.into_attributes()))
 }
+
+// ------- transfers and approvals -------
+pub fn transfer_nft<TNftExtension, TCollectionConfig, TExtensionIndexes>(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    recipient: String,
+    token_id: String,
+) -> Result<Response, Cw721ContractError>
+where
+    TNftExtension: Cw721State,
+    TCollectionConfig: Cw721CollectionConfig,
+    TExtensionIndexes: Cw721ExtensionIndexes<TNftExtension>,
+{
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    _transfer_nft::<TNftExtension, TCollectionConfig, TExtensionIndexes>(
+        deps,
+        env,
+        info,
+        &recipient_addr,
+        &token_id,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "transfer_nft")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("recipient", recipient)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn send_nft<TNftExtension, TCollectionConfig, TExtensionIndexes>(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    contract: String,
+    token_id: String,
+    msg: Binary,
+) -> Result<Response, Cw721ContractError>
+where
+    TNftExtension: Cw721State,
+    TCollectionConfig: Cw721CollectionConfig,
+    TExtensionIndexes: Cw721ExtensionIndexes<TNftExtension>,
+{
+    let contract_addr = deps.api.addr_validate(&contract)?;
+    // Transfer token
+    _transfer_nft::<TNftExtension, TCollectionConfig, TExtensionIndexes>(
+        deps,
+        env,
+        info,
+        &contract_addr,
+        &token_id,
+    )?;
+
+    let send = Cw721ReceiveMsg {
+        sender: info.sender.to_string(),
+        token_id: token_id.clone(),
+        msg,
+    };
+
+    // Send message
+    Ok(Response::new()
+        .add_message(send.into_cosmos_msg(contract.clone())?)
+        .add_attribute("action", "send_nft")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("recipient", contract)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn approve<TNftExtension, TCollectionConfig, TExtensionIndexes>(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    spender: String,
+    token_id: String,
+    expires: Option<Expiration>,
+) -> Result<Response, Cw721ContractError>
+where
+    TNftExtension: Cw721State,
+    TCollectionConfig: Cw721CollectionConfig,
+    TExtensionIndexes: Cw721ExtensionIndexes<TNftExtension>,
+{
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    _update_approvals::<TNftExtension, TCollectionConfig, TExtensionIndexes>(
+        deps,
+        env,
+        info,
+        &spender_addr,
+        &token_id,
+        true,
+        expires,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "approve")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("spender", spender)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn revoke<TNftExtension, TCollectionConfig, TExtensionIndexes>(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    spender: String,
+    token_id: String,
+) -> Result<Response, Cw721ContractError>
+where
+    TNftExtension: Cw721State,
+    TCollectionConfig: Cw721CollectionConfig,
+    TExtensionIndexes: Cw721ExtensionIndexes<TNftExtension>,
+{
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    _update_approvals::<TNftExtension, TCollectionConfig, TExtensionIndexes>(
+        deps,
+        env,
+        info,
+        &spender_addr,
+        &token_id,
+        false,
+        None,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("spender", spender)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn approve_all(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    operator: String,
+    expires: Option<Expiration>,
+) -> Result<Response, Cw721ContractError> {
+    // reject expired data as invalid
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(Cw721ContractError::Expired {});
+    }
+
+    // set the operator for us
+    let operator_addr = deps.api.addr_validate(&operator)?;
+    Cw721Config::<Option<Empty>, Option<Empty>>::default()
+        .operators
+        .save(deps.storage, (&info.sender, &operator_addr), &expires)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "approve_all")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("operator", operator))
+}
+
+pub fn revoke_all(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    operator: String,
+) -> Result<Response, Cw721ContractError> {
+    let operator_addr = deps.api.addr_validate(&operator)?;
+    Cw721Config::<Option<Empty>, Option<Empty>>::default()
+        .operators
+        .remove(deps.storage, (&info.sender, &operator_addr));
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke_all")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("operator", operator))
+}
+
+/// Returns an error if the sender is not allowed to move the token under the given policy
+pub fn check_can_send<TNftExtension>(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    token: &NftInfo<TNftExtension>,
+    policy: &TransferPolicy,
+) -> Result<(), Cw721ContractError> {
+    match policy {
+        TransferPolicy::Soulbound => Err(Cw721ContractError::Soulbound {}),
+        TransferPolicy::OwnerOnly => {
+            if token.owner == *sender {
+                Ok(())
+            } else {
+                Err(Cw721ContractError::Ownership(OwnershipError::NotOwner))
+            }
+        }
+        TransferPolicy::Transferable => {
+            // owner can send
+            if token.owner == *sender {
+                return Ok(());
+            }
+
+            // any non-expired token approval can send
+            if token
+                .approvals
+                .iter()
+                .any(|apr| apr.spender == *sender && !apr.is_expired(&env.block))
+            {
+                return Ok(());
+            }
+
+            // operator can send
+            if is_operator(deps.storage, env, &token.owner, sender)? {
+                return Ok(());
+            }
+            Err(Cw721ContractError::Ownership(OwnershipError::NotOwner))
+        }
+    }
+}
+
+/// Returns an error if the sender can not manage approvals of the token
+pub fn check_can_approve<TNftExtension>(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    token: &NftInfo<TNftExtension>,
+) -> Result<(), Cw721ContractError> {
+    // owner can approve
+    if token.owner == *sender {
+        return Ok(());
+    }
+    // operator can approve
+    if is_operator(deps.storage, env, &token.owner, sender)? {
+        return Ok(());
+    }
+    Err(Cw721ContractError::Ownership(OwnershipError::NotOwner))
+}
+
+/// Returns an error if the sender can not burn the token.
+/// The token owner, an approved spender or operator of the owner and the collection burner can burn.
+/// Spenders and operators can not burn tokens of a soulbound collection.
+pub fn check_can_burn<TNftExtension>(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    token: &NftInfo<TNftExtension>,
+    policy: &TransferPolicy,
+) -> Result<(), Cw721ContractError> {
+    // owner can burn
+    if token.owner == *sender {
+        return Ok(());
+    }
+    if *policy != TransferPolicy::Soulbound {
+        // any non-expired token approval can burn
+        if token
+            .approvals
+            .iter()
+            .any(|apr| apr.spender == *sender && !apr.is_expired(&env.block))
+        {
+            return Ok(());
+        }
+        // operator can burn
+        if is_operator(deps.storage, env, &token.owner, sender)? {
+            return Ok(());
+        }
+    }
+    // burner can burn
+    if BURNER.assert_owner(deps.storage, sender).is_ok() {
+        return Ok(());
+    }
+    Err(Cw721ContractError::NotOwnerOperatorOrBurner {})
+}
+
+/// Stores a new token and updates the token count, fails if the token id is already taken
+pub fn mint_nft<TNftExtension, TCollectionConfig, TExtensionIndexes>(
+    storage: &mut dyn Storage,
+    ctx: &HistoryContext,
+    token_id: &str,
+    token: NftInfo<TNftExtension>,
+) -> Result<(), Cw721ContractError>
+where
+    TNftExtension: Cw721State,
+    TCollectionConfig: Cw721CollectionConfig,
+    TExtensionIndexes: Cw721ExtensionIndexes<TNftExtension>,
+{
+    let config = Cw721Config::<TNftExtension, TCollectionConfig, TExtensionIndexes>::default();
+    let owner = token.owner.clone();
+    config.nft_info.update(storage, token_id, |old| match old {
+        Some(_) => Err(Cw721ContractError::Claimed {}),
+        None => Ok(token),
+    })?;
+    config.increment_tokens(storage)?;
+    config.record_history(storage, ctx, token_id, TokenAction::Mint { owner })?;
+    Ok(())
+}
+
+/// Removes the token after checking the sender is allowed to burn it, see `check_can_burn`
+pub fn burn_nft<TNftExtension, TCollectionConfig, TExtensionIndexes>(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+) -> Result<NftInfo<TNftExtension>, Cw721ContractError>
+where
+    TNftExtension: Cw721State,
+    TCollectionConfig: Cw721CollectionConfig,
+    TExtensionIndexes: Cw721ExtensionIndexes<TNftExtension>,
+{
+    let config = Cw721Config::<TNftExtension, TCollectionConfig, TExtensionIndexes>::default();
+    let policy = config
+        .collection_config
+        .load(deps.storage)?
+        .transfer_policy();
+    let token = config.nft_info.load(deps.storage, token_id)?;
+    check_can_burn(deps.as_ref(), env, &info.sender, &token, &policy)?;
+
+    config.nft_info.remove(deps.storage, token_id)?;
+    config.decrement_tokens(deps.storage)?;
+    config.record_history(
+        deps.storage,
+        &HistoryContext::new(env, info),
+        token_id,
+        TokenAction::Burn {
+            owner: token.owner.clone(),
+        },
+    )?;
+    Ok(token)
+}
+
+/// Stores a batch of new tokens and updates the token count once.
+/// The whole batch is validated before anything is written: it is rejected if it is empty,
+/// if a token id occurs twice or if a token id is already taken.
+/// The optional reason of a token is kept in its history instead of the one of `ctx`.
+pub fn mint_batch<TNftExtension, TCollectionConfig, TExtensionIndexes>(
+    storage: &mut dyn Storage,
+    ctx: &HistoryContext,
+    tokens: Vec<(String, NftInfo<TNftExtension>, Option<String>)>,
+) -> Result<(), Cw721ContractError>
+where
+    TNftExtension: Cw721State,
+    TCollectionConfig: Cw721CollectionConfig,
+    TExtensionIndexes: Cw721ExtensionIndexes<TNftExtension>,
+{
+    if tokens.is_empty() {
+        return Err(Cw721ContractError::EmptyBatch {});
+    }
+    let config = Cw721Config::<TNftExtension, TCollectionConfig, TExtensionIndexes>::default();
+
+    let mut token_ids: BTreeSet<&str> = BTreeSet::new();
+    for (token_id, _, _) in tokens.iter() {
+        if !token_ids.insert(token_id) {
+            return Err(Cw721ContractError::DuplicateInBatch {
+                token_id: token_id.clone(),
+            });
+        }
+        if config.nft_info.has(storage, token_id) {
+            return Err(Cw721ContractError::Claimed {});
+        }
+    }
+
+    let track_history = config.history_enabled(storage)?;
+    for (token_id, token, reason) in tokens.iter() {
+        config.nft_info.save(storage, token_id, token)?;
+        if track_history {
+            let ctx = HistoryContext {
+                reason: reason.clone().or_else(|| ctx.reason.clone()),
+                ..ctx.clone()
+            };
+            let action = TokenAction::Mint {
+                owner: token.owner.clone(),
+            };
+            config.append_history(storage, &ctx, token_id, action)?;
+        }
+    }
+    config.increase_tokens(storage, tokens.len() as u64)?;
+    Ok(())
+}
+
+/// Removes a batch of tokens and updates the token count once.
+/// The sender must be allowed to burn every token of the batch, see `check_can_burn`,
+/// otherwise nothing is removed.
+pub fn burn_batch<TNftExtension, TCollectionConfig, TExtensionIndexes>(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_ids: &[String],
+) -> Result<Vec<NftInfo<TNftExtension>>, Cw721ContractError>
+where
+    TNftExtension: Cw721State,
+    TCollectionConfig: Cw721CollectionConfig,
+    TExtensionIndexes: Cw721ExtensionIndexes<TNftExtension>,
+{
+    if token_ids.is_empty() {
+        return Err(Cw721ContractError::EmptyBatch {});
+    }
+    let config = Cw721Config::<TNftExtension, TCollectionConfig, TExtensionIndexes>::default();
+    let policy = config
+        .collection_config
+        .load(deps.storage)?
+        .transfer_policy();
+
+    let mut seen: BTreeSet<&str> = BTreeSet::new();
+    let mut tokens = Vec::with_capacity(token_ids.len());
+    for token_id in token_ids {
+        if !seen.insert(token_id) {
+            return Err(Cw721ContractError::DuplicateInBatch {
+                token_id: token_id.clone(),
+            });
+        }
+        let token = config.nft_info.load(deps.storage, token_id)?;
+        check_can_burn(deps.as_ref(), env, &info.sender, &token, &policy)?;
+        tokens.push(token);
+    }
+
+    let ctx = HistoryContext::new(env, info);
+    let track_history = config.history_enabled(deps.storage)?;
+    for (token_id, token) in token_ids.iter().zip(tokens.iter()) {
+        config.nft_info.remove(deps.storage, token_id)?;
+        if track_history {
+            let action = TokenAction::Burn {
+                owner: token.owner.clone(),
+            };
+            config.append_history(deps.storage, &ctx, token_id, action)?;
+        }
+    }
+    config.decrease_tokens(deps.storage, token_ids.len() as u64)?;
+    Ok(tokens)
+}
+
+fn is_operator(storage: &dyn Storage, env: &Env, owner: &Addr, operator: &Addr) -> StdResult<bool> {
+    let expires = Cw721Config::<Option<Empty>, Option<Empty>>::default()
+        .operators
+        .may_load(storage, (owner, operator))?;
+    Ok(matches!(expires, Some(ex) if !ex.is_expired(&env.block)))
+}
+
+fn _transfer_nft<TNftExtension, TCollectionConfig, TExtensionIndexes>(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    recipient: &Addr,
+    token_id: &str,
+) -> Result<NftInfo<TNftExtension>, Cw721ContractError>
+where
+    TNftExtension: Cw721State,
+    TCollectionConfig: Cw721CollectionConfig,
+    TExtensionIndexes: Cw721ExtensionIndexes<TNftExtension>,
+{
+    let config = Cw721Config::<TNftExtension, TCollectionConfig, TExtensionIndexes>::default();
+    let policy = config
+        .collection_config
+        .load(deps.storage)?
+        .transfer_policy();
+    let mut token = config.nft_info.load(deps.storage, token_id)?;
+    check_can_send(deps.as_ref(), env, &info.sender, &token, &policy)?;
+
+    let previous_owner = token.owner.clone();
+    // set owner and remove existing approvals
+    token.owner = recipient.clone();
+    token.approvals = vec![];
+    // NB: the owner index is updated by the indexed map
+    config.nft_info.save(deps.storage, token_id, &token)?;
+    config.record_history(
+        deps.storage,
+        &HistoryContext::new(env, info),
+        token_id,
+        TokenAction::Transfer {
+            from: previous_owner,
+            to: recipient.clone(),
+        },
+    )?;
+    Ok(token)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn _update_approvals<TNftExtension, TCollectionConfig, TExtensionIndexes>(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    spender: &Addr,
+    token_id: &str,
+    // if add == false, remove. if add == true, remove then set with this expiration
+    add: bool,
+    expires: Option<Expiration>,
+) -> Result<NftInfo<TNftExtension>, Cw721ContractError>
+where
+    TNftExtension: Cw721State,
+    TCollectionConfig: Cw721CollectionConfig,
+    TExtensionIndexes: Cw721ExtensionIndexes<TNftExtension>,
+{
+    let config = Cw721Config::<TNftExtension, TCollectionConfig, TExtensionIndexes>::default();
+    let mut token = config.nft_info.load(deps.storage, token_id)?;
+    // ensure we have permissions
+    check_can_approve(deps.as_ref(), env, &info.sender, &token)?;
+
+    // update the approval list (remove any for the same spender before adding)
+    token.approvals.retain(|apr| apr.spender != *spender);
+
+    // only difference between approve and revoke
+    if add {
+        // reject expired data as invalid
+        let expires = expires.unwrap_or_default();
+        if expires.is_expired(&env.block) {
+            return Err(Cw721ContractError::Expired {});
+        }
+        let approval = Approval {
+            spender: spender.clone(),
+            expires,
+        };
+        token.approvals.push(approval);
+    }
+
+    config.nft_info.save(deps.storage, token_id, &token)?;
+
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::{query_token_history, query_tokens};
+    use crate::state::NoExtensionIndexes;
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::testing::{
+        message_info, mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{Addr, OwnedDeps};
+
+    #[cw_serde]
+    struct TestConfig {
+        transfer_policy: TransferPolicy,
+        track_history: bool,
+    }
+
+    impl Cw721CollectionConfig for TestConfig {
+        fn transfer_policy(&self) -> TransferPolicy {
+            self.transfer_policy.clone()
+        }
+
+        fn track_history(&self) -> bool {
+            self.track_history
+        }
+    }
+
+    type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+    fn setup(policy: TransferPolicy) -> (TestDeps, Addr) {
+        let mut deps = mock_dependencies();
+        let owner = deps.api.addr_make("owner");
+        let config = Cw721Config::<Option<Empty>, TestConfig>::default();
+        config
+            .collection_config
+            .save(
+                &mut deps.storage,
+                &TestConfig {
+                    transfer_policy: policy,
+                    track_history: false,
+                },
+            )
+            .unwrap();
+        config
+            .nft_info
+            .save(
+                &mut deps.storage,
+                "1",
+                &NftInfo {
+                    owner: owner.clone(),
+                    token_uri: None,
+                    approvals: vec![],
+                    extension: None,
+                },
+            )
+            .unwrap();
+        (deps, owner)
+    }
+
+    fn transfer(
+        deps: &mut TestDeps,
+        sender: &Addr,
+        recipient: &Addr,
+    ) -> Result<Response, Cw721ContractError> {
+        transfer_nft::<Option<Empty>, TestConfig, NoExtensionIndexes>(
+            deps.as_mut(),
+            &mock_env(),
+            &message_info(sender, &[]),
+            recipient.to_string(),
+            "1".to_string(),
+        )
+    }
+
+    fn approve_spender(
+        deps: &mut TestDeps,
+        sender: &Addr,
+        spender: &Addr,
+        expires: Option<Expiration>,
+    ) {
+        approve::<Option<Empty>, TestConfig, NoExtensionIndexes>(
+            deps.as_mut(),
+            &mock_env(),
+            &message_info(sender, &[]),
+            spender.to_string(),
+            "1".to_string(),
+            expires,
+        )
+        .unwrap();
+    }
+
+    fn owner_of(deps: &TestDeps) -> Addr {
+        Cw721Config::<Option<Empty>, Option<Empty>>::default()
+            .nft_info
+            .load(&deps.storage, "1")
+            .unwrap()
+            .owner
+    }
+
+    #[test]
+    fn test_soulbound_rejects_transfer() {
+        let (mut deps, owner) = setup(TransferPolicy::Soulbound);
+        let recipient = deps.api.addr_make("recipient");
+
+        let err = transfer(&mut deps, &owner, &recipient).unwrap_err();
+        assert_eq!(err, Cw721ContractError::Soulbound {});
+        assert_eq!(owner_of(&deps), owner);
+    }
+
+    #[test]
+    fn test_owner_only_transfer() {
+        let (mut deps, owner) = setup(TransferPolicy::OwnerOnly);
+        let spender = deps.api.addr_make("spender");
+        let recipient = deps.api.addr_make("recipient");
+
+        // approved spender can not transfer
+        approve_spender(&mut deps, &owner, &spender, None);
+        let err = transfer(&mut deps, &spender, &recipient).unwrap_err();
+        assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+        transfer(&mut deps, &owner, &recipient).unwrap();
+        assert_eq!(owner_of(&deps), recipient);
+
+        // the owner index follows the new owner
+        let env = mock_env();
+        let tokens = query_tokens(deps.as_ref(), &env, owner.to_string(), None, None, None)
+            .unwrap()
+            .tokens;
+        assert!(tokens.is_empty());
+        let tokens = query_tokens(deps.as_ref(), &env, recipient.to_string(), None, None, None)
+            .unwrap()
+            .tokens;
+        assert_eq!(tokens, vec!["1".to_string()]);
+    }
+
+    #[test]
+    fn test_transferable_by_spender_and_operator() {
+        let (mut deps, owner) = setup(TransferPolicy::Transferable);
+        let spender = deps.api.addr_make("spender");
+        let operator = deps.api.addr_make("operator");
+        let recipient = deps.api.addr_make("recipient");
+        let env = mock_env();
+
+        // random address can not transfer
+        let err = transfer(&mut deps, &operator, &recipient).unwrap_err();
+        assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+        // expired approvals are rejected
+        let err = approve::<Option<Empty>, TestConfig, NoExtensionIndexes>(
+            deps.as_mut(),
+            &env,
+            &message_info(&owner, &[]),
+            spender.to_string(),
+            "1".to_string(),
+            Some(Expiration::AtHeight(env.block.height)),
+        )
+        .unwrap_err();
+        assert_eq!(err, Cw721ContractError::Expired {});
+
+        // approved spender transfers and the approvals are cleared
+        approve_spender(&mut deps, &owner, &spender, None);
+        transfer(&mut deps, &spender, &recipient).unwrap();
+        let token = Cw721Config::<Option<Empty>, Option<Empty>>::default()
+            .nft_info
+            .load(&deps.storage, "1")
+            .unwrap();
+        assert_eq!(token.owner, recipient);
+        assert!(token.approvals.is_empty());
+
+        // operator of the new owner transfers the token back
+        approve_all(
+            deps.as_mut(),
+            &env,
+            &message_info(&recipient, &[]),
+            operator.to_string(),
+            None,
+        )
+        .unwrap();
+        transfer(&mut deps, &operator, &owner).unwrap();
+        assert_eq!(owner_of(&deps), owner);
+
+        // revoked operator can not transfer anymore
+        revoke_all(
+            deps.as_mut(),
+            &env,
+            &message_info(&recipient, &[]),
+            operator.to_string(),
+        )
+        .unwrap();
+        approve_all(
+            deps.as_mut(),
+            &env,
+            &message_info(&owner, &[]),
+            operator.to_string(),
+            None,
+        )
+        .unwrap();
+        revoke_all(
+            deps.as_mut(),
+            &env,
+            &message_info(&owner, &[]),
+            operator.to_string(),
+        )
+        .unwrap();
+        let err = transfer(&mut deps, &operator, &recipient).unwrap_err();
+        assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    }
+
+    #[test]
+    fn test_check_can_burn() {
+        let (mut deps, owner) = setup(TransferPolicy::Soulbound);
+        let burner = deps.api.addr_make("burner");
+        let operator = deps.api.addr_make("operator");
+        let stranger = deps.api.addr_make("stranger");
+        let env = mock_env();
+        initialize_burner(&mut deps.storage, &deps.api, Some(burner.as_str())).unwrap();
+        approve_all(
+            deps.as_mut(),
+            &env,
+            &message_info(&owner, &[]),
+            operator.to_string(),
+            None,
+        )
+        .unwrap();
+
+        let token = Cw721Config::<Option<Empty>, Option<Empty>>::default()
+            .nft_info
+            .load(&deps.storage, "1")
+            .unwrap();
+        let transferable = TransferPolicy::Transferable;
+        for allowed in [&owner, &burner, &operator] {
+            check_can_burn(deps.as_ref(), &env, allowed, &token, &transferable).unwrap();
+        }
+        let err =
+            check_can_burn(deps.as_ref(), &env, &stranger, &token, &transferable).unwrap_err();
+        assert_eq!(err, Cw721ContractError::NotOwnerOperatorOrBurner {});
+
+        // operators and spenders can not burn soulbound tokens
+        let soulbound = TransferPolicy::Soulbound;
+        for allowed in [&owner, &burner] {
+            check_can_burn(deps.as_ref(), &env, allowed, &token, &soulbound).unwrap();
+        }
+        let err = check_can_burn(deps.as_ref(), &env, &operator, &token, &soulbound).unwrap_err();
+        assert_eq!(err, Cw721ContractError::NotOwnerOperatorOrBurner {});
+        let err = burn_nft::<Option<Empty>, TestConfig, NoExtensionIndexes>(
+            deps.as_mut(),
+            &env,
+            &message_info(&operator, &[]),
+            "1",
+        )
+        .unwrap_err();
+        assert_eq!(err, Cw721ContractError::NotOwnerOperatorOrBurner {});
+
+        let err = burn_nft::<Option<Empty>, TestConfig, NoExtensionIndexes>(
+            deps.as_mut(),
+            &env,
+            &message_info(&stranger, &[]),
+            "1",
+        )
+        .unwrap_err();
+        assert_eq!(err, Cw721ContractError::NotOwnerOperatorOrBurner {});
+
+        Cw721Config::<Option<Empty>, TestConfig>::default()
+            .token_count
+            .save(&mut deps.storage, &1)
+            .unwrap();
+        burn_nft::<Option<Empty>, TestConfig, NoExtensionIndexes>(
+            deps.as_mut(),
+            &env,
+            &message_info(&burner, &[]),
+            "1",
+        )
+        .unwrap();
+        assert!(Cw721Config::<Option<Empty>, Option<Empty>>::default()
+            .nft_info
+            .may_load(&deps.storage, "1")
+            .unwrap()
+            .is_none());
+    }
+
+    fn new_token(owner: &Addr) -> NftInfo<Option<Empty>> {
+        NftInfo {
+            owner: owner.clone(),
+            token_uri: None,
+            approvals: vec![],
+            extension: None,
+        }
+    }
+
+    #[test]
+    fn test_mint_and_burn_batch() {
+        let (mut deps, owner) = setup(TransferPolicy::Soulbound);
+        let stranger = deps.api.addr_make("stranger");
+        let env = mock_env();
+        let config = Cw721Config::<Option<Empty>, TestConfig>::default();
+        config.token_count.save(&mut deps.storage, &1).unwrap();
+        let ctx = HistoryContext::new(&env, &message_info(&owner, &[]));
+
+        // duplicates inside the batch and already minted tokens reject the whole batch
+        let err = mint_batch::<Option<Empty>, TestConfig, NoExtensionIndexes>(
+            &mut deps.storage,
+            &ctx,
+            vec![
+                ("2".to_string(), new_token(&owner), None),
+                ("2".to_string(), new_token(&owner), None),
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            Cw721ContractError::DuplicateInBatch {
+                token_id: "2".to_string()
+            }
+        );
+        let err = mint_batch::<Option<Empty>, TestConfig, NoExtensionIndexes>(
+            &mut deps.storage,
+            &ctx,
+            vec![
+                ("2".to_string(), new_token(&owner), None),
+                ("1".to_string(), new_token(&owner), None),
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(err, Cw721ContractError::Claimed {});
+        assert!(!config.nft_info.has(&deps.storage, "2"));
+        assert_eq!(config.token_count(&deps.storage).unwrap(), 1);
+
+        mint_batch::<Option<Empty>, TestConfig, NoExtensionIndexes>(
+            &mut deps.storage,
+            &ctx,
+            vec![
+                ("2".to_string(), new_token(&owner), None),
+                ("3".to_string(), new_token(&stranger), None),
+            ],
+        )
+        .unwrap();
+        assert_eq!(config.token_count(&deps.storage).unwrap(), 3);
+
+        // the owner can not burn a token of somebody else, so nothing is burned
+        let token_ids = vec!["1".to_string(), "2".to_string(), "3".to_string()];
+        let err = burn_batch::<Option<Empty>, TestConfig, NoExtensionIndexes>(
+            deps.as_mut(),
+            &env,
+            &message_info(&owner, &[]),
+            &token_ids,
+        )
+        .unwrap_err();
+        assert_eq!(err, Cw721ContractError::NotOwnerOperatorOrBurner {});
+        assert_eq!(config.token_count(&deps.storage).unwrap(), 3);
+
+        let burned = burn_batch::<Option<Empty>, TestConfig, NoExtensionIndexes>(
+            deps.as_mut(),
+            &env,
+            &message_info(&owner, &[]),
+            &token_ids[..2],
+        )
+        .unwrap();
+        assert_eq!(burned.len(), 2);
+        assert_eq!(config.token_count(&deps.storage).unwrap(), 1);
+        assert!(config.nft_info.has(&deps.storage, "3"));
+    }
+
+    #[test]
+    fn test_token_history() {
+        let mut deps = mock_dependencies();
+        let minter = deps.api.addr_make("minter");
+        let owner = deps.api.addr_make("owner");
+        let recipient = deps.api.addr_make("recipient");
+        let env = mock_env();
+        let config = Cw721Config::<Option<Empty>, TestConfig>::default();
+        config
+            .collection_config
+            .save(
+                &mut deps.storage,
+                &TestConfig {
+                    transfer_policy: TransferPolicy::OwnerOnly,
+                    track_history: true,
+                },
+            )
+            .unwrap();
+
+        let ctx = HistoryContext::new(&env, &message_info(&minter, &[])).with_reason("batch");
+        mint_batch::<Option<Empty>, TestConfig, NoExtensionIndexes>(
+            &mut deps.storage,
+            &ctx,
+            vec![
+                (
+                    "1".to_string(),
+                    new_token(&owner),
+                    Some("lysis".to_string()),
+                ),
+                ("2".to_string(), new_token(&owner), None),
+            ],
+        )
+        .unwrap();
+        transfer(&mut deps, &owner, &recipient).unwrap();
+        burn_nft::<Option<Empty>, TestConfig, NoExtensionIndexes>(
+            deps.as_mut(),
+            &env,
+            &message_info(&recipient, &[]),
+            "1",
+        )
+        .unwrap();
+
+        let history = query_token_history(&deps.storage, "1", None, None)
+            .unwrap()
+            .entries;
+        assert_eq!(
+            history
+                .iter()
+                .map(|entry| (entry.seq, entry.action.clone(), entry.reason.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    1,
+                    TokenAction::Mint {
+                        owner: owner.clone()
+                    },
+                    Some("lysis".to_string())
+                ),
+                (
+                    3,
+                    TokenAction::Transfer {
+                        from: owner.clone(),
+                        to: recipient.clone()
+                    },
+                    None
+                ),
+                (
+                    4,
+                    TokenAction::Burn {
+                        owner: recipient.clone()
+                    },
+                    None
+                ),
+            ]
+        );
+        assert_eq!(history[0].sender, minter);
+        assert_eq!(history[2].sender, recipient);
+
+        // pagination continues after the given sequence number
+        let page = query_token_history(&deps.storage, "1", Some(1), Some(1))
+            .unwrap()
+            .entries;
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].seq, 3);
+
+        let history = query_token_history(&deps.storage, "2", None, None)
+            .unwrap()
+            .entries;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].reason, Some("batch".to_string()));
+
+        // cleaning the collection records the burns too
+        let ctx = HistoryContext::new(&env, &message_info(&minter, &[])).with_reason("burn_all");
+        config.clean_tokens(&mut deps.storage, &ctx, None).unwrap();
+        let history = query_token_history(&deps.storage, "2", None, None)
+            .unwrap()
+            .entries;
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].action, TokenAction::Burn { owner });
+        assert_eq!(history[1].reason, Some("burn_all".to_string()));
+    }
+}
@@ -1,5 +1,8 @@
+pub mod collection;
 pub mod error;
 pub mod execute;
+pub mod metadata;
+pub mod migration;
 pub mod msg;
 pub mod query;
 pub mod state;
@@ -0,0 +1,95 @@
+use crate::state::CollectionInfo;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_json_vec, Binary, StdResult};
+
+/// Prefix of a `token_uri` carrying the metadata itself
+pub const DATA_URI_PREFIX: &str = "data:application/json;base64,";
+
+/// Token metadata following the ERC721 Metadata JSON Schema,
+/// with the `attributes` extension understood by wallets and marketplaces
+#[cw_serde]
+pub struct NftMetadata {
+    pub name: String,
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    pub attributes: Vec<NftAttribute>,
+}
+
+#[cw_serde]
+pub struct NftAttribute {
+    pub trait_type: String,
+    pub value: String,
+    /// Hint for wallets on how to show the value, e.g. `number`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_type: Option<String>,
+}
+
+impl NftAttribute {
+    pub fn new(trait_type: impl Into<String>, value: impl ToString) -> Self {
+        Self {
+            trait_type: trait_type.into(),
+            value: value.to_string(),
+            display_type: None,
+        }
+    }
+
+    pub fn number(trait_type: impl Into<String>, value: impl ToString) -> Self {
+        Self {
+            display_type: Some("number".to_string()),
+            ..Self::new(trait_type, value)
+        }
+    }
+}
+
+impl NftMetadata {
+    /// Metadata of a token without any data of its own: `<collection name> #<token id>`
+    pub fn base(collection: &CollectionInfo, token_id: &str) -> Self {
+        Self {
+            name: format!("{} #{}", collection.name, token_id),
+            description: String::new(),
+            image: None,
+            attributes: vec![],
+        }
+    }
+
+    /// Returns the metadata as a `data:application/json;base64` uri
+    pub fn to_data_uri(&self) -> StdResult<String> {
+        let json = to_json_vec(self)?;
+        Ok(format!(
+            "{}{}",
+            DATA_URI_PREFIX,
+            Binary::new(json).to_base64()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{from_json, Timestamp};
+
+    #[test]
+    fn test_data_uri() {
+        let collection = CollectionInfo {
+            name: "Tribute".to_string(),
+            symbol: "TT".to_string(),
+            updated_at: Timestamp::from_seconds(0),
+        };
+        let mut metadata = NftMetadata::base(&collection, "1");
+        metadata
+            .attributes
+            .push(NftAttribute::number("worldwide_day", 20250101));
+        assert_eq!(metadata.name, "Tribute #1");
+
+        let uri = metadata.to_data_uri().unwrap();
+        let encoded = uri.strip_prefix(DATA_URI_PREFIX).unwrap();
+        let json = Binary::from_base64(encoded).unwrap();
+        assert_eq!(from_json::<NftMetadata>(&json).unwrap(), metadata);
+        // unset optional fields are left out of the json
+        assert_eq!(
+            String::from_utf8(json.to_vec()).unwrap(),
+            r#"{"name":"Tribute #1","description":"","attributes":[{"trait_type":"worldwide_day","value":"20250101","display_type":"number"}]}"#
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use crate::error::Cw721ContractError;
+use crate::state::{Cw721Config, NftInfo};
+use crate::traits::{Cw721ExtensionIndexes, Cw721State};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Empty, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Item};
+
+/// Layout version of the token data currently stored by the collection.
+/// Collections deployed before versioning was introduced have no entry and are at version 1.
+pub const STORAGE_VERSION: Item<u16> = Item::new("cw721_storage_version");
+/// Last token migrated by an unfinished `migrate_nft_infos`
+const MIGRATION_CURSOR: Item<String> = Item::new("cw721_migration_cursor");
+/// Last token saved by an unfinished `reindex_nft_infos`
+const REINDEX_CURSOR: Item<String> = Item::new("cw721_reindex_cursor");
+
+/// Token data with a numbered storage layout.
+/// Every change of the stored data of a collection gets a new type with the next version.
+pub trait VersionedExtension: Cw721State {
+    const VERSION: u16;
+}
+
+/// Token data that can be built from the previous storage layout
+pub trait MigrateFrom<TPrevious: VersionedExtension>: VersionedExtension {
+    fn migrate_from(previous: TPrevious) -> Self;
+}
+
+/// Result of a `migrate_nft_infos` or `reindex_nft_infos` call
+#[cw_serde]
+pub struct MigrationProgress {
+    /// Storage version after the call
+    pub version: u16,
+    pub migrated: u64,
+    /// False if tokens of the previous version are left and the migration must be called again
+    pub finished: bool,
+}
+
+pub fn storage_version(storage: &dyn Storage) -> StdResult<u16> {
+    Ok(STORAGE_VERSION.may_load(storage)?.unwrap_or(1))
+}
+
+/// Sets the storage version of a new collection, so later layouts know where it started
+pub fn initialize_storage_version<TNftExtension: VersionedExtension>(
+    storage: &mut dyn Storage,
+) -> StdResult<()> {
+    STORAGE_VERSION.save(storage, &TNftExtension::VERSION)
+}
+
+/// Checks the stored tokens have the layout of `TNftExtension`, the last step of a migration
+pub fn ensure_storage_version<TNftExtension: VersionedExtension>(
+    storage: &dyn Storage,
+) -> Result<MigrationProgress, Cw721ContractError> {
+    let stored = storage_version(storage)?;
+    if stored > TNftExtension::VERSION {
+        return Err(Cw721ContractError::StorageDowngrade {
+            stored,
+            target: TNftExtension::VERSION,
+        });
+    }
+    if stored < TNftExtension::VERSION {
+        return Err(Cw721ContractError::UnexpectedStorageVersion {
+            expected: TNftExtension::VERSION,
+            stored,
+        });
+    }
+    Ok(MigrationProgress {
+        version: stored,
+        migrated: 0,
+        finished: true,
+    })
+}
+
+/// Checks the tokens can be read and written as `TNftExtension`.
+/// Until `migrate_nft_infos` has finished, tokens of the previous layout are left in the store
+/// and new tokens would be written into the wrong one, so changes are refused meanwhile.
+pub fn assert_storage_version<TNftExtension: VersionedExtension>(
+    storage: &dyn Storage,
+) -> Result<(), Cw721ContractError> {
+    let stored = storage_version(storage)?;
+    if stored < TNftExtension::VERSION {
+        return Err(Cw721ContractError::MigrationPending {
+            expected: TNftExtension::VERSION,
+            stored,
+        });
+    }
+    ensure_storage_version::<TNftExtension>(storage)?;
+    Ok(())
+}
+
+/// Rewrites at most `limit` tokens from the `TFrom` layout to the `TTo` layout,
+/// continuing after the last token migrated by the previous call.
+///
+/// Every token is removed together with its `IFrom` index entries and saved again, so the
+/// `ITo` indexes are built from the new data. The storage version moves to `TTo::VERSION` once
+/// no token of the old layout is left. Migrating a store that is already past `TTo::VERSION` is
+/// refused, a store already at `TTo::VERSION` is left untouched.
+pub fn migrate_nft_infos<TFrom, IFrom, TTo, ITo>(
+    storage: &mut dyn Storage,
+    limit: usize,
+) -> Result<MigrationProgress, Cw721ContractError>
+where
+    TFrom: VersionedExtension,
+    IFrom: Cw721ExtensionIndexes<TFrom>,
+    TTo: MigrateFrom<TFrom>,
+    ITo: Cw721ExtensionIndexes<TTo>,
+{
+    let stored = storage_version(storage)?;
+    if stored >= TTo::VERSION {
+        return ensure_storage_version::<TTo>(storage);
+    }
+    if stored != TFrom::VERSION {
+        return Err(Cw721ContractError::UnexpectedStorageVersion {
+            expected: TFrom::VERSION,
+            stored,
+        });
+    }
+
+    let from = Cw721Config::<TFrom, Option<Empty>, IFrom>::default();
+    let to = Cw721Config::<TTo, Option<Empty>, ITo>::default();
+
+    // tokens are migrated in key order, everything after the cursor still has the old layout
+    let cursor = MIGRATION_CURSOR.may_load(storage)?;
+    let tokens = from
+        .nft_info
+        .range(
+            storage,
+            cursor.as_deref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit + 1)
+        .collect::<StdResult<Vec<(String, NftInfo<TFrom>)>>>()?;
+    let finished = tokens.len() <= limit;
+
+    let mut migrated = 0u64;
+    for (token_id, token) in tokens.into_iter().take(limit) {
+        from.nft_info.remove(storage, &token_id)?;
+        let token = NftInfo {
+            owner: token.owner,
+            approvals: token.approvals,
+            token_uri: token.token_uri,
+            extension: TTo::migrate_from(token.extension),
+        };
+        to.nft_info.save(storage, &token_id, &token)?;
+        MIGRATION_CURSOR.save(storage, &token_id)?;
+        migrated += 1;
+    }
+
+    if !finished {
+        return Ok(MigrationProgress {
+            version: stored,
+            migrated,
+            finished,
+        });
+    }
+    MIGRATION_CURSOR.remove(storage);
+    STORAGE_VERSION.save(storage, &TTo::VERSION)?;
+    Ok(MigrationProgress {
+        version: TTo::VERSION,
+        migrated,
+        finished,
+    })
+}
+
+/// Saves at most `limit` tokens again, continuing after the last token saved by the previous
+/// call, so indexes added to a collection get the entries of the tokens stored before.
+///
+/// A save replaces the index entries of the token, so tokens written between two calls stay
+/// indexed correctly and a finished reindex can be run again.
+pub fn reindex_nft_infos<TNftExtension, TIndexes>(
+    storage: &mut dyn Storage,
+    limit: usize,
+) -> Result<MigrationProgress, Cw721ContractError>
+where
+    TNftExtension: VersionedExtension,
+    TIndexes: Cw721ExtensionIndexes<TNftExtension>,
+{
+    let version = ensure_storage_version::<TNftExtension>(storage)?.version;
+    let config = Cw721Config::<TNftExtension, Option<Empty>, TIndexes>::default();
+
+    let cursor = REINDEX_CURSOR.may_load(storage)?;
+    let tokens = config
+        .nft_info
+        .range(
+            storage,
+            cursor.as_deref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit + 1)
+        .collect::<StdResult<Vec<(String, NftInfo<TNftExtension>)>>>()?;
+    let finished = tokens.len() <= limit;
+
+    let mut migrated = 0u64;
+    for (token_id, token) in tokens.into_iter().take(limit) {
+        config.nft_info.save(storage, &token_id, &token)?;
+        REINDEX_CURSOR.save(storage, &token_id)?;
+        migrated += 1;
+    }
+    if finished {
+        REINDEX_CURSOR.remove(storage);
+    }
+    Ok(MigrationProgress {
+        version,
+        migrated,
+        finished,
+    })
+}
@@ -1,6 +1,6 @@
-use crate::state::CollectionInfo;
+use crate::state::{Approval, CollectionInfo, TokenHistoryEntry};
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
+use cosmwasm_std::{to_json_binary, Binary, CosmosMsg, StdResult, WasmMsg};
 
 #[cw_serde]
 pub struct Cw721InstantiateMsg<TCollectionExtensionMsg> {
@@ -21,6 +21,21 @@ pub struct Cw721InstantiateMsg<TCollectionExtensionMsg> {
     pub creator: Option<String>,
 }
 
+/// A single token of a `MintBatch` message
+#[cw_serde]
+pub struct MintBatchItem<TExtension> {
+    /// Unique ID of the NFT
+    pub token_id: String,
+    /// The owner of the newly minted NFT
+    pub owner: String,
+    /// Universal resource identifier for this NFT
+    pub token_uri: Option<String>,
+    /// Any custom extension used by the collection
+    pub extension: TExtension,
+    /// Why the token is minted, kept in the token history
+    pub reason: Option<String>,
+}
+
 #[cw_serde]
 pub struct CollectionInfoMsg<TCollectionExtensionMsg> {
     pub name: Option<String>,
@@ -28,17 +43,31 @@ pub struct CollectionInfoMsg<TCollectionExtensionMsg> {
     pub extension: TCollectionExtensionMsg,
 }
 
-/// This is a wrapper around CollectionInfo that includes the extension, contract info, and number of tokens (supply).
+/// This is a wrapper around CollectionInfo that includes the collection config.
 #[cw_serde]
-pub struct ContractInfoResponse<TCollectionExtensionMsg> {
+pub struct CollectionInfoResponse<TCollectionConfig> {
     pub collection_info: CollectionInfo,
-    pub collection_config: TCollectionExtensionMsg,
+    pub collection_config: TCollectionConfig,
+}
+
+#[cw_serde]
+pub struct ContractInfoResponse {
+    pub name: String,
+    pub symbol: String,
 }
 
 #[cw_serde]
 pub struct OwnerOfResponse {
     /// Owner of the token
     pub owner: String,
+    /// If set this address is approved to transfer/send the token as well
+    pub approvals: Vec<Approval>,
+}
+
+#[cw_serde]
+pub struct TokenHistoryResponse {
+    /// Entries ordered by `seq`, pass the last `seq` as `start_after` to get the next page
+    pub entries: Vec<TokenHistoryEntry>,
 }
 
 #[cw_serde]
@@ -48,10 +77,20 @@ pub struct NumTokensResponse {
 
 #[cw_serde]
 pub struct NftInfoResponse<TNftExtension> {
+    /// Universal resource identifier for this NFT
+    /// Should point to a JSON file that conforms to the ERC721
+    /// Metadata JSON Schema
+    pub token_uri: Option<String>,
     /// You can add any custom metadata here when you extend cw721-base
     pub extension: TNftExtension,
-    pub owner: Addr,
-    pub token_id: String,
+}
+
+#[cw_serde]
+pub struct AllNftInfoResponse<TNftExtension> {
+    /// Who can transfer the token
+    pub access: OwnerOfResponse,
+    /// Data on the token itself,
+    pub info: NftInfoResponse<TNftExtension>,
 }
 
 #[cw_serde]
@@ -61,3 +100,60 @@ pub struct TokensResponse {
     /// to achieve pagination.
     pub tokens: Vec<String>,
 }
+
+#[cw_serde]
+pub struct ApprovalResponse {
+    pub approval: Approval,
+}
+
+#[cw_serde]
+pub struct ApprovalsResponse {
+    pub approvals: Vec<Approval>,
+}
+
+#[cw_serde]
+pub struct OperatorResponse {
+    pub approval: Approval,
+}
+
+#[cw_serde]
+pub struct OperatorsResponse {
+    pub operators: Vec<Approval>,
+}
+
+/// Cw721ReceiveMsg should be de/serialized under `ReceiveNft()` variant in a ExecuteMsg
+#[cw_serde]
+pub struct Cw721ReceiveMsg {
+    pub sender: String,
+    pub token_id: String,
+    pub msg: Binary,
+}
+
+impl Cw721ReceiveMsg {
+    /// serializes the message
+    pub fn into_json_binary(self) -> StdResult<Binary> {
+        let msg = ReceiverExecuteMsg::ReceiveNft(self);
+        to_json_binary(&msg)
+    }
+
+    /// creates a cosmos_msg sending this struct to the named contract
+    pub fn into_cosmos_msg<TAddr: Into<String>>(
+        self,
+        contract_addr: TAddr,
+    ) -> StdResult<CosmosMsg> {
+        let msg = self.into_json_binary()?;
+        let execute = WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg,
+            funds: vec![],
+        };
+        Ok(execute.into())
+    }
+}
+
+/// This is just a helper to properly serialize the above message.
+/// The actual receiver should include this variant in the larger ExecuteMsg enum
+#[cw_serde]
+enum ReceiverExecuteMsg {
+    ReceiveNft(Cw721ReceiveMsg),
+}
@@ -1,9 +1,17 @@
-use cosmwasm_std::{Addr, Deps, Empty, Env, Order, StdResult, Storage};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, Empty, Env, Order, StdError, StdResult, Storage,
+};
 use cw_ownable::Ownership;
 use cw_storage_plus::Bound;
+use cw_utils::Expiration;
 
-use crate::msg::ContractInfoResponse;
-use crate::state::BURNER;
+use crate::metadata::NftMetadata;
+use crate::msg::{
+    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, CollectionInfoResponse,
+    ContractInfoResponse, OperatorResponse, OperatorsResponse, TokenHistoryResponse,
+};
+use crate::state::{Approval, NftInfo, BURNER};
 use crate::traits::Cw721CollectionConfig;
 use crate::{
     msg::{NftInfoResponse, NumTokensResponse, OwnerOfResponse, TokensResponse},
@@ -14,6 +22,236 @@ use crate::{
 pub const DEFAULT_LIMIT: u32 = 10;
 pub const MAX_LIMIT: u32 = 1000;
 
+/// Queries defined by the CW721 spec together with the role and history queries every collection
+/// supports. Collections expose these variants next to their custom queries and dispatch them
+/// with `query_cw721`.
+#[cw_serde]
+pub enum Cw721QueryMsg {
+    /// Return the owner of the given token, error if token does not exist
+    /// Return type: OwnerOfResponse
+    OwnerOf {
+        token_id: String,
+        /// unset or false will filter out expired approvals, you must set to true to see them
+        include_expired: Option<bool>,
+    },
+    /// Return operator that can access all of the owner's tokens.
+    /// Return type: `ApprovalResponse`
+    Approval {
+        token_id: String,
+        spender: String,
+        include_expired: Option<bool>,
+    },
+    /// Return approvals that a token has
+    /// Return type: `ApprovalsResponse`
+    Approvals {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    /// Return approval of a given operator for all tokens of an owner, error if not set
+    /// Return type: `OperatorResponse`
+    Operator {
+        owner: String,
+        operator: String,
+        include_expired: Option<bool>,
+    },
+    /// List all operators that can access all of the owner's tokens
+    /// Return type: `OperatorsResponse`
+    AllOperators {
+        owner: String,
+        /// unset or false will filter out expired items, you must set to true to see them
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Total number of tokens issued
+    /// Return type: `NumTokensResponse`
+    NumTokens {},
+    /// Returns top-level metadata about the contract
+    /// Return type: `ContractInfoResponse`
+    ContractInfo {},
+    /// Returns metadata about one particular token, based on *ERC721 Metadata JSON Schema*
+    /// but directly from the contract
+    /// Return type: `NftInfoResponse`
+    NftInfo { token_id: String },
+    /// Returns the result of both `NftInfo` and `OwnerOf` as one query as an optimization
+    /// for clients
+    /// Return type: `AllNftInfoResponse`
+    AllNftInfo {
+        token_id: String,
+        /// unset or false will filter out expired approvals, you must set to true to see them
+        include_expired: Option<bool>,
+    },
+    /// Returns all tokens owned by the given address, [] if unset.
+    /// Return type: `TokensResponse`
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        /// Not a part of the spec, ascending if unset
+        query_order: Option<Order>,
+    },
+    /// Lists all token_ids controlled by the contract.
+    /// Return type: `TokensResponse`
+    AllTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        /// Not a part of the spec, ascending if unset
+        query_order: Option<Order>,
+    },
+    /// Returns the collection info together with the collection config
+    /// Return type: `CollectionInfoResponse`
+    GetCollectionInfo {},
+    /// Return type: `Ownership<Addr>`
+    GetMinterOwnership {},
+    /// Return type: `Ownership<Addr>`
+    GetCreatorOwnership {},
+    /// Return type: `Ownership<Addr>`
+    GetBurnerOwnership {},
+    /// Returns the ERC721 metadata built from the token data
+    /// Return type: `NftMetadata`
+    NftMetadata { token_id: String },
+    /// Returns the recorded history of the token, also after it was burned.
+    /// Empty unless the collection keeps history.
+    /// Return type: `TokenHistoryResponse`
+    TokenHistory {
+        token_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+pub fn query_cw721<TNftExtension, TCollectionConfig>(
+    deps: Deps,
+    env: &Env,
+    msg: Cw721QueryMsg,
+) -> StdResult<Binary>
+where
+    TNftExtension: Cw721State,
+    TCollectionConfig: Cw721CollectionConfig,
+{
+    match msg {
+        Cw721QueryMsg::OwnerOf {
+            token_id,
+            include_expired,
+        } => to_json_binary(&query_owner_of(
+            deps.storage,
+            env,
+            token_id,
+            include_expired.unwrap_or(false),
+        )?),
+        Cw721QueryMsg::Approval {
+            token_id,
+            spender,
+            include_expired,
+        } => to_json_binary(&query_approval(
+            deps,
+            env,
+            token_id,
+            spender,
+            include_expired.unwrap_or(false),
+        )?),
+        Cw721QueryMsg::Approvals {
+            token_id,
+            include_expired,
+        } => to_json_binary(&query_approvals(
+            deps,
+            env,
+            token_id,
+            include_expired.unwrap_or(false),
+        )?),
+        Cw721QueryMsg::Operator {
+            owner,
+            operator,
+            include_expired,
+        } => to_json_binary(&query_operator(
+            deps,
+            env,
+            owner,
+            operator,
+            include_expired.unwrap_or(false),
+        )?),
+        Cw721QueryMsg::AllOperators {
+            owner,
+            include_expired,
+            start_after,
+            limit,
+        } => to_json_binary(&query_operators(
+            deps,
+            env,
+            owner,
+            include_expired.unwrap_or(false),
+            start_after,
+            limit,
+        )?),
+        Cw721QueryMsg::NumTokens {} => to_json_binary(&query_num_tokens(deps.storage)?),
+        Cw721QueryMsg::ContractInfo {} => to_json_binary(&query_contract_info(deps.storage)?),
+        Cw721QueryMsg::NftInfo { token_id } => to_json_binary(&query_nft_info::<
+            TNftExtension,
+            TCollectionConfig,
+        >(deps.storage, token_id)?),
+        Cw721QueryMsg::AllNftInfo {
+            token_id,
+            include_expired,
+        } => to_json_binary(&query_all_nft_info::<TNftExtension, TCollectionConfig>(
+            deps.storage,
+            env,
+            token_id,
+            include_expired.unwrap_or(false),
+        )?),
+        Cw721QueryMsg::Tokens {
+            owner,
+            start_after,
+            limit,
+            query_order,
+        } => to_json_binary(&query_tokens(
+            deps,
+            env,
+            owner,
+            start_after,
+            limit,
+            query_order,
+        )?),
+        Cw721QueryMsg::AllTokens {
+            start_after,
+            limit,
+            query_order,
+        } => to_json_binary(&query_all_tokens(
+            deps,
+            env,
+            start_after,
+            limit,
+            query_order,
+        )?),
+        Cw721QueryMsg::GetCollectionInfo {} => {
+            to_json_binary(&query_collection_info::<TCollectionConfig>(deps.storage)?)
+        }
+        Cw721QueryMsg::GetMinterOwnership {} => {
+            to_json_binary(&query_minter_ownership(deps.storage)?)
+        }
+        Cw721QueryMsg::GetCreatorOwnership {} => {
+            to_json_binary(&query_creator_ownership(deps.storage)?)
+        }
+        Cw721QueryMsg::GetBurnerOwnership {} => {
+            to_json_binary(&query_burner_ownership(deps.storage)?)
+        }
+        Cw721QueryMsg::NftMetadata { token_id } => to_json_binary(&query_nft_metadata::<
+            TNftExtension,
+        >(
+            deps.storage, &token_id
+        )?),
+        Cw721QueryMsg::TokenHistory {
+            token_id,
+            start_after,
+            limit,
+        } => to_json_binary(&query_token_history(
+            deps.storage,
+            &token_id,
+            start_after,
+            limit,
+        )?),
+    }
+}
+
 // --- query helpers ---
 pub fn query_minter_ownership(storage: &dyn Storage) -> StdResult<Ownership<Addr>> {
     MINTER.get_ownership(storage)
@@ -32,26 +270,103 @@ pub fn query_num_tokens(storage: &dyn Storage) -> StdResult<NumTokensResponse> {
     Ok(NumTokensResponse { count })
 }
 
-pub fn query_nft_info<TNftExtension>(
+pub fn query_nft_info<TNftExtension, TCollectionConfig>(
     storage: &dyn Storage,
     token_id: String,
 ) -> StdResult<NftInfoResponse<TNftExtension>>
 where
     TNftExtension: Cw721State,
+    TCollectionConfig: Cw721CollectionConfig,
 {
     let info = Cw721Config::<TNftExtension, Option<Empty>>::default()
         .nft_info
         .load(storage, &token_id)?;
     Ok(NftInfoResponse {
+        token_uri: token_uri::<TNftExtension, TCollectionConfig>(storage, &token_id, &info)?,
         extension: info.extension,
-        owner: info.owner,
-        token_id,
     })
 }
 
-pub fn query_contract_info<TCollectionConfig>(
+pub fn query_nft_metadata<TNftExtension>(
+    storage: &dyn Storage,
+    token_id: &str,
+) -> StdResult<NftMetadata>
+where
+    TNftExtension: Cw721State,
+{
+    let config = Cw721Config::<TNftExtension, Option<Empty>>::default();
+    let info = config.nft_info.load(storage, token_id)?;
+    let collection = config.collection_info.load(storage)?;
+    Ok(info.extension.metadata(&collection, token_id))
+}
+
+/// Returns the stored `token_uri`, or the token metadata as a `data:` uri
+/// if the collection renders it for tokens without one
+fn token_uri<TNftExtension, TCollectionConfig>(
+    storage: &dyn Storage,
+    token_id: &str,
+    info: &NftInfo<TNftExtension>,
+) -> StdResult<Option<String>>
+where
+    TNftExtension: Cw721State,
+    TCollectionConfig: Cw721CollectionConfig,
+{
+    if info.token_uri.is_some() {
+        return Ok(info.token_uri.clone());
+    }
+    let config = Cw721Config::<TNftExtension, TCollectionConfig>::default();
+    let render = config
+        .collection_config
+        .may_load(storage)?
+        .is_some_and(|config| config.render_token_uri());
+    if !render {
+        return Ok(None);
+    }
+    let collection = config.collection_info.load(storage)?;
+    info.extension
+        .metadata(&collection, token_id)
+        .to_data_uri()
+        .map(Some)
+}
+
+pub fn query_all_nft_info<TNftExtension, TCollectionConfig>(
+    storage: &dyn Storage,
+    env: &Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<AllNftInfoResponse<TNftExtension>>
+where
+    TNftExtension: Cw721State,
+    TCollectionConfig: Cw721CollectionConfig,
+{
+    let info = Cw721Config::<TNftExtension, Option<Empty>>::default()
+        .nft_info
+        .load(storage, &token_id)?;
+    Ok(AllNftInfoResponse {
+        access: OwnerOfResponse {
+            owner: info.owner.to_string(),
+            approvals: humanize_approvals(env, &info.approvals, include_expired),
+        },
+        info: NftInfoResponse {
+            token_uri: token_uri::<TNftExtension, TCollectionConfig>(storage, &token_id, &info)?,
+            extension: info.extension,
+        },
+    })
+}
+
+pub fn query_contract_info(storage: &dyn Storage) -> StdResult<ContractInfoResponse> {
+    let info = Cw721Config::<Option<Empty>, Option<Empty>>::default()
+        .collection_info
+        .load(storage)?;
+    Ok(ContractInfoResponse {
+        name: info.name,
+        symbol: info.symbol,
+    })
+}
+
+pub fn query_collection_info<TCollectionConfig>(
     storage: &dyn Storage,
-) -> StdResult<ContractInfoResponse<TCollectionConfig>>
+) -> StdResult<CollectionInfoResponse<TCollectionConfig>>
 where
     TCollectionConfig: Cw721CollectionConfig,
 {
@@ -62,7 +377,7 @@ where
         .collection_config
         .load(storage)?;
 
-    Ok(ContractInfoResponse {
+    Ok(CollectionInfoResponse {
         collection_info: info,
         collection_config: config,
     })
@@ -70,14 +385,16 @@ where
 
 pub fn query_owner_of(
     storage: &dyn Storage,
-    _env: &Env,
+    env: &Env,
     token_id: String,
+    include_expired: bool,
 ) -> StdResult<OwnerOfResponse> {
     let nft_info = Cw721Config::<Option<Empty>, Option<Empty>>::default()
         .nft_info
         .load(storage, &token_id)?;
     Ok(OwnerOfResponse {
         owner: nft_info.owner.to_string(),
+        approvals: humanize_approvals(env, &nft_info.approvals, include_expired),
     })
 }
 
@@ -132,14 +449,141 @@ pub fn query_all_tokens(
     Ok(TokensResponse { tokens: tokens? })
 }
 
+pub fn query_approval(
+    deps: Deps,
+    env: &Env,
+    token_id: String,
+    spender: String,
+    include_expired: bool,
+) -> StdResult<ApprovalResponse> {
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    let token = Cw721Config::<Option<Empty>, Option<Empty>>::default()
+        .nft_info
+        .load(deps.storage, &token_id)?;
+
+    // token owner has absolute approval
+    if token.owner == spender_addr {
+        let approval = Approval {
+            spender: token.owner,
+            expires: Expiration::Never {},
+        };
+        return Ok(ApprovalResponse { approval });
+    }
+
+    let approval = token
+        .approvals
+        .into_iter()
+        .filter(|t| t.spender == spender_addr)
+        .find(|t| include_expired || !t.is_expired(&env.block))
+        .ok_or_else(|| StdError::not_found("Approval not found"))?;
+
+    Ok(ApprovalResponse { approval })
+}
+
+/// approvals returns all approvals owner given access to
+pub fn query_approvals(
+    deps: Deps,
+    env: &Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<ApprovalsResponse> {
+    let token = Cw721Config::<Option<Empty>, Option<Empty>>::default()
+        .nft_info
+        .load(deps.storage, &token_id)?;
+    let approvals = humanize_approvals(env, &token.approvals, include_expired);
+
+    Ok(ApprovalsResponse { approvals })
+}
+
+pub fn query_operator(
+    deps: Deps,
+    env: &Env,
+    owner: String,
+    operator: String,
+    include_expired: bool,
+) -> StdResult<OperatorResponse> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let operator_addr = deps.api.addr_validate(&operator)?;
+
+    let expires = Cw721Config::<Option<Empty>, Option<Empty>>::default()
+        .operators
+        .may_load(deps.storage, (&owner_addr, &operator_addr))?
+        .filter(|expires| include_expired || !expires.is_expired(&env.block))
+        .ok_or_else(|| StdError::not_found("Approval not found"))?;
+
+    Ok(OperatorResponse {
+        approval: Approval {
+            spender: operator_addr,
+            expires,
+        },
+    })
+}
+
+/// operators returns all operators owner given access to
+pub fn query_operators(
+    deps: Deps,
+    env: &Env,
+    owner: String,
+    include_expired: bool,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<OperatorsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = start_after
+        .map(|s| deps.api.addr_validate(&s))
+        .transpose()?;
+    let start = start_addr.as_ref().map(Bound::exclusive);
+
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let operators = Cw721Config::<Option<Empty>, Option<Empty>>::default()
+        .operators
+        .prefix(&owner_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, expires)) => include_expired || !expires.is_expired(&env.block),
+            Err(_) => true,
+        })
+        .take(limit)
+        .map(|item| item.map(|(spender, expires)| Approval { spender, expires }))
+        .collect::<StdResult<_>>()?;
+    Ok(OperatorsResponse { operators })
+}
+
+/// Returns history entries of the token in the order they were recorded.
+/// The history is kept after the token is burned.
+pub fn query_token_history(
+    storage: &dyn Storage,
+    token_id: &str,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<TokenHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let entries = Cw721Config::<Option<Empty>, Option<Empty>>::default()
+        .history
+        .prefix(token_id)
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, entry)| entry))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(TokenHistoryResponse { entries })
+}
+
+fn humanize_approvals(env: &Env, approvals: &[Approval], include_expired: bool) -> Vec<Approval> {
+    approvals
+        .iter()
+        .filter(|apr| include_expired || !apr.is_expired(&env.block))
+        .cloned()
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
 
-    use crate::query::query_all_tokens;
-    use crate::state::{Cw721Config, NftInfo};
+    use super::*;
+    use crate::state::NftInfo;
     use cosmwasm_std::testing::{mock_dependencies, mock_env};
-    use cosmwasm_std::Order;
-    use cosmwasm_std::{Addr, Empty};
+    use cosmwasm_std::Addr;
 
     #[test]
     fn test_query_all_tokens_asc() {
@@ -156,6 +600,7 @@ mod tests {
                     &NftInfo {
                         owner: Addr::unchecked("me"),
                         token_uri: None,
+                        approvals: vec![],
                         extension: None,
                     },
                 )
@@ -201,6 +646,7 @@ mod tests {
                     &NftInfo {
                         owner: Addr::unchecked("me"),
                         token_uri: None,
+                        approvals: vec![],
                         extension: None,
                     },
                 )
@@ -230,4 +676,71 @@ mod tests {
         assert_eq!(response.tokens.first().unwrap(), "6");
         assert_eq!(response.tokens.last().unwrap(), "4");
     }
+
+    #[test]
+    fn test_query_owner_of_filters_expired_approvals() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let config = Cw721Config::<Option<Empty>, Option<Empty>>::default();
+        let owner = deps.api.addr_make("owner");
+        let spender = deps.api.addr_make("spender");
+        let expired = deps.api.addr_make("expired");
+
+        config
+            .nft_info
+            .save(
+                &mut deps.storage,
+                "1",
+                &NftInfo {
+                    owner: owner.clone(),
+                    token_uri: Some("ipfs://token".to_string()),
+                    approvals: vec![
+                        Approval {
+                            spender: spender.clone(),
+                            expires: Expiration::Never {},
+                        },
+                        Approval {
+                            spender: expired.clone(),
+                            expires: Expiration::AtHeight(env.block.height - 1),
+                        },
+                    ],
+                    extension: None,
+                },
+            )
+            .unwrap();
+
+        let response = query_owner_of(&deps.storage, &env, "1".to_string(), false).unwrap();
+        assert_eq!(response.owner, owner.to_string());
+        assert_eq!(response.approvals.len(), 1);
+        assert_eq!(response.approvals[0].spender, spender);
+
+        let response = query_all_nft_info::<Option<Empty>, Option<Empty>>(
+            &deps.storage,
+            &env,
+            "1".to_string(),
+            true,
+        )
+        .unwrap();
+        assert_eq!(response.access.approvals.len(), 2);
+        assert_eq!(response.info.token_uri, Some("ipfs://token".to_string()));
+
+        // owner is always approved, expired approvals are not found
+        let response = query_approval(
+            deps.as_ref(),
+            &env,
+            "1".to_string(),
+            owner.to_string(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(response.approval.spender, owner);
+        query_approval(
+            deps.as_ref(),
+            &env,
+            "1".to_string(),
+            expired.to_string(),
+            false,
+        )
+        .unwrap_err();
+    }
 }
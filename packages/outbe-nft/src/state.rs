@@ -1,8 +1,13 @@
-use crate::traits::{Cw721CollectionConfig, Cw721State};
+use crate::traits::{Cw721CollectionConfig, Cw721ExtensionIndexes, Cw721State};
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Order, StdError, StdResult, Storage, Timestamp};
+use cosmwasm_std::{
+    Addr, BlockInfo, Env, MessageInfo, Order, StdError, StdResult, Storage, Timestamp,
+};
 use cw_ownable::{OwnershipStore, OWNERSHIP_KEY};
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex};
+use cw_storage_plus::{
+    Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex, Prefixer, PrimaryKey,
+};
+use cw_utils::Expiration;
 
 /// Creator owns this contract and can update collection info!
 /// !!! Important note here: !!!
@@ -21,53 +26,102 @@ pub struct CollectionInfo {
     pub updated_at: Timestamp,
 }
 
-pub struct Cw721Config<'a, TNftExtension, TCollectionConfig>
+/// Defines who is allowed to move tokens of a collection to another owner.
+#[cw_serde]
+#[derive(Default)]
+pub enum TransferPolicy {
+    /// Tokens are bound to their owner and can never be transferred
+    #[default]
+    Soulbound,
+    /// Only the token owner can transfer or send a token
+    OwnerOnly,
+    /// The owner, an approved spender or an operator can transfer or send a token
+    Transferable,
+}
+
+/// Storage of an NFT collection.
+///
+/// `TExtensionIndexes` declares additional secondary indexes over the NFT extension.
+/// All writes to `nft_info` of a collection must go through the same set of indexes,
+/// otherwise the indexes get out of sync with the stored tokens.
+pub struct Cw721Config<'a, TNftExtension, TCollectionConfig, TExtensionIndexes = NoExtensionIndexes>
 where
     TNftExtension: Cw721State,
     TCollectionConfig: Cw721CollectionConfig,
+    TExtensionIndexes: Cw721ExtensionIndexes<TNftExtension>,
 {
     pub collection_info: Item<CollectionInfo>,
     pub collection_config: Item<TCollectionConfig>,
     pub token_count: Item<u64>,
-    pub nft_info: IndexedMap<&'a str, NftInfo<TNftExtension>, TokenIndexes<'a, TNftExtension>>,
+    /// Stores approvals for all tokens of an owner, keyed by `(owner, operator)`
+    pub operators: Map<(&'a Addr, &'a Addr), Expiration>,
+    /// Continuation cursors of unfinished bulk deletions, keyed by job name
+    pub burn_cursors: Map<&'a str, String>,
+    /// Append-only history of tokens, keyed by `(token_id, seq)`.
+    /// Kept only if the collection config enables it, see `Cw721CollectionConfig::track_history`
+    pub history: Map<(&'a str, u64), TokenHistoryEntry>,
+    /// Sequence number of the last history entry
+    pub history_seq: Item<u64>,
+    pub nft_info: IndexedMap<
+        &'a str,
+        NftInfo<TNftExtension>,
+        TokenIndexes<'a, TNftExtension, TExtensionIndexes>,
+    >,
 }
 
-impl<TNftExtension, TCollectionConfig> Default
-    for Cw721Config<'static, TNftExtension, TCollectionConfig>
+impl<TNftExtension, TCollectionConfig, TExtensionIndexes> Default
+    for Cw721Config<'static, TNftExtension, TCollectionConfig, TExtensionIndexes>
 where
     TNftExtension: Cw721State,
     TCollectionConfig: Cw721CollectionConfig,
+    TExtensionIndexes: Cw721ExtensionIndexes<TNftExtension>,
 {
     fn default() -> Self {
         Self::new(
             "cw721_collection_info",
             "cw721_collection_config",
             "num_tokens",
+            "operators",
+            "burn_cursors",
+            "token_history",
+            "token_history_seq",
             "tokens",
             "tokens__owner",
         )
     }
 }
 
-impl<TNftExtension, TCollectionConfig> Cw721Config<'_, TNftExtension, TCollectionConfig>
+impl<TNftExtension, TCollectionConfig, TExtensionIndexes>
+    Cw721Config<'_, TNftExtension, TCollectionConfig, TExtensionIndexes>
 where
     TNftExtension: Cw721State,
     TCollectionConfig: Cw721CollectionConfig,
+    TExtensionIndexes: Cw721ExtensionIndexes<TNftExtension>,
 {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         collection_info_key: &'static str,
         collection_config_key: &'static str,
         token_count_key: &'static str,
+        operator_key: &'static str,
+        burn_cursor_key: &'static str,
+        history_key: &'static str,
+        history_seq_key: &'static str,
         nft_info_key: &'static str,
         nft_info_owner_key: &'static str,
     ) -> Self {
         let indexes = TokenIndexes {
             owner: MultiIndex::new(token_owner_idx, nft_info_key, nft_info_owner_key),
+            extension: TExtensionIndexes::new(nft_info_key),
         };
         Self {
             collection_info: Item::new(collection_info_key),
             collection_config: Item::new(collection_config_key),
             token_count: Item::new(token_count_key),
+            operators: Map::new(operator_key),
+            burn_cursors: Map::new(burn_cursor_key),
+            history: Map::new(history_key),
+            history_seq: Item::new(history_seq_key),
             nft_info: IndexedMap::new(nft_info_key, indexes),
         }
     }
@@ -77,43 +131,99 @@ where
     }
 
     pub fn increment_tokens(&self, storage: &mut dyn Storage) -> StdResult<u64> {
-        let val = self.token_count(storage)? + 1;
+        self.increase_tokens(storage, 1)
+    }
+
+    pub fn decrement_tokens(&self, storage: &mut dyn Storage) -> StdResult<u64> {
+        self.decrease_tokens(storage, 1)
+    }
+
+    pub fn increase_tokens(&self, storage: &mut dyn Storage, amount: u64) -> StdResult<u64> {
+        let val = self.token_count(storage)? + amount;
         self.token_count.save(storage, &val)?;
         Ok(val)
     }
 
-    pub fn decrement_tokens(&self, storage: &mut dyn Storage) -> StdResult<u64> {
-        let val = self.token_count(storage)? - 1;
+    pub fn decrease_tokens(&self, storage: &mut dyn Storage, amount: u64) -> StdResult<u64> {
+        let val = self.token_count(storage)? - amount;
         self.token_count.save(storage, &val)?;
         Ok(val)
     }
 
+    pub fn history_enabled(&self, storage: &dyn Storage) -> StdResult<bool> {
+        Ok(self
+            .collection_config
+            .may_load(storage)?
+            .is_some_and(|config| config.track_history()))
+    }
+
+    /// Appends an entry to the history of the token if the collection keeps history
+    pub fn record_history(
+        &self,
+        storage: &mut dyn Storage,
+        ctx: &HistoryContext,
+        token_id: &str,
+        action: TokenAction,
+    ) -> StdResult<()> {
+        if !self.history_enabled(storage)? {
+            return Ok(());
+        }
+        self.append_history(storage, ctx, token_id, action)
+    }
+
+    /// Appends an entry to the history of the token, callers check `history_enabled` first
+    pub(crate) fn append_history(
+        &self,
+        storage: &mut dyn Storage,
+        ctx: &HistoryContext,
+        token_id: &str,
+        action: TokenAction,
+    ) -> StdResult<()> {
+        let seq = self.history_seq.may_load(storage)?.unwrap_or_default() + 1;
+        self.history_seq.save(storage, &seq)?;
+        self.history.save(
+            storage,
+            (token_id, seq),
+            &TokenHistoryEntry {
+                seq,
+                action,
+                time: ctx.time,
+                sender: ctx.sender.clone(),
+                reason: ctx.reason.clone(),
+            },
+        )
+    }
+
+    /// Removes up to `batch_size` tokens, all of them if not set, recording their burn
     pub fn clean_tokens(
         &self,
         storage: &mut dyn Storage,
+        ctx: &HistoryContext,
         batch_size: Option<usize>,
     ) -> StdResult<()> {
-        // NB clean indexes as well
         let batch: usize = batch_size.unwrap_or(usize::MAX);
 
-        let paths = self
+        let tokens = self
             .nft_info
-            .prefix_range(storage, None, None, Order::Ascending)
+            .range(storage, None, None, Order::Ascending)
             .filter_map(|item| item.ok())
             .take(batch)
             .collect::<Vec<_>>();
 
-        if paths.is_empty() {
+        if tokens.is_empty() {
             return Ok(());
         }
 
-        let paths_len = paths.len() as u64;
-        for (pk, value) in paths {
+        let track_history = self.history_enabled(storage)?;
+        let paths_len = tokens.len() as u64;
+        for (token_id, token) in tokens {
+            // NB: removing from the indexed map cleans all the indexes as well
             self.nft_info
-                .idx
-                .owner
-                .remove(storage, pk.as_bytes(), &value)?;
-            self.nft_info.remove(storage, &pk)?;
+                .replace(storage, &token_id, None, Some(&token))?;
+            if track_history {
+                let action = TokenAction::Burn { owner: token.owner };
+                self.append_history(storage, ctx, &token_id, action)?;
+            }
         }
         self.token_count.update(storage, |val| {
             if val > paths_len {
@@ -125,6 +235,175 @@ where
 
         Ok(())
     }
+
+    /// Visits up to `limit` tokens starting after the stored cursor of `job` and removes the ones
+    /// matching `predicate`. The cursor is kept between calls until all tokens have been visited,
+    /// so a deletion of any size can be finished in several transactions.
+    pub fn burn_where<F>(
+        &self,
+        storage: &mut dyn Storage,
+        ctx: &HistoryContext,
+        job: &str,
+        limit: usize,
+        predicate: F,
+    ) -> StdResult<BulkBurnProgress>
+    where
+        F: Fn(&str, &NftInfo<TNftExtension>) -> bool,
+    {
+        let limit = limit.max(1);
+        let cursor = self.burn_cursors.may_load(storage, job)?;
+        let start = cursor.as_deref().map(Bound::exclusive);
+        let mut visited = self
+            .nft_info
+            .range(storage, start, None, Order::Ascending)
+            .take(limit + 1)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let finished = visited.len() <= limit;
+        visited.truncate(limit);
+        let cursor = if finished {
+            self.burn_cursors.remove(storage, job);
+            None
+        } else {
+            let last = visited.last().map(|(token_id, _)| token_id.clone());
+            if let Some(last) = &last {
+                self.burn_cursors.save(storage, job, last)?;
+            }
+            last
+        };
+
+        let track_history = self.history_enabled(storage)?;
+        let mut burned = 0u64;
+        for (token_id, token) in visited {
+            if predicate(&token_id, &token) {
+                // NB: removing from the indexed map cleans all the indexes as well
+                self.nft_info
+                    .replace(storage, &token_id, None, Some(&token))?;
+                if track_history {
+                    let action = TokenAction::Burn { owner: token.owner };
+                    self.append_history(storage, ctx, &token_id, action)?;
+                }
+                burned += 1;
+            }
+        }
+        self.decrease_tokens(storage, burned)?;
+
+        Ok(BulkBurnProgress {
+            burned,
+            cursor,
+            finished,
+        })
+    }
+
+    /// Removes up to `limit` tokens found under `prefix` of the given extension index.
+    /// Removed tokens leave the index, so the next call simply starts from the beginning
+    /// of the prefix again and no cursor needs to be stored.
+    pub fn burn_by_index<'i, IK>(
+        &self,
+        storage: &mut dyn Storage,
+        ctx: &HistoryContext,
+        index: &MultiIndex<'i, IK, NftInfo<TNftExtension>, String>,
+        prefix: IK,
+        limit: usize,
+    ) -> StdResult<BulkBurnProgress>
+    where
+        IK: PrimaryKey<'i> + Prefixer<'i>,
+    {
+        let mut token_ids = index
+            .prefix(prefix)
+            .keys(storage, None, None, Order::Ascending)
+            .take(limit + 1)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let finished = token_ids.len() <= limit;
+        token_ids.truncate(limit);
+
+        let track_history = self.history_enabled(storage)?;
+        for token_id in token_ids.iter() {
+            let token = self.nft_info.load(storage, token_id)?;
+            self.nft_info
+                .replace(storage, token_id, None, Some(&token))?;
+            if track_history {
+                let action = TokenAction::Burn { owner: token.owner };
+                self.append_history(storage, ctx, token_id, action)?;
+            }
+        }
+        let burned = token_ids.len() as u64;
+        self.decrease_tokens(storage, burned)?;
+
+        Ok(BulkBurnProgress {
+            burned,
+            cursor: None,
+            finished,
+        })
+    }
+}
+
+/// Change of a token recorded in its history
+#[cw_serde]
+pub enum TokenAction {
+    Mint {
+        owner: Addr,
+    },
+    /// Collection specific state of the token changed
+    StateChange {
+        from: String,
+        to: String,
+    },
+    Transfer {
+        from: Addr,
+        to: Addr,
+    },
+    Burn {
+        owner: Addr,
+    },
+}
+
+#[cw_serde]
+pub struct TokenHistoryEntry {
+    /// Position of the entry in the collection history, grows with every entry
+    pub seq: u64,
+    pub action: TokenAction,
+    /// Block time of the change
+    pub time: Timestamp,
+    /// Address that made the change
+    pub sender: Addr,
+    /// Optional explanation of the change
+    pub reason: Option<String>,
+}
+
+/// Block time, sender and reason recorded with the history entries of a change
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistoryContext {
+    pub time: Timestamp,
+    pub sender: Addr,
+    pub reason: Option<String>,
+}
+
+impl HistoryContext {
+    pub fn new(env: &Env, info: &MessageInfo) -> Self {
+        Self {
+            time: env.block.time,
+            sender: info.sender.clone(),
+            reason: None,
+        }
+    }
+
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+}
+
+/// Outcome of a single step of a bulk deletion
+#[cw_serde]
+pub struct BulkBurnProgress {
+    /// Number of tokens removed in this step
+    pub burned: u64,
+    /// Last visited token, the next step continues after it
+    pub cursor: Option<String>,
+    /// False if there is work left for another step
+    pub finished: bool,
 }
 
 pub fn token_owner_idx<TNftExtension>(_pk: &[u8], d: &NftInfo<TNftExtension>) -> Addr {
@@ -141,25 +420,156 @@ pub struct NftInfo<TNftExtension> {
     /// Metadata JSON Schema
     pub token_uri: Option<String>,
 
+    /// Approvals are stored here, as we clear them all upon transfer and cannot accumulate much
+    #[serde(default)]
+    pub approvals: Vec<Approval>,
+
     /// You can add any custom metadata here when you extend cw721-base
     pub extension: TNftExtension,
 }
 
-pub struct TokenIndexes<'a, TNftExtension>
+#[cw_serde]
+pub struct Approval {
+    /// Account that can transfer/send the token
+    pub spender: Addr,
+    /// When the Approval expires (maybe Expiration::never)
+    pub expires: Expiration,
+}
+
+impl Approval {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires.is_expired(block)
+    }
+}
+
+pub struct TokenIndexes<'a, TNftExtension, TExtensionIndexes = NoExtensionIndexes>
 where
     TNftExtension: Cw721State,
+    TExtensionIndexes: Cw721ExtensionIndexes<TNftExtension>,
 {
     pub owner: MultiIndex<'a, Addr, NftInfo<TNftExtension>, String>,
+    /// Collection specific indexes
+    pub extension: TExtensionIndexes,
+}
+
+impl<TNftExtension, TExtensionIndexes> IndexList<NftInfo<TNftExtension>>
+    for TokenIndexes<'_, TNftExtension, TExtensionIndexes>
+where
+    TNftExtension: Cw721State,
+    TExtensionIndexes: Cw721ExtensionIndexes<TNftExtension>,
+{
+    fn get_indexes(
+        &'_ self,
+    ) -> Box<dyn Iterator<Item = &'_ dyn Index<NftInfo<TNftExtension>>> + '_> {
+        let owner: &dyn Index<NftInfo<TNftExtension>> = &self.owner;
+        Box::new(std::iter::once(owner).chain(self.extension.get_indexes()))
+    }
 }
 
-impl<TNftExtension> IndexList<NftInfo<TNftExtension>> for TokenIndexes<'_, TNftExtension>
+/// Used by collections that do not need any indexes except `owner`.
+pub struct NoExtensionIndexes;
+
+impl<TNftExtension> Cw721ExtensionIndexes<TNftExtension> for NoExtensionIndexes
+where
+    TNftExtension: Cw721State,
+{
+    fn new(_pk_namespace: &'static str) -> Self {
+        NoExtensionIndexes
+    }
+}
+
+impl<TNftExtension> IndexList<NftInfo<TNftExtension>> for NoExtensionIndexes
 where
     TNftExtension: Cw721State,
 {
     fn get_indexes(
         &'_ self,
     ) -> Box<dyn Iterator<Item = &'_ dyn Index<NftInfo<TNftExtension>>> + '_> {
-        let v: Vec<&dyn Index<NftInfo<TNftExtension>>> = vec![&self.owner];
-        Box::new(v.into_iter())
+        Box::new(std::iter::empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+    use cosmwasm_std::Empty;
+
+    #[test]
+    fn test_burn_where_continues_from_cursor() {
+        let mut storage = MockStorage::new();
+        let config = Cw721Config::<Option<Empty>, Option<Empty>>::default();
+        for i in 0..5 {
+            let owner = if i % 2 == 0 { "even" } else { "odd" };
+            config
+                .nft_info
+                .save(
+                    &mut storage,
+                    &i.to_string(),
+                    &NftInfo {
+                        owner: Addr::unchecked(owner),
+                        token_uri: None,
+                        approvals: vec![],
+                        extension: None,
+                    },
+                )
+                .unwrap();
+        }
+        config.increase_tokens(&mut storage, 5).unwrap();
+
+        let ctx = HistoryContext {
+            time: Timestamp::from_seconds(1),
+            sender: Addr::unchecked("burner"),
+            reason: None,
+        };
+        let is_even = |_: &str, token: &NftInfo<Option<Empty>>| token.owner.as_str() == "even";
+
+        let progress = config
+            .burn_where(&mut storage, &ctx, "even", 2, is_even)
+            .unwrap();
+        assert_eq!(
+            progress,
+            BulkBurnProgress {
+                burned: 1,
+                cursor: Some("1".to_string()),
+                finished: false,
+            }
+        );
+        let progress = config
+            .burn_where(&mut storage, &ctx, "even", 2, is_even)
+            .unwrap();
+        assert_eq!(progress.burned, 1);
+        assert!(!progress.finished);
+        let progress = config
+            .burn_where(&mut storage, &ctx, "even", 2, is_even)
+            .unwrap();
+        assert_eq!(
+            progress,
+            BulkBurnProgress {
+                burned: 1,
+                cursor: None,
+                finished: true,
+            }
+        );
+        assert!(config
+            .burn_cursors
+            .may_load(&storage, "even")
+            .unwrap()
+            .is_none());
+
+        let remaining = config
+            .nft_info
+            .keys(&storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(remaining, vec!["1".to_string(), "3".to_string()]);
+        assert_eq!(config.token_count(&storage).unwrap(), 2);
+
+        // a zero limit still visits a token, so the job keeps moving
+        let progress = config
+            .burn_where(&mut storage, &ctx, "odd", 0, |_, _| false)
+            .unwrap();
+        assert_eq!(progress.cursor, Some("1".to_string()));
+        assert!(!progress.finished);
     }
 }
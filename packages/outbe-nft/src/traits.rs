@@ -1,4 +1,7 @@
+use crate::metadata::NftMetadata;
+use crate::state::{CollectionInfo, NftInfo, TransferPolicy};
 use cosmwasm_std::Empty;
+use cw_storage_plus::IndexList;
 use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -18,13 +21,72 @@ pub trait Cw721CustomMsg: Serialize + Clone + Debug + PartialEq + JsonSchema {}
 impl Cw721CustomMsg for Empty {}
 impl<T> Cw721CustomMsg for Option<T> where T: Cw721CustomMsg {}
 
-pub trait Cw721State: Serialize + DeserializeOwned + Clone + Debug {}
+pub trait Cw721State: Serialize + DeserializeOwned + Clone + Debug {
+    /// ERC721 metadata of the token, see `QueryMsg::NftMetadata`.
+    /// Collections override it to describe their token data as attributes.
+    fn metadata(&self, collection: &CollectionInfo, token_id: &str) -> NftMetadata {
+        NftMetadata::base(collection, token_id)
+    }
+}
 
 impl Cw721State for Empty {}
-impl<T> Cw721State for Option<T> where T: Cw721State {}
+impl<T> Cw721State for Option<T>
+where
+    T: Cw721State,
+{
+    fn metadata(&self, collection: &CollectionInfo, token_id: &str) -> NftMetadata {
+        match self {
+            Some(extension) => extension.metadata(collection, token_id),
+            None => NftMetadata::base(collection, token_id),
+        }
+    }
+}
 
-pub trait Cw721CollectionConfig: Serialize + DeserializeOwned + Clone + Debug {}
+pub trait Cw721CollectionConfig: Serialize + DeserializeOwned + Clone + Debug {
+    /// Defines who can transfer tokens of the collection. Collections are soulbound by default.
+    fn transfer_policy(&self) -> TransferPolicy {
+        TransferPolicy::Soulbound
+    }
+
+    /// Whether mints, state changes, transfers and burns are kept in the token history.
+    fn track_history(&self) -> bool {
+        false
+    }
+
+    /// Whether tokens without a `token_uri` get their metadata as a `data:` uri instead
+    fn render_token_uri(&self) -> bool {
+        false
+    }
+}
 
 impl Cw721CollectionConfig for Empty {}
 
-impl<T> Cw721CollectionConfig for Option<T> where T: Cw721CollectionConfig {}
+impl<T> Cw721CollectionConfig for Option<T>
+where
+    T: Cw721CollectionConfig,
+{
+    fn transfer_policy(&self) -> TransferPolicy {
+        self.as_ref()
+            .map(|config| config.transfer_policy())
+            .unwrap_or_default()
+    }
+
+    fn track_history(&self) -> bool {
+        self.as_ref().is_some_and(|config| config.track_history())
+    }
+
+    fn render_token_uri(&self) -> bool {
+        self.as_ref()
+            .is_some_and(|config| config.render_token_uri())
+    }
+}
+
+/// Secondary indexes over `NftInfo` that a collection maintains on top of the `owner` index.
+/// They are kept in sync by `Cw721Config::nft_info` on every save, update and remove.
+pub trait Cw721ExtensionIndexes<TNftExtension>: IndexList<NftInfo<TNftExtension>>
+where
+    TNftExtension: Cw721State,
+{
+    /// Creates the indexes for the `nft_info` map stored under `pk_namespace`.
+    fn new(pk_namespace: &'static str) -> Self;
+}
@@ -0,0 +1,106 @@
+// Poseidon hash over the scalar field of BLS12-381, same as `tribute-factory/src/poseidon.rs`.
+// Parameters of `poseidonperm_x5_255_3`: S-box x^5, width 3, 8 full and 57 partial rounds,
+// round constants and MDS matrix generated with the Grain LFSR.
+
+export const FIELD_ORDER = 0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001n;
+
+const FIELD_BITS = 255;
+const WIDTH = 3;
+const FULL_ROUNDS = 8;
+const PARTIAL_ROUNDS = 57;
+
+function mod(value: bigint): bigint {
+    const result = value % FIELD_ORDER;
+    return result >= 0n ? result : result + FIELD_ORDER;
+}
+
+function pow(base: bigint, exponent: bigint): bigint {
+    let result = 1n;
+    base = mod(base);
+    while (exponent > 0n) {
+        if (exponent & 1n) {
+            result = mod(result * base);
+        }
+        base = mod(base * base);
+        exponent >>= 1n;
+    }
+    return result;
+}
+
+function toBits(value: number, length: number): number[] {
+    return value.toString(2).padStart(length, '0').split('').map(Number);
+}
+
+function generateParameters(): { roundConstants: bigint[], mds: bigint[][] } {
+    let state = [
+        ...toBits(1, 2), // prime field
+        ...toBits(0, 4), // x^alpha S-box
+        ...toBits(FIELD_BITS, 12),
+        ...toBits(WIDTH, 12),
+        ...toBits(FULL_ROUNDS, 10),
+        ...toBits(PARTIAL_ROUNDS, 10),
+        ...new Array(30).fill(1),
+    ];
+    const nextBit = (): number => {
+        const bit = state[62] ^ state[51] ^ state[38] ^ state[23] ^ state[13] ^ state[0];
+        state = [...state.slice(1), bit];
+        return bit;
+    };
+    for (let i = 0; i < 160; i++) {
+        nextBit();
+    }
+    const nextInt = (): bigint => {
+        let value = 0n;
+        for (let i = 0; i < FIELD_BITS; i++) {
+            // bits are taken in pairs, the second one is kept when the first one is set
+            while (nextBit() === 0) {
+                nextBit();
+            }
+            value = (value << 1n) | BigInt(nextBit());
+        }
+        return value;
+    };
+
+    const roundConstants: bigint[] = [];
+    for (let i = 0; i < (FULL_ROUNDS + PARTIAL_ROUNDS) * WIDTH; i++) {
+        let value = nextInt();
+        while (value >= FIELD_ORDER) {
+            value = nextInt();
+        }
+        roundConstants.push(value);
+    }
+
+    const points = Array.from({length: 2 * WIDTH}, () => mod(nextInt()));
+    const xs = points.slice(0, WIDTH);
+    const ys = points.slice(WIDTH);
+    const mds = xs.map(x => ys.map(y => pow(x + y, FIELD_ORDER - 2n)));
+    return {roundConstants, mds};
+}
+
+const {roundConstants: ROUND_CONSTANTS, mds: MDS} = generateParameters();
+
+export function permute(input: bigint[]): bigint[] {
+    let state = input.map(mod);
+    for (let round = 0; round < FULL_ROUNDS + PARTIAL_ROUNDS; round++) {
+        state = state.map((element, i) => mod(element + ROUND_CONSTANTS[round * WIDTH + i]));
+        const isFullRound = round < FULL_ROUNDS / 2 || round >= FULL_ROUNDS / 2 + PARTIAL_ROUNDS;
+        state = state.map((element, i) => (isFullRound || i === 0) ? pow(element, 5n) : element);
+        state = MDS.map(row => mod(row.reduce((sum, coeff, j) => sum + coeff * state[j], 0n)));
+    }
+    return state;
+}
+
+// Hash of two field elements, the first element of the permutation of [0, left, right]
+export function hash2(left: bigint, right: bigint): bigint {
+    return permute([0n, left, right])[0];
+}
+
+// Big endian bytes reduced modulo the field order
+export function bytesToField(bytes: Uint8Array): bigint {
+    return mod(bytes.reduce((value, byte) => (value << 8n) | BigInt(byte), 0n));
+}
+
+// Big endian 32 bytes encoding of a field element
+export function fieldToBytes(value: bigint): Uint8Array {
+    return Uint8Array.from(value.toString(16).padStart(64, '0').match(/../g)!.map(byte => parseInt(byte, 16)));
+}
@@ -14,6 +14,9 @@ import {encryptTributeInput} from "../lib/encryption";
 import bs58 from "bs58";
 import {TributeFactoryQueryClient} from "../clients/tribute-factory/TributeFactory.client";
 
+// Max number of offers of an OfferBatch of the tribute factory
+const MAX_OFFER_BATCH_SIZE = 100;
+
 async function main() {
   const wallets = await readWalletsFromFile();
   if (wallets.length > 0) {
@@ -48,43 +51,51 @@ async function main() {
 
   let encryptionInfo = await queryEncryptionInfo(walletClient, tbFactoryContractAddress)
 
-  let instructions: ExecuteInstruction[] = [];
+  let offers: JsonObject[] = [];
   for (let i = 0; i < wallets.length; i++) {
     let tribute = randomTribute(wallets[i].outbe_address, RUN_DATE, coenUsdcRate)
+    offers.push(offerTribute(tribute, encryptionInfo))
+  }
 
-    let msg = offerTribute(tribute, encryptionInfo)
-    // let msg = offerInsecureTribute(tribute)
-
+  let instructions: ExecuteInstruction[] = [];
+  for (let i = 0; i < offers.length; i += MAX_OFFER_BATCH_SIZE) {
     instructions.push({
         contractAddress: tbFactoryContractAddress,
-        msg: msg
+        msg: {
+          offer_batch: {
+            offers: offers.slice(i, i + MAX_OFFER_BATCH_SIZE),
+            mode: "best_effort",
+          }
+        }
       }
     )
   }
   let tx = await walletClient.executeMultiple(account.address, instructions, TX_FEE)
-  console.log(instructions.length, "Tributes created, tx ", tx.transactionHash)
+  console.log(offers.length, "Tributes offered, tx ", tx.transactionHash)
 
   console.log("Number of Tribute tokens: ", await tributeClient.numTokens())
 }
 
 function offerTribute(tribute: TributeInputPayload, encryption: EncryptionInfoResponse): JsonObject {
-  const encryptedData = encryptTributeInput(tribute, encryption.public_key, encryption.salt);
+  if (!encryption.current) {
+    throw new Error("Tribute factory has no active TEE key");
+  }
+  const encryptedData = encryptTributeInput(tribute, encryption.current.public_key, encryption.current.salt);
   let owner = new TextDecoder().decode(bs58.decode(tribute.owner));
   return {
-    offer: {
-      cipher_text: encryptedData.cipher_text,
-      nonce: encryptedData.nonce,
-      ephemeral_pubkey: encryptedData.ephemeral_pubkey,
-      zk_proof: {
-        proof: "",
-        public_data: {
-          public_key: "",
-          merkle_root: "",
-        },
-        verification_key: "",
+    cipher_text: encryptedData.cipher_text,
+    nonce: encryptedData.nonce,
+    ephemeral_pubkey: encryptedData.ephemeral_pubkey,
+    key_id: encryption.current.key_id,
+    zk_proof: {
+      proof: "",
+      public_data: {
+        public_key: "",
+        merkle_root: "",
       },
-      tribute_owner_l1: owner,
-    }
+      verification_key: "",
+    },
+    tribute_owner_l1: owner,
   }
 }
 